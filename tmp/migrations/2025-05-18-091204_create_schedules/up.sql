@@ -0,0 +1,26 @@
+-- Your SQL goes here
+CREATE TABLE schedules
+(
+    id          BIGINT PRIMARY KEY               NOT NULL DEFAULT snowflake.nextval(),
+    created_at  TIMESTAMP                        NOT NULL DEFAULT NOW(),
+    created_by  TEXT                             NOT NULL,
+    updated_at  TIMESTAMP                        NOT NULL DEFAULT NOW(),
+    updated_by  TEXT                             NOT NULL,
+    task        VARCHAR(255)                     NOT NULL,
+    library_id  BIGINT REFERENCES libraries (id),
+    cron        TEXT                             NOT NULL,
+    enabled     BOOLEAN                          NOT NULL DEFAULT TRUE,
+    last_run_at TIMESTAMP,
+    next_run_at TIMESTAMP
+);
+
+INSERT INTO schedules (created_by, updated_by, task, library_id, cron)
+SELECT 'SYSTEM', 'SYSTEM', 'scan_library', id, '0 0 3 * * *'
+FROM libraries;
+
+INSERT INTO schedules (created_by, updated_by, task, library_id, cron)
+SELECT 'SYSTEM', 'SYSTEM', 'refresh_artwork', id, '0 0 4 * * Sun'
+FROM libraries;
+
+INSERT INTO schedules (created_by, updated_by, task, cron)
+VALUES ('SYSTEM', 'SYSTEM', 'clean_cache', '0 0 5 * * *');
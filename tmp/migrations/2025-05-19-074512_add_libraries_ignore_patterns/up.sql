@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE libraries
+    ADD COLUMN ignore_patterns TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE media
+    DROP COLUMN provider_ids,
+    DROP COLUMN credits,
+    DROP COLUMN tags,
+    DROP COLUMN collection;
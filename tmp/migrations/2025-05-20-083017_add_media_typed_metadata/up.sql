@@ -0,0 +1,12 @@
+-- Your SQL goes here
+ALTER TABLE media
+    ADD COLUMN provider_ids JSONB  NOT NULL DEFAULT '{}',
+    ADD COLUMN credits      JSONB  NOT NULL DEFAULT '[]',
+    ADD COLUMN tags         TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN collection   JSONB;
+
+-- Like scans, a bare <id> is only known to be a provider id when it is an IMDb one
+UPDATE media
+SET provider_ids = jsonb_build_object('imdb', attributes ->> 'nfoId')
+WHERE attributes ->> 'nfoId' LIKE 'tt%'
+  AND parent_id IS NULL;
@@ -0,0 +1,13 @@
+-- Your SQL goes here
+CREATE TABLE extras
+(
+    id         BIGINT PRIMARY KEY NOT NULL DEFAULT snowflake.nextval(),
+    created_at TIMESTAMP          NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP          NOT NULL DEFAULT NOW(),
+    media_id   BIGINT             NOT NULL REFERENCES media (id) ON DELETE CASCADE,
+    kind       VARCHAR(255)       NOT NULL,
+    title      TEXT               NOT NULL,
+    path       TEXT               NOT NULL UNIQUE
+);
+
+CREATE INDEX extras_media_id_idx ON extras (media_id);
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE media
+    ADD COLUMN metadata_status VARCHAR(255) NOT NULL DEFAULT 'complete';
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE libraries
+    DROP COLUMN metadata_provider,
+    DROP COLUMN episode_ordering;
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+ALTER TABLE libraries
+    ADD COLUMN metadata_provider VARCHAR(255) NOT NULL DEFAULT 'tmdb',
+    ADD COLUMN episode_ordering  VARCHAR(255) NOT NULL DEFAULT 'aired';
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE libraries
+    ADD COLUMN preferred_languages TEXT[] NOT NULL DEFAULT '{en}';
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DELETE
+FROM schedules
+WHERE task = 'generate_blur_hashes';
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+INSERT INTO schedules (created_by, updated_by, task, cron)
+VALUES ('SYSTEM', 'SYSTEM', 'generate_blur_hashes', '0 30 5 * * *');
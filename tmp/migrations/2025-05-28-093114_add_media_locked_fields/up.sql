@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE media
+    ADD COLUMN locked_fields TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,10 @@
+-- This file should undo anything in `up.sql`
+DELETE
+FROM schedules
+WHERE task = 'refresh_metadata';
+
+ALTER TABLE libraries
+    DROP COLUMN metadata_refresh_days;
+
+ALTER TABLE media
+    DROP COLUMN metadata_refreshed_at;
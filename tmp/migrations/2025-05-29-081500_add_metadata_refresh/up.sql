@@ -0,0 +1,10 @@
+-- Your SQL goes here
+ALTER TABLE media
+    ADD COLUMN metadata_refreshed_at TIMESTAMP;
+
+ALTER TABLE libraries
+    ADD COLUMN metadata_refresh_days INTEGER NOT NULL DEFAULT 30;
+
+INSERT INTO schedules (created_by, updated_by, task, library_id, cron)
+SELECT 'SYSTEM', 'SYSTEM', 'refresh_metadata', id, '0 0 5 * * *'
+FROM libraries;
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE collection_items;
+
+DELETE
+FROM media
+WHERE type = 'collection';
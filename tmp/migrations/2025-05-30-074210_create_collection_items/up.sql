@@ -0,0 +1,13 @@
+-- Your SQL goes here
+CREATE TABLE collection_items
+(
+    id            BIGINT PRIMARY KEY NOT NULL DEFAULT snowflake.nextval(),
+    created_at    TIMESTAMP          NOT NULL DEFAULT NOW(),
+    updated_at    TIMESTAMP          NOT NULL DEFAULT NOW(),
+    collection_id BIGINT             NOT NULL REFERENCES media (id) ON DELETE CASCADE,
+    media_id      BIGINT             NOT NULL REFERENCES media (id) ON DELETE CASCADE,
+    set_name      TEXT,
+    UNIQUE (collection_id, media_id)
+);
+
+CREATE INDEX collection_items_media_id_idx ON collection_items (media_id);
@@ -0,0 +1,34 @@
+-- Your SQL goes here
+CREATE TABLE people
+(
+    id                    BIGINT PRIMARY KEY NOT NULL DEFAULT snowflake.nextval(),
+    created_at            TIMESTAMP          NOT NULL DEFAULT NOW(),
+    updated_at            TIMESTAMP          NOT NULL DEFAULT NOW(),
+    name                  TEXT               NOT NULL,
+    provider_ids          JSONB              NOT NULL DEFAULT '{}',
+    biography             TEXT,
+    birthday              DATE,
+    deathday              DATE,
+    place_of_birth        TEXT,
+    thumb                 TEXT,
+    headshot              JSONB,
+    metadata_refreshed_at TIMESTAMP
+);
+
+CREATE INDEX people_name_idx ON people (name);
+CREATE INDEX people_provider_ids_idx ON people USING GIN (provider_ids);
+
+CREATE TABLE media_people
+(
+    id         BIGINT PRIMARY KEY NOT NULL DEFAULT snowflake.nextval(),
+    created_at TIMESTAMP          NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP          NOT NULL DEFAULT NOW(),
+    media_id   BIGINT             NOT NULL REFERENCES media (id) ON DELETE CASCADE,
+    person_id  BIGINT             NOT NULL REFERENCES people (id) ON DELETE CASCADE,
+    role       TEXT               NOT NULL,
+    character  TEXT,
+    sort_order INTEGER
+);
+
+CREATE INDEX media_people_media_id_idx ON media_people (media_id);
+CREATE INDEX media_people_person_id_idx ON media_people (person_id);
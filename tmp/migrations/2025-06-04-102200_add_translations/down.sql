@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE users
+    DROP COLUMN metadata_languages;
+
+ALTER TABLE media
+    DROP COLUMN translations;
@@ -0,0 +1,6 @@
+-- Your SQL goes here
+ALTER TABLE media
+    ADD COLUMN translations JSONB NOT NULL DEFAULT '{}';
+
+ALTER TABLE users
+    ADD COLUMN metadata_languages TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,16 @@
+-- Your SQL goes here
+CREATE TABLE provider_episodes
+(
+    id           BIGINT PRIMARY KEY NOT NULL DEFAULT snowflake.nextval(),
+    created_at   TIMESTAMP          NOT NULL DEFAULT NOW(),
+    updated_at   TIMESTAMP          NOT NULL DEFAULT NOW(),
+    media_id     BIGINT             NOT NULL REFERENCES media (id) ON DELETE CASCADE,
+    season       INTEGER            NOT NULL,
+    episode      INTEGER            NOT NULL,
+    title        TEXT,
+    plot         TEXT,
+    aired        DATE,
+    provider_ids JSONB              NOT NULL DEFAULT '{}'
+);
+
+CREATE INDEX provider_episodes_media_id_idx ON provider_episodes (media_id);
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DELETE FROM schedules
+WHERE task = 'refresh_episodes';
+
+ALTER TABLE users
+    DROP COLUMN calendar_token;
@@ -0,0 +1,7 @@
+-- Your SQL goes here
+ALTER TABLE users
+    ADD COLUMN calendar_token TEXT UNIQUE;
+
+INSERT INTO schedules (created_by, updated_by, task, library_id, cron)
+SELECT 'SYSTEM', 'SYSTEM', 'refresh_episodes', id, '0 30 4 * * *'
+FROM libraries;
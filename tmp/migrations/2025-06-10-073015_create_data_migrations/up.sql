@@ -0,0 +1,12 @@
+-- Your SQL goes here
+CREATE TABLE data_migrations
+(
+    id           BIGINT PRIMARY KEY NOT NULL DEFAULT snowflake.nextval(),
+    created_at   TIMESTAMP          NOT NULL DEFAULT NOW(),
+    updated_at   TIMESTAMP          NOT NULL DEFAULT NOW(),
+    name         TEXT UNIQUE        NOT NULL,
+    completed_at TIMESTAMP
+);
+
+INSERT INTO data_migrations (name)
+VALUES ('migrate_artwork');
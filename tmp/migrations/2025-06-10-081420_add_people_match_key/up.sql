@@ -0,0 +1,16 @@
+-- Your SQL goes here
+ALTER TABLE people
+    ADD COLUMN match_key TEXT UNIQUE;
+
+-- People created twice by concurrent scans leave the key to the first of them
+UPDATE people
+SET match_key = keys.match_key
+FROM (SELECT id, match_key, ROW_NUMBER() OVER (PARTITION BY match_key ORDER BY id) AS number
+      FROM (SELECT id,
+                   COALESCE('tmdb:' || (provider_ids ->> 'tmdb'),
+                            'tvdb:' || (provider_ids ->> 'tvdb'),
+                            'imdb:' || (provider_ids ->> 'imdb')) AS match_key
+            FROM people) candidates
+      WHERE match_key IS NOT NULL) keys
+WHERE people.id = keys.id
+  AND keys.number = 1;
@@ -0,0 +1,169 @@
+use crate::models::Media;
+use crate::repositories;
+use crate::views::{CalendarEpisodeView, MediaView};
+use chrono::{Days, NaiveDate, NaiveDateTime};
+use diesel::QueryResult;
+use diesel_async::AsyncPgConnection;
+use std::collections::HashMap;
+
+/// RFC 5545 3.1
+const MAX_LINE_LENGTH: usize = 75;
+
+pub async fn upcoming(
+    connection: &mut AsyncPgConnection,
+    from: NaiveDate,
+    to: NaiveDate,
+    languages: &[String],
+) -> QueryResult<Vec<CalendarEpisodeView>> {
+    let episodes =
+        repositories::provider_episode::find_airing_between(connection, from, to).await?;
+
+    let show_titles: HashMap<i64, String> = repositories::media::find_by_ids(
+        connection,
+        episodes.iter().map(|episode| episode.media_id).collect(),
+    )
+    .await?
+    .into_iter()
+    .filter(Media::is_continuing)
+    .map(|show| (show.id, MediaView::localized(show, languages).title))
+    .collect();
+
+    Ok(episodes
+        .into_iter()
+        .filter_map(|episode| {
+            let show_title = show_titles.get(&episode.media_id)?.clone();
+            Some(CalendarEpisodeView::from((episode, show_title)))
+        })
+        .collect())
+}
+
+/// Event ids are made of the show, season and episode so calendar apps update events when the air
+/// date moves.
+pub fn ical(episodes: &[CalendarEpisodeView], stamp: NaiveDateTime) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Selfless//Upcoming Episodes//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:Upcoming Episodes".to_string(),
+    ];
+
+    for episode in episodes {
+        let Some(aired) = episode.aired else {
+            continue;
+        };
+
+        let mut summary = format!(
+            "{} S{:02}E{:02}",
+            episode.show_title, episode.season, episode.episode
+        );
+        if let Some(title) = &episode.title {
+            summary.push_str(&format!(" - {title}"));
+        }
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!(
+            "UID:{}-{}-{}@selfless",
+            episode.show_id, episode.season, episode.episode
+        ));
+        lines.push(format!("DTSTAMP:{}", stamp.format("%Y%m%dT%H%M%SZ")));
+        lines.push(format!("DTSTART;VALUE=DATE:{}", aired.format("%Y%m%d")));
+        if let Some(end) = aired.checked_add_days(Days::new(1)) {
+            lines.push(format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")));
+        }
+        lines.push(format!("SUMMARY:{}", escape(&summary)));
+        if let Some(plot) = &episode.plot {
+            lines.push(format!("DESCRIPTION:{}", escape(plot)));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    lines
+        .iter()
+        .map(|line| fold(line))
+        .map(|line| line + "\r\n")
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_escapes_special_characters() {
+        assert_eq!(escape(r"a\b"), r"a\\b");
+        assert_eq!(escape("a;b,c"), r"a\;b\,c");
+        assert_eq!(escape("a\r\nb\nc"), r"a\nb\nc");
+        assert_eq!(escape("Plain text: ok"), "Plain text: ok");
+    }
+
+    #[test]
+    fn fold_keeps_short_lines() {
+        let line = "a".repeat(MAX_LINE_LENGTH);
+        assert_eq!(fold(&line), line);
+    }
+
+    #[test]
+    fn fold_splits_long_lines() {
+        let line = "a".repeat(MAX_LINE_LENGTH + 1);
+        assert_eq!(
+            fold(&line),
+            format!("{}\r\n a", "a".repeat(MAX_LINE_LENGTH))
+        );
+    }
+
+    #[test]
+    fn fold_continuation_lines_count_the_leading_space() {
+        let line = "a".repeat(MAX_LINE_LENGTH * 2);
+        let folded = fold(&line);
+        let lines: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(|line| line.len() <= MAX_LINE_LENGTH));
+        assert_eq!(lines.concat().replace(' ', ""), line);
+    }
+
+    #[test]
+    fn fold_does_not_split_multi_byte_characters() {
+        // 74 bytes, then a 2 byte character that would end at byte 76
+        let line = format!("{}é", "a".repeat(MAX_LINE_LENGTH - 1));
+        assert_eq!(
+            fold(&line),
+            format!("{}\r\n é", "a".repeat(MAX_LINE_LENGTH - 1))
+        );
+
+        // 73 bytes, then a 2 byte character that ends exactly at byte 75
+        let line = format!("{}é", "a".repeat(MAX_LINE_LENGTH - 2));
+        assert_eq!(fold(&line), line);
+
+        let line = "日本語".repeat(20);
+        let folded = fold(&line);
+        for line in folded.split("\r\n") {
+            assert!(line.len() <= MAX_LINE_LENGTH);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
@@ -1,8 +1,11 @@
+use crate::clients::http::HttpClient;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
 
-const FANART_BASE_URL: &str = "https://webservice.fanart.tv/v3";
-const FANART_API_KEY: &str = "8a541d9b51adeedb584a32eed079c432";
-const FANART_CLIENT_KEY: &str = "4a041ef55797cc4af9f3023270b45dda";
+pub const FANART_BASE_URL: &str = "https://webservice.fanart.tv/v3";
+const CACHE_TTL: Duration = Duration::from_secs(60 * 60 * 24 * 7);
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FanartMovieResponse {
@@ -71,62 +74,69 @@ pub struct FanartImage {
     pub season: Option<String>,
 }
 
+impl FanartImage {
+    /// `likes` is sent as a string, compare it as a number so "10" ranks above "9".
+    pub fn like_count(&self) -> u32 {
+        self.likes.parse().unwrap_or(0)
+    }
+}
+
 pub struct FanartService {
-    client: reqwest::Client,
+    http: Arc<HttpClient>,
+    base_url: String,
+    api_key: String,
+    /// A personal key gets newer images than the project key alone.
+    client_key: Option<String>,
 }
 
 impl FanartService {
-    pub fn new() -> Self {
+    pub fn new(
+        http: Arc<HttpClient>,
+        base_url: String,
+        api_key: String,
+        client_key: Option<String>,
+    ) -> Self {
+        http.rate_limit(&base_url, Duration::from_millis(200));
+
         Self {
-            client: reqwest::Client::new(),
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            client_key,
         }
     }
 
-    pub async fn fetch_movie_art(&self, id: String) -> anyhow::Result<FanartMovieResponse> {
-        let response = self
-            .client
-            .get(format!(
-                "{FANART_BASE_URL}/movies/{id}?api_key={FANART_API_KEY}&client_key={FANART_CLIENT_KEY}"
-            ))
-            .send()
-            .await?;
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> anyhow::Result<T> {
+        let mut request = self
+            .http
+            .get(&format!("{}{}", self.base_url, path))
+            .query(&[("api_key", self.api_key.as_str())]);
+        if let Some(client_key) = &self.client_key {
+            request = request.query(&[("client_key", client_key.as_str())]);
+        }
+
+        let response = self.http.fetch(request, CACHE_TTL).await?;
 
-        if !response.status().is_success() {
+        if !response.is_success() {
             return Err(anyhow::Error::msg(format!(
-                "Failed to fetch movie artwork: {}",
-                response.text().await?
+                "Failed to fetch {path} from fanart.tv: {}",
+                response.body
             )));
         }
 
-        let body = response.text().await?;
+        let body = response.body;
         serde_json::from_str(&body).map_err(|e| {
             anyhow::Error::msg(format!(
-                "Failed to parse movie artwork: {e}, for body: {body}"
+                "Failed to parse {path} from fanart.tv: {e}, for body: {body}"
             ))
         })
     }
 
-    pub async fn fetch_tv_art(&self, id: String) -> anyhow::Result<FanartTVResponse> {
-        let response = self
-            .client
-            .get(format!(
-                "{FANART_BASE_URL}/tv/{id}?api_key={FANART_API_KEY}&client_key={FANART_CLIENT_KEY}"
-            ))
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(anyhow::Error::msg(format!(
-                "Failed to fetch movie artwork: {}",
-                response.text().await?
-            )));
-        }
+    pub async fn fetch_movie_art(&self, id: String) -> anyhow::Result<FanartMovieResponse> {
+        self.get(&format!("/movies/{id}")).await
+    }
 
-        let body = response.text().await?;
-        serde_json::from_str(&body).map_err(|e| {
-            anyhow::Error::msg(format!(
-                "Failed to parse movie artwork: {e}, for body: {body}"
-            ))
-        })
+    pub async fn fetch_tv_art(&self, id: String) -> anyhow::Result<FanartTVResponse> {
+        self.get(&format!("/tv/{id}")).await
     }
 }
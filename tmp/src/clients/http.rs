@@ -0,0 +1,350 @@
+use axum::body::Bytes;
+use reqwest::header::{
+    HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER,
+};
+use reqwest::{Request, RequestBuilder, Response, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tokio::fs;
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+const MAX_ATTEMPTS: u32 = 4;
+const BASE_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+const DEFAULT_INTERVAL: Duration = Duration::from_millis(50);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        self.status.is_success()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedResponse {
+    fetched_at: SystemTime,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body: String,
+}
+
+struct HostLimit {
+    interval: Duration,
+    next: Instant,
+}
+
+pub struct HttpClient {
+    client: reqwest::Client,
+    cache_dir: PathBuf,
+    hosts: Mutex<HashMap<String, HostLimit>>,
+}
+
+impl HttpClient {
+    pub fn new(cache_dir: PathBuf) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .connect_timeout(CONNECT_TIMEOUT)
+                .timeout(TIMEOUT)
+                .build()
+                .unwrap(),
+            cache_dir,
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, url: &str) -> RequestBuilder {
+        self.client.get(url)
+    }
+
+    pub fn post(&self, url: &str) -> RequestBuilder {
+        self.client.post(url)
+    }
+
+    pub fn rate_limit(&self, base_url: &str, interval: Duration) {
+        let Some(host) = Url::parse(base_url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+        else {
+            warn!("Cannot rate limit {}, it is not a valid URL", base_url);
+            return;
+        };
+
+        self.hosts.lock().unwrap().insert(
+            host,
+            HostLimit {
+                interval,
+                next: Instant::now(),
+            },
+        );
+    }
+
+    async fn wait_for_slot(&self, host: &str) {
+        let slot = {
+            let mut hosts = self.hosts.lock().unwrap();
+            let limit = hosts.entry(host.to_string()).or_insert(HostLimit {
+                interval: DEFAULT_INTERVAL,
+                next: Instant::now(),
+            });
+
+            let slot = limit.next.max(Instant::now());
+            limit.next = slot + limit.interval;
+            slot
+        };
+
+        tokio::time::sleep_until(slot).await;
+    }
+
+    fn pause_host(&self, host: &str, delay: Duration) {
+        if let Some(limit) = self.hosts.lock().unwrap().get_mut(host) {
+            limit.next = limit.next.max(Instant::now() + delay);
+        }
+    }
+
+    pub async fn execute(&self, request: RequestBuilder) -> anyhow::Result<Response> {
+        self.execute_request(request.build()?).await
+    }
+
+    async fn execute_request(&self, request: Request) -> anyhow::Result<Response> {
+        let host = request.url().host_str().unwrap_or_default().to_string();
+
+        let mut attempt = 0;
+        loop {
+            let attempt_request = request
+                .try_clone()
+                .ok_or(anyhow::Error::msg("Request body cannot be retried"))?;
+
+            self.wait_for_slot(&host).await;
+            attempt += 1;
+
+            let delay = match self.client.execute(attempt_request).await {
+                Ok(response) if !is_retryable(response.status()) || attempt >= MAX_ATTEMPTS => {
+                    return Ok(response);
+                }
+                Ok(response) => {
+                    let delay = retry_after(response.headers()).unwrap_or(backoff(attempt));
+                    if response.status() == StatusCode::TOO_MANY_REQUESTS {
+                        self.pause_host(&host, delay);
+                    }
+
+                    warn!(
+                        "{} answered {}, retrying in {:?}",
+                        host,
+                        response.status(),
+                        delay
+                    );
+                    delay
+                }
+                Err(e) if attempt < MAX_ATTEMPTS && (e.is_connect() || e.is_timeout()) => {
+                    let delay = backoff(attempt);
+                    warn!("Request to {} failed: {}, retrying in {:?}", host, e, delay);
+                    delay
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Serves the cached response while it is younger than `ttl`, revalidating it after.
+    pub async fn fetch(
+        &self,
+        request: RequestBuilder,
+        ttl: Duration,
+    ) -> anyhow::Result<HttpResponse> {
+        let mut request = request.build()?;
+        let path = self.cache_path(&request);
+
+        let cached = match fs::read(&path).await {
+            Ok(bytes) => serde_json::from_slice::<CachedResponse>(&bytes).ok(),
+            Err(_) => None,
+        };
+
+        if let Some(cached) = &cached {
+            let age = cached.fetched_at.elapsed().unwrap_or(Duration::MAX);
+            if age < ttl {
+                debug!("Serving {} from cache", request.url().path());
+                return Ok(HttpResponse {
+                    status: StatusCode::OK,
+                    body: cached.body.clone(),
+                });
+            }
+
+            if let Some(etag) = cached.etag.as_deref().and_then(|v| v.parse().ok()) {
+                request.headers_mut().insert(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) =
+                cached.last_modified.as_deref().and_then(|v| v.parse().ok())
+            {
+                request
+                    .headers_mut()
+                    .insert(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = self.execute_request(request).await?;
+        let status = response.status();
+
+        if status == StatusCode::NOT_MODIFIED
+            && let Some(mut cached) = cached
+        {
+            cached.fetched_at = SystemTime::now();
+            self.store(&path, &cached).await;
+            return Ok(HttpResponse {
+                status: StatusCode::OK,
+                body: cached.body,
+            });
+        }
+
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value: &HeaderValue| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        let body = response.text().await?;
+
+        if status.is_success() {
+            self.store(
+                &path,
+                &CachedResponse {
+                    fetched_at: SystemTime::now(),
+                    etag,
+                    last_modified,
+                    body: body.clone(),
+                },
+            )
+            .await;
+        }
+
+        Ok(HttpResponse { status, body })
+    }
+
+    pub async fn get_bytes(&self, url: &str) -> anyhow::Result<Bytes> {
+        Ok(self
+            .execute(self.get(url))
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?)
+    }
+
+    /// Headers such as auth tokens are left out of the key.
+    fn cache_path(&self, request: &Request) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(request.method().as_str());
+        hasher.update(b" ");
+        hasher.update(request.url().as_str());
+        let hash: String = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        self.cache_dir.join(format!("{hash}.json"))
+    }
+
+    async fn store(&self, path: &Path, cached: &CachedResponse) {
+        let result = async {
+            fs::create_dir_all(&self.cache_dir).await?;
+            fs::write(path, serde_json::to_vec(cached)?).await?;
+            Ok::<_, anyhow::Error>(())
+        }
+        .await;
+
+        if let Err(e) = result {
+            warn!("Failed to cache response in {:?}: {}", path, e);
+        }
+    }
+
+    pub async fn clean_cache(&self, max_age: Duration) -> anyhow::Result<()> {
+        if !self.cache_dir.is_dir() {
+            return Ok(());
+        }
+
+        let mut dir = fs::read_dir(&self.cache_dir).await?;
+        while let Some(entry) = dir.next_entry().await? {
+            let modified = entry.metadata().await?.modified()?;
+            if modified.elapsed().unwrap_or_default() > max_age {
+                fs::remove_file(entry.path()).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn backoff(attempt: u32) -> Duration {
+    (BASE_BACKOFF * 2u32.pow(attempt - 1)).min(MAX_BACKOFF)
+}
+
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    let delay = match value.parse::<u64>() {
+        Ok(seconds) => Duration::from_secs(seconds),
+        Err(_) => chrono::DateTime::parse_from_rfc2822(value)
+            .ok()?
+            .signed_duration_since(chrono::Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    };
+
+    Some(delay.min(MAX_BACKOFF))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(retry_after: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(retry_after).unwrap());
+        headers
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        assert_eq!(backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff(2), Duration::from_secs(2));
+        assert_eq!(backoff(4), Duration::from_secs(8));
+        assert_eq!(backoff(10), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn retry_after_reads_seconds() {
+        assert_eq!(retry_after(&headers("5")), Some(Duration::from_secs(5)));
+        assert_eq!(retry_after(&headers("3600")), Some(MAX_BACKOFF));
+        assert_eq!(retry_after(&HeaderMap::new()), None);
+        assert_eq!(retry_after(&headers("soon")), None);
+    }
+
+    #[test]
+    fn retry_after_reads_http_dates() {
+        let in_ten_seconds = (chrono::Utc::now() + chrono::Duration::seconds(10)).to_rfc2822();
+        let delay = retry_after(&headers(&in_ten_seconds)).unwrap();
+        assert!(delay > Duration::from_secs(8) && delay <= Duration::from_secs(10));
+
+        assert_eq!(
+            retry_after(&headers("Wed, 21 Oct 2015 07:28:00 GMT")),
+            Some(Duration::ZERO)
+        );
+    }
+}
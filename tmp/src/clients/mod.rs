@@ -1 +1,4 @@
 pub mod fanart;
+pub mod http;
+pub mod tmdb;
+pub mod tvdb;
@@ -0,0 +1,386 @@
+use crate::clients::http::HttpClient;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub const TMDB_BASE_URL: &str = "https://api.themoviedb.org/3";
+pub const TMDB_IMAGE_BASE_URL: &str = "https://image.tmdb.org/t/p/original";
+const CACHE_TTL: Duration = Duration::from_secs(60 * 60 * 24);
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TmdbPage<T> {
+    pub page: i32,
+    pub results: Vec<T>,
+    pub total_results: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TmdbSearchResult {
+    pub id: i64,
+    pub title: Option<String>,
+    pub original_title: Option<String>,
+    pub release_date: Option<String>,
+    pub name: Option<String>,
+    pub original_name: Option<String>,
+    pub first_air_date: Option<String>,
+    pub overview: Option<String>,
+    pub poster_path: Option<String>,
+    #[serde(default)]
+    pub popularity: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TmdbGenre {
+    pub id: i64,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TmdbCompany {
+    pub id: i64,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TmdbCountry {
+    pub iso_3166_1: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TmdbCollection {
+    pub id: i64,
+    pub name: String,
+    /// Only set when the collection itself is fetched.
+    pub overview: Option<String>,
+    pub poster_path: Option<String>,
+    pub backdrop_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TmdbExternalIds {
+    pub imdb_id: Option<String>,
+    pub tvdb_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TmdbCast {
+    pub id: i64,
+    pub name: String,
+    pub character: Option<String>,
+    pub order: Option<i32>,
+    pub profile_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TmdbCrew {
+    pub id: i64,
+    pub name: String,
+    pub job: String,
+    pub department: String,
+    pub profile_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TmdbCredits {
+    #[serde(default)]
+    pub cast: Vec<TmdbCast>,
+    #[serde(default)]
+    pub crew: Vec<TmdbCrew>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TmdbMovie {
+    pub id: i64,
+    pub title: String,
+    pub original_title: Option<String>,
+    pub overview: Option<String>,
+    pub tagline: Option<String>,
+    pub release_date: Option<String>,
+    pub runtime: Option<i32>,
+    pub vote_average: Option<f64>,
+    pub vote_count: Option<i32>,
+    #[serde(default)]
+    pub genres: Vec<TmdbGenre>,
+    #[serde(default)]
+    pub production_companies: Vec<TmdbCompany>,
+    #[serde(default)]
+    pub production_countries: Vec<TmdbCountry>,
+    pub belongs_to_collection: Option<TmdbCollection>,
+    pub imdb_id: Option<String>,
+    #[serde(default)]
+    pub credits: TmdbCredits,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TmdbSeasonSummary {
+    pub id: i64,
+    pub season_number: i32,
+    pub name: Option<String>,
+    pub overview: Option<String>,
+    pub air_date: Option<String>,
+    pub episode_count: Option<i32>,
+    pub poster_path: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TmdbSeries {
+    pub id: i64,
+    pub name: String,
+    pub original_name: Option<String>,
+    pub overview: Option<String>,
+    pub tagline: Option<String>,
+    pub first_air_date: Option<String>,
+    pub last_air_date: Option<String>,
+    pub status: Option<String>,
+    #[serde(default)]
+    pub episode_run_time: Vec<i32>,
+    pub vote_average: Option<f64>,
+    pub vote_count: Option<i32>,
+    #[serde(default)]
+    pub genres: Vec<TmdbGenre>,
+    #[serde(default)]
+    pub networks: Vec<TmdbCompany>,
+    #[serde(default)]
+    pub production_countries: Vec<TmdbCountry>,
+    #[serde(default)]
+    pub seasons: Vec<TmdbSeasonSummary>,
+    #[serde(default)]
+    pub external_ids: TmdbExternalIds,
+    #[serde(default)]
+    pub credits: TmdbCredits,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TmdbEpisode {
+    pub id: i64,
+    pub season_number: i32,
+    pub episode_number: i32,
+    pub name: Option<String>,
+    pub overview: Option<String>,
+    pub air_date: Option<String>,
+    pub runtime: Option<i32>,
+    pub vote_average: Option<f64>,
+    pub vote_count: Option<i32>,
+    pub still_path: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TmdbSeason {
+    pub id: i64,
+    pub season_number: i32,
+    pub name: Option<String>,
+    pub overview: Option<String>,
+    pub air_date: Option<String>,
+    pub poster_path: Option<String>,
+    #[serde(default)]
+    pub episodes: Vec<TmdbEpisode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TmdbImage {
+    pub file_path: String,
+    pub iso_639_1: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub vote_average: f64,
+    #[serde(default)]
+    pub vote_count: i32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TmdbImages {
+    #[serde(default)]
+    pub posters: Vec<TmdbImage>,
+    #[serde(default)]
+    pub backdrops: Vec<TmdbImage>,
+    #[serde(default)]
+    pub logos: Vec<TmdbImage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TmdbPerson {
+    pub id: i64,
+    pub name: String,
+    pub biography: Option<String>,
+    pub birthday: Option<String>,
+    pub deathday: Option<String>,
+    pub place_of_birth: Option<String>,
+    pub profile_path: Option<String>,
+    pub imdb_id: Option<String>,
+}
+
+/// Movies have a `title`, series and collections a `name`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TmdbTranslationData {
+    pub title: Option<String>,
+    pub name: Option<String>,
+    pub overview: Option<String>,
+    pub tagline: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TmdbTranslation {
+    pub iso_639_1: String,
+    #[serde(default)]
+    pub data: TmdbTranslationData,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TmdbTranslations {
+    #[serde(default)]
+    pub translations: Vec<TmdbTranslation>,
+}
+
+pub struct TmdbService {
+    http: Arc<HttpClient>,
+    base_url: String,
+    image_base_url: String,
+    api_key: String,
+}
+
+impl TmdbService {
+    pub fn new(
+        http: Arc<HttpClient>,
+        base_url: String,
+        image_base_url: String,
+        api_key: String,
+    ) -> Self {
+        // TMDB allows around 50 requests per second
+        http.rate_limit(&base_url, Duration::from_millis(25));
+
+        Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            image_base_url: image_base_url.trim_end_matches('/').to_string(),
+            api_key,
+        }
+    }
+
+    pub fn image_url(&self, path: &str) -> String {
+        format!("{}{}", self.image_base_url, path)
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> anyhow::Result<T> {
+        let response = self
+            .http
+            .fetch(
+                self.http
+                    .get(&format!("{}{}", self.base_url, path))
+                    .query(&[("api_key", self.api_key.as_str())])
+                    .query(query),
+                CACHE_TTL,
+            )
+            .await?;
+
+        if !response.is_success() {
+            return Err(anyhow::Error::msg(format!(
+                "Failed to fetch {path} from TMDB: {}",
+                response.body
+            )));
+        }
+
+        let body = response.body;
+        serde_json::from_str(&body).map_err(|e| {
+            anyhow::Error::msg(format!(
+                "Failed to parse {path} from TMDB: {e}, for body: {body}"
+            ))
+        })
+    }
+
+    pub async fn search_movie(
+        &self,
+        query: &str,
+        year: Option<i32>,
+    ) -> anyhow::Result<TmdbPage<TmdbSearchResult>> {
+        let mut parameters = vec![("query", query.to_string())];
+        if let Some(year) = year {
+            parameters.push(("year", year.to_string()));
+        }
+
+        self.get("/search/movie", &parameters).await
+    }
+
+    pub async fn search_tv(
+        &self,
+        query: &str,
+        year: Option<i32>,
+    ) -> anyhow::Result<TmdbPage<TmdbSearchResult>> {
+        let mut parameters = vec![("query", query.to_string())];
+        if let Some(year) = year {
+            parameters.push(("first_air_date_year", year.to_string()));
+        }
+
+        self.get("/search/tv", &parameters).await
+    }
+
+    pub async fn search_collection(
+        &self,
+        query: &str,
+    ) -> anyhow::Result<TmdbPage<TmdbSearchResult>> {
+        self.get("/search/collection", &[("query", query.to_string())])
+            .await
+    }
+
+    pub async fn fetch_movie(&self, id: &str) -> anyhow::Result<TmdbMovie> {
+        self.get(
+            &format!("/movie/{id}"),
+            &[("append_to_response", "credits".to_string())],
+        )
+        .await
+    }
+
+    pub async fn fetch_tv(&self, id: &str) -> anyhow::Result<TmdbSeries> {
+        self.get(
+            &format!("/tv/{id}"),
+            &[("append_to_response", "credits,external_ids".to_string())],
+        )
+        .await
+    }
+
+    pub async fn fetch_tv_season(&self, id: &str, season: i32) -> anyhow::Result<TmdbSeason> {
+        self.get(&format!("/tv/{id}/season/{season}"), &[]).await
+    }
+
+    pub async fn fetch_collection(&self, id: &str) -> anyhow::Result<TmdbCollection> {
+        self.get(&format!("/collection/{id}"), &[]).await
+    }
+
+    pub async fn fetch_movie_images(&self, id: &str) -> anyhow::Result<TmdbImages> {
+        self.get(&format!("/movie/{id}/images"), &[]).await
+    }
+
+    pub async fn fetch_tv_images(&self, id: &str) -> anyhow::Result<TmdbImages> {
+        self.get(&format!("/tv/{id}/images"), &[]).await
+    }
+
+    pub async fn fetch_collection_images(&self, id: &str) -> anyhow::Result<TmdbImages> {
+        self.get(&format!("/collection/{id}/images"), &[]).await
+    }
+
+    pub async fn fetch_person(&self, id: &str) -> anyhow::Result<TmdbPerson> {
+        self.get(&format!("/person/{id}"), &[]).await
+    }
+
+    pub async fn fetch_movie_translations(&self, id: &str) -> anyhow::Result<TmdbTranslations> {
+        self.get(&format!("/movie/{id}/translations"), &[]).await
+    }
+
+    pub async fn fetch_tv_translations(&self, id: &str) -> anyhow::Result<TmdbTranslations> {
+        self.get(&format!("/tv/{id}/translations"), &[]).await
+    }
+
+    pub async fn fetch_collection_translations(
+        &self,
+        id: &str,
+    ) -> anyhow::Result<TmdbTranslations> {
+        self.get(&format!("/collection/{id}/translations"), &[])
+            .await
+    }
+}
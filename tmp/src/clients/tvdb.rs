@@ -0,0 +1,365 @@
+use crate::clients::http::HttpClient;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+pub const TVDB_BASE_URL: &str = "https://api4.thetvdb.com/v4";
+const CACHE_TTL: Duration = Duration::from_secs(60 * 60 * 24);
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TvdbResponse<T> {
+    pub data: T,
+    pub links: Option<TvdbLinks>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TvdbLinks {
+    pub next: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TvdbLogin {
+    token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TvdbSearchResult {
+    pub tvdb_id: Option<String>,
+    pub name: String,
+    pub year: Option<String>,
+    pub overview: Option<String>,
+    pub image_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TvdbTranslation {
+    pub name: Option<String>,
+    pub overview: Option<String>,
+    pub tagline: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TvdbNamed {
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TvdbRemoteId {
+    pub id: String,
+    pub source_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TvdbCharacter {
+    pub name: Option<String>,
+    pub person_name: Option<String>,
+    pub people_id: Option<i64>,
+    pub people_type: Option<String>,
+    pub person_img_url: Option<String>,
+    pub sort: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TvdbArtwork {
+    pub image: String,
+    #[serde(rename = "type")]
+    pub type_: i32,
+    pub language: Option<String>,
+    #[serde(default)]
+    pub score: f64,
+    #[serde(default)]
+    pub width: u32,
+    #[serde(default)]
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TvdbSeasonType {
+    #[serde(rename = "type")]
+    pub type_: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TvdbSeason {
+    pub id: i64,
+    pub number: i32,
+    pub name: Option<String>,
+    #[serde(rename = "type")]
+    pub type_: TvdbSeasonType,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TvdbSeries {
+    pub id: i64,
+    pub name: String,
+    pub first_aired: Option<String>,
+    pub last_aired: Option<String>,
+    pub status: Option<TvdbNamed>,
+    pub average_runtime: Option<i32>,
+    pub original_network: Option<TvdbNamed>,
+    pub original_country: Option<String>,
+    #[serde(default)]
+    pub genres: Vec<TvdbNamed>,
+    #[serde(default)]
+    pub remote_ids: Vec<TvdbRemoteId>,
+    #[serde(default)]
+    pub characters: Vec<TvdbCharacter>,
+    #[serde(default)]
+    pub artworks: Vec<TvdbArtwork>,
+    #[serde(default)]
+    pub seasons: Vec<TvdbSeason>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TvdbReleaseDate {
+    pub date: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TvdbMovie {
+    pub id: i64,
+    pub name: String,
+    pub runtime: Option<i32>,
+    pub first_release: Option<TvdbReleaseDate>,
+    pub original_country: Option<String>,
+    #[serde(default)]
+    pub genres: Vec<TvdbNamed>,
+    #[serde(default)]
+    pub studios: Vec<TvdbNamed>,
+    #[serde(default)]
+    pub remote_ids: Vec<TvdbRemoteId>,
+    #[serde(default)]
+    pub characters: Vec<TvdbCharacter>,
+    #[serde(default)]
+    pub artworks: Vec<TvdbArtwork>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TvdbEpisode {
+    pub id: i64,
+    pub name: Option<String>,
+    pub overview: Option<String>,
+    pub aired: Option<String>,
+    pub runtime: Option<i32>,
+    pub season_number: i32,
+    pub number: i32,
+    pub absolute_number: Option<i32>,
+    pub image: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TvdbEpisodePage {
+    #[serde(default)]
+    pub episodes: Vec<TvdbEpisode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TvdbBiography {
+    pub biography: Option<String>,
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TvdbPerson {
+    pub id: i64,
+    pub name: String,
+    pub image: Option<String>,
+    pub birth: Option<String>,
+    pub death: Option<String>,
+    pub birth_place: Option<String>,
+    #[serde(default)]
+    pub biographies: Vec<TvdbBiography>,
+    #[serde(default)]
+    pub remote_ids: Vec<TvdbRemoteId>,
+}
+
+pub struct TvdbService {
+    http: Arc<HttpClient>,
+    base_url: String,
+    api_key: String,
+    pin: Option<String>,
+    token: RwLock<Option<String>>,
+}
+
+impl TvdbService {
+    pub fn new(
+        http: Arc<HttpClient>,
+        base_url: String,
+        api_key: String,
+        pin: Option<String>,
+    ) -> Self {
+        http.rate_limit(&base_url, Duration::from_millis(100));
+
+        Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            pin,
+            token: RwLock::new(None),
+        }
+    }
+
+    async fn login(&self) -> anyhow::Result<String> {
+        let mut body = serde_json::json!({ "apikey": self.api_key });
+        if let Some(pin) = &self.pin {
+            body["pin"] = serde_json::Value::String(pin.clone());
+        }
+
+        let response = self
+            .http
+            .execute(
+                self.http
+                    .post(&format!("{}/login", self.base_url))
+                    .json(&body),
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::Error::msg(format!(
+                "Failed to log in to TVDB: {}",
+                response.text().await?
+            )));
+        }
+
+        let login: TvdbResponse<TvdbLogin> = response.json().await?;
+        *self.token.write().await = Some(login.data.token.clone());
+        Ok(login.data.token)
+    }
+
+    async fn token(&self) -> anyhow::Result<String> {
+        if let Some(token) = self.token.read().await.clone() {
+            return Ok(token);
+        }
+
+        self.login().await
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> anyhow::Result<TvdbResponse<T>> {
+        let mut token = self.token().await?;
+
+        // Tokens expire after a month, log in again once if the current one is rejected
+        for retry in [true, false] {
+            let response = self
+                .http
+                .fetch(
+                    self.http
+                        .get(&format!("{}{}", self.base_url, path))
+                        .bearer_auth(&token)
+                        .query(query),
+                    CACHE_TTL,
+                )
+                .await?;
+
+            if response.status == StatusCode::UNAUTHORIZED && retry {
+                token = self.login().await?;
+                continue;
+            }
+
+            if !response.is_success() {
+                return Err(anyhow::Error::msg(format!(
+                    "Failed to fetch {path} from TVDB: {}",
+                    response.body
+                )));
+            }
+
+            let body = response.body;
+            return serde_json::from_str(&body).map_err(|e| {
+                anyhow::Error::msg(format!(
+                    "Failed to parse {path} from TVDB: {e}, for body: {body}"
+                ))
+            });
+        }
+
+        unreachable!()
+    }
+
+    pub async fn search(
+        &self,
+        query: &str,
+        type_: &str,
+        year: Option<i32>,
+    ) -> anyhow::Result<Vec<TvdbSearchResult>> {
+        let mut parameters = vec![("query", query.to_string()), ("type", type_.to_string())];
+        if let Some(year) = year {
+            parameters.push(("year", year.to_string()));
+        }
+
+        Ok(self.get("/search", &parameters).await?.data)
+    }
+
+    pub async fn fetch_series(&self, id: &str) -> anyhow::Result<TvdbSeries> {
+        Ok(self.get(&format!("/series/{id}/extended"), &[]).await?.data)
+    }
+
+    pub async fn fetch_series_translation(
+        &self,
+        id: &str,
+        language: &str,
+    ) -> anyhow::Result<TvdbTranslation> {
+        Ok(self
+            .get(&format!("/series/{id}/translations/{language}"), &[])
+            .await?
+            .data)
+    }
+
+    pub async fn fetch_series_episodes(
+        &self,
+        id: &str,
+        season_type: &str,
+        language: &str,
+    ) -> anyhow::Result<Vec<TvdbEpisode>> {
+        let mut episodes = Vec::new();
+
+        let mut page = 0;
+        loop {
+            let response: TvdbResponse<TvdbEpisodePage> = self
+                .get(
+                    &format!("/series/{id}/episodes/{season_type}/{language}"),
+                    &[("page", page.to_string())],
+                )
+                .await?;
+
+            episodes.extend(response.data.episodes);
+
+            if response.links.and_then(|links| links.next).is_none() {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(episodes)
+    }
+
+    pub async fn fetch_movie(&self, id: &str) -> anyhow::Result<TvdbMovie> {
+        Ok(self.get(&format!("/movies/{id}/extended"), &[]).await?.data)
+    }
+
+    pub async fn fetch_movie_translation(
+        &self,
+        id: &str,
+        language: &str,
+    ) -> anyhow::Result<TvdbTranslation> {
+        Ok(self
+            .get(&format!("/movies/{id}/translations/{language}"), &[])
+            .await?
+            .data)
+    }
+
+    pub async fn fetch_person(&self, id: &str) -> anyhow::Result<TvdbPerson> {
+        Ok(self.get(&format!("/people/{id}/extended"), &[]).await?.data)
+    }
+}
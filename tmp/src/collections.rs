@@ -0,0 +1,191 @@
+use crate::jobs::fetch_metadata::{FetchMetadata, FetchMetadataPayload};
+use crate::models::{
+    CollectionItem, InsertableCollectionItem, InsertableMedia, Media, MediaAttributes, MediaSet,
+};
+use crate::repositories;
+use crate::state::AppState;
+use chrono::{Datelike, Utc};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, AsyncPgConnection};
+use serde_json::json;
+use tracing::info;
+
+pub async fn create(
+    state: &AppState,
+    connection: &mut AsyncPgConnection,
+    library_id: i64,
+    set: &MediaSet,
+) -> Result<Media, anyhow::Error> {
+    let collection = insert(connection, library_id, set).await?;
+    fetch_metadata(state, collection.id)?;
+    Ok(collection)
+}
+
+async fn insert(
+    connection: &mut AsyncPgConnection,
+    library_id: i64,
+    set: &MediaSet,
+) -> Result<Media, anyhow::Error> {
+    let mut collection = InsertableMedia {
+        type_: "collection".to_string(),
+        library_id,
+        title: set.name.clone(),
+        attributes: json!({
+            "title": set.name,
+            "plot": set.overview,
+        }),
+        ..Default::default()
+    };
+    collection.update_metadata_status();
+
+    let collection = repositories::media::create(connection, &collection).await?;
+    info!(
+        "Created collection {} ({})",
+        collection.id, collection.title
+    );
+
+    Ok(collection)
+}
+
+fn fetch_metadata(state: &AppState, collection_id: i64) -> Result<(), anyhow::Error> {
+    state.queue.send(Box::new(FetchMetadata::new(
+        state.clone(),
+        FetchMetadataPayload::new(collection_id),
+    )))?;
+    Ok(())
+}
+
+/// Collections left without items are removed, items added by hand stay where they are.
+pub async fn sync_media(state: &AppState, media: &Media) -> Result<(), anyhow::Error> {
+    if media.parent_id.is_some() || media.type_ == "collection" {
+        return Ok(());
+    }
+
+    let mut connection = state.pool.get().await?;
+    let set = media.collection.as_ref().map(|collection| &collection.0);
+
+    let items = repositories::collection_item::find_by_media_id(&mut connection, media.id).await?;
+    let mut in_set = false;
+    for item in items {
+        let Some(set_name) = &item.set_name else {
+            continue;
+        };
+
+        if set.is_some_and(|set| set.name == *set_name) {
+            in_set = true;
+            continue;
+        }
+
+        repositories::collection_item::delete(&mut connection, item.collection_id, media.id)
+            .await?;
+        if repositories::collection_item::find_by_collection_id(&mut connection, item.collection_id)
+            .await?
+            .is_empty()
+        {
+            info!("Removing empty collection {}", item.collection_id);
+            repositories::media::delete(&mut connection, item.collection_id).await?;
+        }
+    }
+
+    let Some(set) = set.filter(|_| !in_set) else {
+        return Ok(());
+    };
+
+    let created = connection
+        .transaction::<_, anyhow::Error, _>(|connection| {
+            async move {
+                repositories::collection_item::lock_library(connection, media.library_id).await?;
+                add_to_set(connection, media, set).await
+            }
+            .scope_boxed()
+        })
+        .await?;
+
+    // Queued once committed so the job can find the collection
+    if let Some(collection) = created {
+        fetch_metadata(state, collection.id)?;
+    }
+    Ok(())
+}
+
+/// Callers hold the library's lock so no other item creates the collection meanwhile.
+async fn add_to_set(
+    connection: &mut AsyncPgConnection,
+    media: &Media,
+    set: &MediaSet,
+) -> Result<Option<Media>, anyhow::Error> {
+    let mut created = None;
+    let collection_id = match repositories::collection_item::find_collection_id_by_set_name(
+        connection,
+        media.library_id,
+        &set.name,
+    )
+    .await?
+    {
+        Some(collection_id) => collection_id,
+        None => {
+            match repositories::media::find_collection_by_title(
+                connection,
+                media.library_id,
+                &set.name,
+            )
+            .await?
+            {
+                Some(collection) => collection.id,
+                None => {
+                    let collection = insert(connection, media.library_id, set).await?;
+                    let id = collection.id;
+                    created = Some(collection);
+                    id
+                }
+            }
+        }
+    };
+
+    repositories::collection_item::create(
+        connection,
+        &InsertableCollectionItem {
+            collection_id,
+            media_id: media.id,
+            set_name: Some(set.name.clone()),
+        },
+    )
+    .await?;
+
+    Ok(created)
+}
+
+/// Items in the collection through their set lose it, locked so the next scan does not put them
+/// back.
+pub async fn remove_item(
+    connection: &mut AsyncPgConnection,
+    item: &CollectionItem,
+) -> Result<(), anyhow::Error> {
+    if item.set_name.is_some()
+        && let Some(mut media) = repositories::media::find_by_id(connection, item.media_id).await?
+    {
+        media.collection = None;
+        if !media.is_locked("collection") {
+            media.locked_fields.push("collection".to_string());
+            media.locked_fields.sort();
+        }
+        media.updated_at = Utc::now().naive_utc();
+        repositories::media::update(connection, &media).await?;
+    }
+
+    repositories::collection_item::delete(connection, item.collection_id, item.media_id).await?;
+    Ok(())
+}
+
+pub fn sort_by_release(items: &mut [Media]) {
+    items.sort_by_cached_key(|media| {
+        let attributes = MediaAttributes::from(&media.attributes);
+        let year = attributes.premiered.map(|p| p.year()).or(attributes.year);
+        (
+            year.is_none(),
+            year,
+            attributes.premiered,
+            media.title.clone(),
+        )
+    });
+}
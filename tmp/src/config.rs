@@ -0,0 +1,15 @@
+use std::path::Path;
+
+/// Reads a credential from the `name` environment variable, or from the file `name_FILE` points
+/// to so keys can be mounted as Docker secrets.
+pub fn secret(name: &str) -> Option<String> {
+    if let Ok(value) = std::env::var(name) {
+        return Some(value).filter(|value| !value.trim().is_empty());
+    }
+
+    let path = std::env::var(format!("{name}_FILE")).ok()?;
+    std::fs::read_to_string(Path::new(&path))
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
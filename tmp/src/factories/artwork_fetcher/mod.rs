@@ -1,54 +1,168 @@
+use crate::clients::fanart::{FanartImage, FanartService, FANART_BASE_URL};
+use crate::clients::http::HttpClient;
+use crate::config;
+use crate::factories::metadata_provider::RemoteImage;
+use crate::models::{FileType, Library, Media};
 use crate::state::AppState;
 use async_trait::async_trait;
-use axum::body::Bytes;
-use image::{ImageFormat, ImageReader};
+use itertools::Itertools;
 use std::collections::HashMap;
-use std::io::Cursor;
-use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::warn;
 
 pub mod movie;
+pub mod season;
 pub mod tvshow;
 
 #[async_trait]
 pub trait ArtworkFetcher {
     async fn fetch_artwork(&self, context: AppState, media_id: i64) -> Result<(), anyhow::Error>;
+
+    async fn get_images(&self, media: &Media) -> Result<Vec<RemoteImage>, anyhow::Error>;
+}
+
+fn remote_images(file_type: FileType, images: &[FanartImage]) -> Vec<RemoteImage> {
+    images
+        .iter()
+        .map(|image| RemoteImage {
+            file_type: file_type.clone(),
+            url: image.url.clone(),
+            language: Some(image.lang.clone()),
+            width: None,
+            height: None,
+            score: f64::from(image.like_count()),
+        })
+        .collect()
 }
 
 pub struct ArtworkFetcherFactory {
     pub artwork_fetchers: HashMap<String, Box<dyn ArtworkFetcher + Send + Sync>>,
+    pub fanart: Option<Arc<FanartService>>,
 }
 
 impl ArtworkFetcherFactory {
-    pub fn new() -> Self {
+    pub fn new(http_client: Arc<HttpClient>) -> Self {
         let mut artwork_fetchers = HashMap::new();
+
+        let Some(api_key) = config::secret("FANART_API_KEY") else {
+            warn!("FANART_API_KEY not set, artwork will not be fetched from fanart.tv");
+            return Self {
+                artwork_fetchers,
+                fanart: None,
+            };
+        };
+
+        let service = Arc::new(FanartService::new(
+            http_client,
+            std::env::var("FANART_BASE_URL").unwrap_or(FANART_BASE_URL.to_string()),
+            api_key,
+            config::secret("FANART_CLIENT_KEY"),
+        ));
+
         artwork_fetchers.insert(
             "movie".to_string(),
-            Box::new(movie::MovieArtworkFetcher) as Box<dyn ArtworkFetcher + Send + Sync>,
+            Box::new(movie::MovieArtworkFetcher::new(service.clone()))
+                as Box<dyn ArtworkFetcher + Send + Sync>,
         );
         artwork_fetchers.insert(
             "tvshow".to_string(),
-            Box::new(tvshow::TvShowArtworkFetcher) as Box<dyn ArtworkFetcher + Send + Sync>,
+            Box::new(tvshow::TvShowArtworkFetcher::new(service.clone()))
+                as Box<dyn ArtworkFetcher + Send + Sync>,
         );
-        Self { artwork_fetchers }
+        Self {
+            artwork_fetchers,
+            fanart: Some(service),
+        }
     }
 }
 
-impl Default for ArtworkFetcherFactory {
-    fn default() -> Self {
-        Self::new()
+pub async fn find_candidates(
+    state: &AppState,
+    media: &Media,
+    library: &Library,
+    file_type: &FileType,
+) -> Vec<(String, RemoteImage)> {
+    let mut candidates = Vec::new();
+
+    if let Some(artwork_fetcher) = state
+        .artwork_fetcher_factory
+        .artwork_fetchers
+        .get(&media.type_)
+    {
+        match artwork_fetcher.get_images(media).await {
+            Ok(images) => candidates.push(("fanart".to_string(), images)),
+            Err(e) => warn!(
+                "Failed to get fanart.tv images for Media {}: {}",
+                media.id, e
+            ),
+        }
+    }
+
+    if let Some(provider) = state
+        .metadata_provider_factory
+        .get_provider(&library.metadata_provider)
+        && let Some(id) = media.provider_ids.get(provider.name())
+    {
+        match provider.get_images(&media.type_, id).await {
+            Ok(images) => candidates.push((provider.name().to_string(), images)),
+            Err(e) => warn!(
+                "Failed to get {} images for Media {}: {}",
+                provider.name(),
+                media.id,
+                e
+            ),
+        }
     }
+
+    candidates
+        .into_iter()
+        .flat_map(|(source, images)| {
+            images
+                .into_iter()
+                .filter(|image| image.file_type == *file_type)
+                .sorted_by(|a, b| b.score.total_cmp(&a.score))
+                .map(move |image| (source.clone(), image))
+        })
+        .collect()
 }
 
-pub fn convert_and_save_image_as_webp(
-    img_bytes: Bytes,
-    path: &PathBuf,
-) -> Result<(), anyhow::Error> {
-    let img = ImageReader::new(Cursor::new(&img_bytes))
-        .with_guessed_format()
-        .map_err(|e| anyhow::Error::msg(format!("Failed to guess thumbnail format: {e}")))?
-        .decode()
-        .map_err(|e| anyhow::Error::msg(format!("Failed to decode thumbnail: {e}")))?;
-    img.save_with_format(path, ImageFormat::WebP)
-        .map_err(|e| anyhow::Error::msg(format!("Failed to save thumbnail: {e}")))?;
-    Ok(())
+/// Images in other languages are dropped, except for backgrounds, which rarely carry text and
+/// prefer language neutral ones.
+pub fn rank_images<T>(
+    images: Vec<T>,
+    file_type: &FileType,
+    languages: &[String],
+    language: impl Fn(&T) -> Option<&str>,
+    popularity: impl Fn(&T) -> f64,
+) -> Vec<T> {
+    let rank = |image: &T| {
+        let position = match language(image) {
+            None | Some("" | "00") => None,
+            Some(language) => Some(
+                languages
+                    .iter()
+                    .position(|preferred| preferred.eq_ignore_ascii_case(language)),
+            ),
+        };
+
+        match (file_type, position) {
+            (FileType::Background, None) => Some(0),
+            (FileType::Background, Some(Some(position))) => Some(position + 1),
+            (FileType::Background, Some(None)) => Some(languages.len() + 1),
+            (_, Some(Some(position))) => Some(position),
+            (_, None) => Some(languages.len()),
+            (_, Some(None)) => None,
+        }
+    };
+
+    images
+        .into_iter()
+        .filter_map(|image| rank(&image).map(|rank| (rank, image)))
+        .sorted_by(|(a_rank, a), (b_rank, b)| {
+            a_rank
+                .cmp(b_rank)
+                .then(popularity(b).total_cmp(&popularity(a)))
+        })
+        .map(|(_, image)| image)
+        .collect()
 }
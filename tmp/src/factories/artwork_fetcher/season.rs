@@ -0,0 +1,213 @@
+use crate::clients::fanart::{FanartImage, FanartTVResponse};
+use crate::factories::artwork_fetcher::{rank_images, tvshow};
+use crate::factories::metadata_provider::SeasonMetadata;
+use crate::models::{EpisodeOrdering, FileType, Library, Media};
+use crate::repositories;
+use crate::state::AppState;
+use std::str::FromStr;
+use tracing::{info, warn};
+
+pub async fn fetch_season_artwork(state: &AppState, media_id: i64) -> Result<(), anyhow::Error> {
+    let mut connection = state.pool.get().await?;
+    let media = repositories::media::find_by_id(&mut connection, media_id)
+        .await?
+        .ok_or(anyhow::Error::msg(format!(
+            "Media with id {media_id} not found"
+        )))?;
+
+    let Some(season) = media.season else {
+        return Err(anyhow::Error::msg(format!(
+            "Media {media_id} is neither a season nor an episode"
+        )));
+    };
+
+    let mut show = None;
+    let mut parent_id = media.parent_id;
+    while let Some(id) = parent_id {
+        let parent = repositories::media::find_by_id(&mut connection, id)
+            .await?
+            .ok_or(anyhow::Error::msg(format!("Media with id {id} not found")))?;
+        parent_id = parent.parent_id;
+        show = Some(parent);
+    }
+    let show = show.ok_or(anyhow::Error::msg(format!("Media {media_id} has no show")))?;
+
+    let library = repositories::library::find_by_id(&mut connection, media.library_id)
+        .await?
+        .ok_or(anyhow::Error::msg(format!(
+            "Library with id {} not found",
+            media.library_id
+        )))?;
+    drop(connection);
+
+    let file_types = match media.episode {
+        Some(_) => vec![FileType::Thumbnail],
+        None => vec![FileType::Poster, FileType::Thumbnail, FileType::Banner],
+    };
+    let missing: Vec<FileType> = file_types
+        .into_iter()
+        .filter(|file_type| {
+            !media.is_locked(file_type.as_str())
+                && !media.files.iter().any(|f| f.type_ == *file_type)
+        })
+        .collect();
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let fanart = fetch_fanart(state, &show).await;
+    let provider_season = fetch_provider_season(state, &library, &show, season).await;
+
+    let mut files = Vec::new();
+    for file_type in missing {
+        let fanart_season = fanart
+            .as_ref()
+            .map(|fanart| fanart_images(fanart, &file_type, Some(season), &library))
+            .unwrap_or_default();
+        let fanart_show = fanart
+            .as_ref()
+            .map(|fanart| fanart_images(fanart, &file_type, None, &library))
+            .unwrap_or_default();
+        let provider = provider_season
+            .as_ref()
+            .and_then(|provider_season| match (media.episode, &file_type) {
+                (Some(episode), FileType::Thumbnail) => provider_season
+                    .episode(episode)
+                    .and_then(|episode| episode.thumb_url.clone()),
+                (None, FileType::Poster) => provider_season.poster_url.clone(),
+                _ => None,
+            })
+            .into_iter()
+            .collect();
+
+        let urls: Vec<String> = match media.episode {
+            Some(_) => [provider, fanart_season, fanart_show].concat(),
+            None => [fanart_season, provider, fanart_show].concat(),
+        };
+
+        let mut saved = false;
+        for url in &urls {
+            let bytes = match state.http_client.get_bytes(url).await {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    warn!("Failed to download {}: {}", url, e);
+                    continue;
+                }
+            };
+
+            match state
+                .metadata_store
+                .save_image(media.id, file_type.clone(), bytes)
+                .await
+            {
+                Ok(file) => {
+                    files.push(file);
+                    saved = true;
+                    break;
+                }
+                Err(e) => warn!("Failed to save {}: {}", url, e),
+            }
+        }
+
+        if !saved {
+            warn!("No {:?} found for Media {}", file_type, media_id);
+        }
+    }
+
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    let mut connection = state.pool.get().await?;
+    let Some(mut media) = repositories::media::find_by_id(&mut connection, media_id).await? else {
+        return Ok(());
+    };
+    for file in files {
+        if !media.files.iter().any(|f| f.type_ == file.type_) {
+            media.files.as_mut().push(file);
+        }
+    }
+    repositories::media::update(&mut connection, &media).await?;
+
+    info!("Finished downloading artwork for media: {}", media_id);
+    Ok(())
+}
+
+async fn fetch_fanart(state: &AppState, show: &Media) -> Option<FanartTVResponse> {
+    let service = state.artwork_fetcher_factory.fanart.as_ref()?;
+    let id = tvshow::fanart_id(show).ok()?;
+
+    service
+        .fetch_tv_art(id)
+        .await
+        .inspect_err(|e| {
+            warn!(
+                "Failed to get fanart.tv artwork for Media {}: {}",
+                show.id, e
+            )
+        })
+        .ok()
+}
+
+async fn fetch_provider_season(
+    state: &AppState,
+    library: &Library,
+    show: &Media,
+    season: i32,
+) -> Option<SeasonMetadata> {
+    let provider = state
+        .metadata_provider_factory
+        .get_provider(&library.metadata_provider)?;
+    let series_id = show.provider_ids.get(provider.name())?;
+    let ordering = EpisodeOrdering::from_str(&library.episode_ordering).ok()?;
+
+    provider
+        .get_season(series_id, season, ordering)
+        .await
+        .inspect_err(|e| {
+            warn!(
+                "Failed to get season {} of Media {} from {}: {}",
+                season,
+                show.id,
+                provider.name(),
+                e
+            )
+        })
+        .ok()
+}
+
+fn fanart_images(
+    fanart: &FanartTVResponse,
+    file_type: &FileType,
+    season: Option<i32>,
+    library: &Library,
+) -> Vec<String> {
+    let images = match (season.is_some(), file_type) {
+        (true, FileType::Poster) => &fanart.season_poster,
+        (true, FileType::Thumbnail) => &fanart.season_thumb,
+        (true, FileType::Banner) => &fanart.season_banner,
+        (false, FileType::Poster) => &fanart.tv_poster,
+        (false, FileType::Thumbnail) => &fanart.tv_thumb,
+        (false, FileType::Banner) => &fanart.tv_banner,
+        _ => return Vec::new(),
+    };
+
+    let season = season.map(|season| season.to_string());
+    let images: Vec<FanartImage> = images
+        .iter()
+        .filter(|image| season.is_none() || image.season == season)
+        .cloned()
+        .collect();
+
+    rank_images(
+        images,
+        file_type,
+        &library.preferred_languages,
+        |image| Some(image.lang.as_str()),
+        |image| f64::from(image.like_count()),
+    )
+    .into_iter()
+    .map(|image| image.url)
+    .collect()
+}
@@ -1,14 +1,31 @@
 use crate::clients::fanart::FanartService;
-use crate::factories::artwork_fetcher::{convert_and_save_image_as_webp, ArtworkFetcher};
-use crate::models::{File, FileType};
+use crate::factories::artwork_fetcher::{rank_images, remote_images, ArtworkFetcher};
+use crate::factories::metadata_provider::RemoteImage;
+use crate::models::{FileType, Media, ProviderIds};
 use crate::repositories;
 use crate::state::AppState;
 use async_trait::async_trait;
-use itertools::Itertools;
-use std::path::Path;
+use std::sync::Arc;
 use tracing::{info, warn};
 
-pub struct TvShowArtworkFetcher;
+pub struct TvShowArtworkFetcher {
+    service: Arc<FanartService>,
+}
+
+impl TvShowArtworkFetcher {
+    pub fn new(service: Arc<FanartService>) -> Self {
+        Self { service }
+    }
+}
+
+pub(super) fn fanart_id(media: &Media) -> Result<String, anyhow::Error> {
+    media
+        .provider_ids
+        .get(ProviderIds::TVDB)
+        .cloned()
+        .or(media.nfo_id())
+        .ok_or(anyhow::Error::msg("Media has no tvdb id".to_string()))
+}
 
 #[async_trait]
 impl ArtworkFetcher for TvShowArtworkFetcher {
@@ -21,23 +38,20 @@ impl ArtworkFetcher for TvShowArtworkFetcher {
                 .ok_or(anyhow::Error::msg("Failed to find media".to_string()))?
         };
 
-        let response = FanartService::new()
-            .fetch_tv_art(
-                media
-                    .attributes
-                    .get("nfoId")
-                    .ok_or(anyhow::Error::msg(
-                        "Media attributes has no nfoId".to_string(),
-                    ))?
-                    .to_string(),
-            )
+        let library = {
+            let mut connection = state.pool.get().await?;
+            repositories::library::find_by_id(&mut connection, media.library_id)
+                .await
+                .map_err(|e| anyhow::Error::msg(format!("Failed to look for library: {e}")))?
+                .ok_or(anyhow::Error::msg("Failed to find library".to_string()))?
+        };
+
+        let response = self
+            .service
+            .fetch_tv_art(fanart_id(&media)?)
             .await
             .map_err(|e| anyhow::Error::msg(format!("Failed to get artwork: {e}")))?;
 
-        if media.path.is_none() {
-            return Err(anyhow::Error::msg("Media path not set".to_string()));
-        }
-
         let file_types = vec![
             FileType::Poster,
             FileType::Logo,
@@ -46,51 +60,37 @@ impl ArtworkFetcher for TvShowArtworkFetcher {
         ];
 
         for file_type in file_types {
-            if media.files.iter().any(|f| f.type_ == file_type) {
+            if media.is_locked(file_type.as_str())
+                || media.files.iter().any(|f| f.type_ == file_type)
+            {
                 continue;
             }
 
-            let images: Vec<_> = match file_type {
-                FileType::Poster => response.tv_poster.clone(),
-                FileType::Logo => response.hdtv_logo.clone(),
-                FileType::Thumbnail => response.tv_thumb.clone(),
-                FileType::Background => response.show_background.clone(),
-                _ => unreachable!(),
-            }
-            .into_iter()
-            .filter(|t| file_type == FileType::Background || t.lang == "en")
-            .sorted_by(|a, b| b.likes.cmp(&a.likes))
-            .collect();
+            let images = rank_images(
+                match file_type {
+                    FileType::Poster => response.tv_poster.clone(),
+                    FileType::Logo => response.hdtv_logo.clone(),
+                    FileType::Thumbnail => response.tv_thumb.clone(),
+                    FileType::Background => response.show_background.clone(),
+                    _ => unreachable!(),
+                },
+                &file_type,
+                &library.preferred_languages,
+                |image| Some(image.lang.as_str()),
+                |image| f64::from(image.like_count()),
+            );
 
             match images.first() {
                 Some(image) => {
-                    let response = reqwest::Client::new()
-                        .get(&image.url)
-                        .send()
-                        .await
-                        .map_err(|e| anyhow::Error::msg(format!("Failed to download images: {e}")))?
-                        .bytes()
-                        .await
-                        .map_err(|e| {
-                            anyhow::Error::msg(format!("Failed to fetch poster bytes: {e}"))
-                        })?;
+                    let response = state.http_client.get_bytes(&image.url).await.map_err(|e| {
+                        anyhow::Error::msg(format!("Failed to download image: {e}"))
+                    })?;
 
-                    let file_name = match file_type {
-                        FileType::Poster => "poster.webp",
-                        FileType::Logo => "logo.webp",
-                        FileType::Thumbnail => "thumbnail.webp",
-                        FileType::Background => "background.webp",
-                        _ => unreachable!(),
-                    };
-                    convert_and_save_image_as_webp(
-                        response,
-                        &Path::new(&media.path.clone().unwrap()).join(file_name),
-                    )?;
-                    media.files.as_mut().push(File {
-                        type_: file_type,
-                        path: file_name.to_string(),
-                        blur_hash: None,
-                    });
+                    let file = state
+                        .metadata_store
+                        .save_image(media.id, file_type, response)
+                        .await?;
+                    media.files.as_mut().push(file);
                 }
                 None => {
                     warn!("No {:?} found for Media {}", file_type, media_id);
@@ -111,4 +111,20 @@ impl ArtworkFetcher for TvShowArtworkFetcher {
         info!("Finished downloading artwork for media: {}", media_id);
         Ok(())
     }
+
+    async fn get_images(&self, media: &Media) -> Result<Vec<RemoteImage>, anyhow::Error> {
+        let response = self.service.fetch_tv_art(fanart_id(media)?).await?;
+
+        Ok([
+            remote_images(FileType::Poster, &response.tv_poster),
+            remote_images(FileType::Logo, &response.hdtv_logo),
+            remote_images(FileType::Logo, &response.clear_logo),
+            remote_images(FileType::Background, &response.show_background),
+            remote_images(FileType::Thumbnail, &response.tv_thumb),
+            remote_images(FileType::ClearArt, &response.hdclear_art),
+            remote_images(FileType::ClearArt, &response.clear_art),
+            remote_images(FileType::Banner, &response.tv_banner),
+        ]
+        .concat())
+    }
 }
@@ -0,0 +1,224 @@
+use crate::models::{File, FileSource, FileType};
+use image::{DynamicImage, ImageReader};
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::LazyLock;
+use std::time::UNIX_EPOCH;
+use tokio::fs;
+
+pub const IMAGE_EXTENSIONS: [&str; 8] = ["jpg", "jpeg", "png", "webp", "gif", "bmp", "avif", "tbn"];
+
+/// Most preferred first within each type.
+const ARTWORK_NAMES: [(&str, FileType); 16] = [
+    ("poster", FileType::Poster),
+    ("folder", FileType::Poster),
+    ("cover", FileType::Poster),
+    ("logo", FileType::Logo),
+    ("clearlogo", FileType::Logo),
+    ("background", FileType::Background),
+    ("fanart", FileType::Background),
+    ("backdrop", FileType::Background),
+    ("thumbnail", FileType::Thumbnail),
+    ("landscape", FileType::Thumbnail),
+    ("thumb", FileType::Thumbnail),
+    ("banner", FileType::Banner),
+    ("clearart", FileType::ClearArt),
+    ("disc", FileType::Disc),
+    ("discart", FileType::Disc),
+    ("cdart", FileType::Disc),
+];
+
+static SEASON_ARTWORK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^season(\d+|-specials)-([a-z0-9]+)$").unwrap());
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalArtwork {
+    pub file_type: FileType,
+    pub priority: usize,
+    pub season: Option<i32>,
+    pub prefix: Option<String>,
+}
+
+pub fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| IMAGE_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+pub fn content_type(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_lowercase)
+        .as_deref()
+    {
+        Some("webp") => "image/webp",
+        Some("jpg" | "jpeg" | "tbn") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("gif") => "image/gif",
+        Some("bmp") => "image/bmp",
+        Some("avif") => "image/avif",
+        _ => "application/octet-stream",
+    }
+}
+
+pub fn blur_hash(image: &DynamicImage) -> Option<String> {
+    let thumbnail = image.thumbnail(64, 64).to_rgba8();
+    blurhash::encode(
+        4,
+        3,
+        thumbnail.width(),
+        thumbnail.height(),
+        thumbnail.as_raw(),
+    )
+    .ok()
+}
+
+/// Hashes from `previous` are reused while the file keeps its size and modification time.
+pub async fn fill_blur_hashes(folder: &Path, files: &mut [File], previous: &[File]) {
+    let mut known: HashMap<(String, Option<u64>, Option<i64>), String> = previous
+        .iter()
+        .filter(|file| file.size.is_some() && file.modified.is_some())
+        .filter_map(|file| {
+            Some((
+                (file.path.clone(), file.size, file.modified),
+                file.blur_hash.clone()?,
+            ))
+        })
+        .collect();
+
+    for file in files
+        .iter_mut()
+        .filter(|file| file.source == FileSource::Library)
+        .filter(|file| file.blur_hash.is_none() && is_image(Path::new(&file.path)))
+    {
+        let path = folder.join(&file.path);
+        if let Ok(metadata) = fs::metadata(&path).await {
+            file.size = Some(metadata.len());
+            file.modified = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .and_then(|modified| i64::try_from(modified.as_millis()).ok());
+        }
+
+        let key = (file.path.clone(), file.size, file.modified);
+        if let Some(blur_hash) = known.get(&key) {
+            file.blur_hash = Some(blur_hash.clone());
+            continue;
+        }
+
+        file.blur_hash = tokio::task::spawn_blocking(move || {
+            let image = ImageReader::open(&path)
+                .ok()?
+                .with_guessed_format()
+                .ok()?
+                .decode()
+                .ok()?;
+            blur_hash(&image)
+        })
+        .await
+        .ok()
+        .flatten();
+
+        if let Some(blur_hash) = &file.blur_hash
+            && file.size.is_some()
+            && file.modified.is_some()
+        {
+            known.insert(key, blur_hash.clone());
+        }
+    }
+}
+
+/// Locked artwork types keep the downloaded or uploaded image even when the library has one.
+pub fn keep_metadata_files(files: &mut Vec<File>, previous: &[File], locked_fields: &[String]) {
+    let previous: Vec<&File> = previous
+        .iter()
+        .filter(|file| file.source == FileSource::Metadata)
+        .collect();
+    let locked = |file_type: &FileType| {
+        locked_fields
+            .iter()
+            .any(|field| field == file_type.as_str())
+            && previous.iter().any(|file| file.type_ == *file_type)
+    };
+
+    files.retain(|file| !locked(&file.type_));
+    for file in &previous {
+        if locked(&file.type_) || !files.iter().any(|f| f.type_ == file.type_) {
+            files.push((*file).clone());
+        }
+    }
+}
+
+fn artwork_name(name: &str) -> Option<(usize, FileType)> {
+    ARTWORK_NAMES
+        .iter()
+        .position(|(artwork_name, _)| *artwork_name == name)
+        .map(|priority| (priority, ARTWORK_NAMES[priority].1.clone()))
+}
+
+pub fn local_artwork(path: &Path) -> Option<LocalArtwork> {
+    if !is_image(path) {
+        return None;
+    }
+
+    let stem = path.file_stem()?.to_str()?;
+    let lowercase_stem = stem.to_lowercase();
+
+    if let Some(captures) = SEASON_ARTWORK.captures(&lowercase_stem) {
+        let season = match &captures[1] {
+            "-specials" => 0,
+            season => season.parse().ok()?,
+        };
+        let (priority, file_type) = artwork_name(&captures[2])?;
+
+        return Some(LocalArtwork {
+            file_type,
+            priority,
+            season: Some(season),
+            prefix: None,
+        });
+    }
+
+    if let Some((priority, file_type)) = artwork_name(&lowercase_stem) {
+        return Some(LocalArtwork {
+            file_type,
+            priority,
+            season: None,
+            prefix: None,
+        });
+    }
+
+    let (prefix, name) = stem.rsplit_once('-')?;
+    let (priority, file_type) = artwork_name(&name.to_lowercase())?;
+
+    Some(LocalArtwork {
+        file_type,
+        priority,
+        season: None,
+        prefix: Some(prefix.to_string()),
+    })
+}
+
+#[derive(Debug, Default)]
+pub struct ArtworkCandidates(HashMap<FileType, (usize, String)>);
+
+impl ArtworkCandidates {
+    pub fn insert(&mut self, artwork: &LocalArtwork, path: String) {
+        match self.0.get(&artwork.file_type) {
+            Some((priority, _)) if *priority <= artwork.priority => {}
+            _ => {
+                self.0
+                    .insert(artwork.file_type.clone(), (artwork.priority, path));
+            }
+        }
+    }
+
+    pub fn into_files(self) -> impl Iterator<Item = (FileType, String)> {
+        self.0
+            .into_iter()
+            .map(|(file_type, (_, path))| (file_type, path))
+    }
+}
@@ -0,0 +1,138 @@
+use crate::factories::library_scanner::is_video;
+use crate::ignore_rules::IgnoreRules;
+use crate::models::{ExtraKind, InsertableExtra};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+const EXTRA_FOLDERS: [(&str, ExtraKind); 10] = [
+    ("trailers", ExtraKind::Trailer),
+    ("featurettes", ExtraKind::Featurette),
+    ("behind the scenes", ExtraKind::BehindTheScenes),
+    ("deleted scenes", ExtraKind::DeletedScene),
+    ("interviews", ExtraKind::Interview),
+    ("scenes", ExtraKind::Scene),
+    ("shorts", ExtraKind::Short),
+    ("extras", ExtraKind::Other),
+    ("other", ExtraKind::Other),
+    ("others", ExtraKind::Other),
+];
+
+const EXTRA_SUFFIXES: [(&str, ExtraKind); 8] = [
+    ("trailer", ExtraKind::Trailer),
+    ("featurette", ExtraKind::Featurette),
+    ("behindthescenes", ExtraKind::BehindTheScenes),
+    ("deleted", ExtraKind::DeletedScene),
+    ("interview", ExtraKind::Interview),
+    ("scene", ExtraKind::Scene),
+    ("short", ExtraKind::Short),
+    ("other", ExtraKind::Other),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalExtra {
+    pub kind: ExtraKind,
+    pub title: String,
+    pub path: PathBuf,
+}
+
+impl LocalExtra {
+    pub fn into_insertable(self, media_id: i64) -> InsertableExtra {
+        InsertableExtra {
+            media_id,
+            kind: self.kind.as_str().to_string(),
+            title: self.title,
+            path: self.path.to_str().unwrap().to_string(),
+        }
+    }
+}
+
+pub fn extra_folder_kind(path: &Path) -> Option<ExtraKind> {
+    let name = path.file_name()?.to_str()?.to_lowercase();
+
+    EXTRA_FOLDERS
+        .iter()
+        .find(|(folder, _)| *folder == name)
+        .map(|(_, kind)| *kind)
+}
+
+pub fn extra_file_kind(path: &Path) -> Option<(ExtraKind, String)> {
+    let stem = path.file_stem()?.to_str()?;
+    let (title, suffix) = stem.rsplit_once('-')?;
+    let suffix = suffix.to_lowercase();
+
+    EXTRA_SUFFIXES
+        .iter()
+        .find(|(extra_suffix, _)| *extra_suffix == suffix)
+        .map(|(_, kind)| (*kind, title.trim().to_string()))
+}
+
+pub async fn scan_extra_folder(
+    folder_path: &Path,
+    kind: ExtraKind,
+    ignore_rules: &IgnoreRules,
+) -> Result<Vec<LocalExtra>, anyhow::Error> {
+    let mut extras = Vec::new();
+
+    let mut dir = fs::read_dir(folder_path).await?;
+    while let Some(entry) = dir.next_entry().await? {
+        let path = entry.path();
+
+        if !path.is_file() || ignore_rules.is_ignored(&path, false) || !is_video(&path).await {
+            continue;
+        }
+
+        let Some(title) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+
+        extras.push(LocalExtra {
+            kind,
+            title: title.to_string(),
+            path,
+        });
+    }
+
+    Ok(extras)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extra_suffixes_are_recognised() {
+        assert_eq!(
+            extra_file_kind(Path::new("/movies/Alien (1979)/Alien (1979)-trailer.mkv")),
+            Some((ExtraKind::Trailer, "Alien (1979)".to_string()))
+        );
+        assert_eq!(
+            extra_file_kind(Path::new("Making of -BehindTheScenes.mp4")),
+            Some((ExtraKind::BehindTheScenes, "Making of".to_string()))
+        );
+        assert_eq!(
+            extra_file_kind(Path::new("Alternate ending-deleted.mkv")),
+            Some((ExtraKind::DeletedScene, "Alternate ending".to_string()))
+        );
+    }
+
+    #[test]
+    fn other_file_names_are_not_extras() {
+        assert_eq!(extra_file_kind(Path::new("Alien (1979).mkv")), None);
+        assert_eq!(extra_file_kind(Path::new("Spider-Man (2002).mkv")), None);
+        assert_eq!(extra_file_kind(Path::new("Alien-trailers.mkv")), None);
+        assert_eq!(extra_file_kind(Path::new("trailer.mkv")), None);
+    }
+
+    #[test]
+    fn extra_folders_are_recognised_case_insensitively() {
+        assert_eq!(
+            extra_folder_kind(Path::new("/movies/Alien (1979)/Behind The Scenes")),
+            Some(ExtraKind::BehindTheScenes)
+        );
+        assert_eq!(
+            extra_folder_kind(Path::new("TRAILERS")),
+            Some(ExtraKind::Trailer)
+        );
+        assert_eq!(extra_folder_kind(Path::new("Season 1")), None);
+    }
+}
@@ -1,11 +1,19 @@
-use crate::models::Library;
+use crate::jobs::fetch_artwork::{FetchArtwork, FetchArtworkPayload};
+use crate::jobs::fetch_metadata::{FetchMetadata, FetchMetadataPayload};
+use crate::models::{Library, Media, MetadataStatus};
 use crate::state::AppState;
 use async_trait::async_trait;
 use regex::Regex;
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::LazyLock;
+use tokio::fs;
+use tokio::io::AsyncReadExt;
 
+pub mod artwork;
+pub mod extras;
 pub mod movie;
+pub mod themes;
 pub mod tvshow;
 
 #[async_trait]
@@ -49,7 +57,57 @@ impl Default for ScannerFactory {
     }
 }
 
+static TITLE_AND_YEAR: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(.+?)[\s.]*[(\[]((?:18|19|20)\d{2})[)\]]").unwrap());
+
+static SEASON_AND_EPISODE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)s(\d{1,3})[ ._-]?e(\d{1,4})").unwrap());
+
+pub fn title_and_year(name: &str) -> (String, Option<i32>) {
+    match TITLE_AND_YEAR.captures(name) {
+        Some(captures) => (
+            captures[1].replace('.', " ").trim().to_string(),
+            captures[2].parse().ok(),
+        ),
+        None => (name.replace('.', " ").trim().to_string(), None),
+    }
+}
+
+pub fn season_and_episode(name: &str) -> Option<(i32, i32)> {
+    let captures = SEASON_AND_EPISODE.captures(name)?;
+    Some((captures[1].parse().ok()?, captures[2].parse().ok()?))
+}
+
+pub fn queue_metadata_jobs(state: &AppState, media: &Media) -> Result<(), anyhow::Error> {
+    if media.metadata_status == MetadataStatus::Complete.as_str() {
+        state.queue.send(Box::new(FetchArtwork::new(
+            state.clone(),
+            FetchArtworkPayload::new(media.id),
+        )))?;
+    } else {
+        state.queue.send(Box::new(FetchMetadata::new(
+            state.clone(),
+            FetchMetadataPayload::new(media.id),
+        )))?;
+    }
+
+    Ok(())
+}
+
 pub fn remove_empty_self_closing_tags(xml: &str) -> String {
     let re = Regex::new(r"<([a-zA-Z0-9_:-]+)\s*/>").unwrap();
     re.replace_all(xml, "").to_string()
 }
+
+pub async fn is_video(path: &Path) -> bool {
+    let Ok(mut open) = fs::File::open(path).await else {
+        return false;
+    };
+
+    let mut buffer = [0; 8192];
+    match open.read(&mut buffer[..]).await {
+        Ok(bytes_read) if bytes_read > 0 => infer::get(&buffer[..bytes_read])
+            .is_some_and(|kind| kind.mime_type().starts_with("video/")),
+        _ => false,
+    }
+}
@@ -1,13 +1,23 @@
-use crate::factories::library_scanner::{remove_empty_self_closing_tags, LibraryScanner};
-use crate::jobs::fetch_artwork::{FetchArtwork, FetchArtworkPayload};
-use crate::models::{File, FileType, InsertableMedia, Library};
+use crate::collections;
+use crate::factories::library_scanner::artwork::{
+    fill_blur_hashes, keep_metadata_files, local_artwork, ArtworkCandidates,
+};
+use crate::factories::library_scanner::extras::{
+    extra_file_kind, extra_folder_kind, scan_extra_folder, LocalExtra,
+};
+use crate::factories::library_scanner::themes::find_themes;
+use crate::factories::library_scanner::{
+    is_video, queue_metadata_jobs, remove_empty_self_closing_tags, title_and_year, LibraryScanner,
+};
+use crate::ignore_rules::IgnoreRules;
+use crate::models::{File, FileSource, FileType, InsertableExtra, InsertableMedia, Library};
 use crate::nfo::Nfo;
+use crate::people;
 use crate::repositories;
 use crate::state::AppState;
 use async_trait::async_trait;
 use std::path::Path;
 use tokio::fs;
-use tokio::io::AsyncReadExt;
 
 pub struct MovieScanner;
 
@@ -19,89 +29,68 @@ impl LibraryScanner for MovieScanner {
         library: Library,
         folder_path: &Path,
     ) -> Result<(), anyhow::Error> {
+        let ignore_rules = IgnoreRules::for_dir(&library, folder_path).await?;
+
         let mut dir = fs::read_dir(folder_path).await?;
 
-        let (video_file, nfo_file, poster_file, logo_file, background_file, thumbnail_file) = {
+        let (video_file, nfo_file, artwork, extras) = {
             let mut video_file = None;
             let mut nfo_file = None;
-            let mut poster_file = false;
-            let mut logo_file = false;
-            let mut background_file = false;
-            let mut thumbnail_file = false;
+            let mut artwork = ArtworkCandidates::default();
+            let mut extras = Vec::new();
 
             while let Some(entry) = dir.next_entry().await? {
-                if video_file.is_some()
-                    && nfo_file.is_some()
-                    && poster_file
-                    && logo_file
-                    && background_file
-                    && thumbnail_file
-                {
-                    break;
-                }
-
                 let path = entry.path();
 
-                if nfo_file.is_none() {
-                    if let Some(extension) = path.extension() {
-                        if extension == "nfo" {
-                            nfo_file = Some(path);
-                            continue;
-                        }
-                    }
+                if ignore_rules.is_ignored(&path, path.is_dir()) {
+                    continue;
                 }
 
-                if video_file.is_none() {
-                    if let Ok(mut open) = fs::File::open(path.clone()).await {
-                        let mut buffer = [0; 8192];
-                        if let Ok(bytes_read) = open.read(&mut buffer[..]).await {
-                            if bytes_read > 0 {
-                                if let Some(kind) = infer::get(&buffer[..bytes_read]) {
-                                    if kind.mime_type().starts_with("video/") {
-                                        video_file = Some(path);
-                                        continue;
-                                    }
-                                }
-                            }
-                        }
+                if path.is_dir() {
+                    if let Some(kind) = extra_folder_kind(&path) {
+                        let folder_ignore_rules = IgnoreRules::for_dir(&library, &path).await?;
+                        extras.extend(scan_extra_folder(&path, kind, &folder_ignore_rules).await?);
                     }
+                    continue;
                 }
 
-                if !poster_file || !logo_file || !background_file || !thumbnail_file {
-                    if let Some(file_name) = path.file_name() {
-                        match file_name.to_str().unwrap() {
-                            "poster.webp" => {
-                                poster_file = true;
-                                continue;
-                            }
-                            "logo.webp" => {
-                                logo_file = true;
-                                continue;
-                            }
-                            "background.webp" => {
-                                background_file = true;
-                                continue;
-                            }
-                            "thumbnail.webp" => {
-                                thumbnail_file = true;
-                                continue;
-                            }
-                            _ => {
-                                continue;
-                            }
-                        }
+                if !path.is_file() {
+                    continue;
+                }
+
+                if nfo_file.is_none()
+                    && let Some(extension) = path.extension()
+                    && extension == "nfo"
+                {
+                    nfo_file = Some(path);
+                    continue;
+                }
+
+                if let Some(local_artwork) = local_artwork(&path) {
+                    if local_artwork.season.is_none() {
+                        artwork.insert(
+                            &local_artwork,
+                            entry.file_name().to_str().unwrap().to_string(),
+                        );
                     }
+                    continue;
+                }
+
+                if !is_video(&path).await {
+                    continue;
+                }
+
+                if let Some((kind, title)) = extra_file_kind(&path) {
+                    extras.push(LocalExtra { kind, title, path });
+                    continue;
+                }
+
+                if video_file.is_none() {
+                    video_file = Some(path);
                 }
             }
 
-            (
-                video_file,
-                nfo_file,
-                poster_file,
-                logo_file,
-                background_file,
-                thumbnail_file,
-            )
+            (video_file, nfo_file, artwork, extras)
         };
 
         let Some(video_file) = video_file else {
@@ -110,68 +99,109 @@ impl LibraryScanner for MovieScanner {
             ));
         };
 
-        let Some(nfo_file) = nfo_file else {
-            return Err(anyhow::Error::msg(
-                "No nfo file found in folder".to_string(),
-            ));
+        let has_nfo = nfo_file.is_some();
+        let mut media = match nfo_file {
+            Some(nfo_file) => {
+                let nfo_string =
+                    remove_empty_self_closing_tags(fs::read_to_string(nfo_file).await?.as_str());
+                let nfo: Nfo = quick_xml::de::from_str(nfo_string.as_str())?;
+                let mut media = InsertableMedia::from(nfo);
+                media.translate(library.preferred_languages.first());
+                media
+            }
+            None => {
+                let (title, year) =
+                    title_and_year(folder_path.file_name().unwrap().to_str().unwrap());
+                InsertableMedia::from_file_name(title, year)
+            }
         };
-
-        let nfo_string =
-            remove_empty_self_closing_tags(fs::read_to_string(nfo_file).await?.as_str());
-        let nfo: Nfo = quick_xml::de::from_str(nfo_string.as_str())?;
-
-        let mut media = InsertableMedia::from(nfo);
         media.type_.clone_from(&library.media_type);
         media.library_id = library.id;
         media.path = Some(folder_path.to_str().unwrap().to_string());
 
         media.files.as_mut().push(File {
             type_: FileType::Video,
-            path: video_file.file_name().unwrap().to_str().unwrap().to_string(),
+            path: video_file
+                .file_name()
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_string(),
             blur_hash: None,
+            size: None,
+            modified: None,
+            source: FileSource::Library,
         });
 
-        if poster_file {
+        for (file_type, path) in artwork.into_files() {
             media.files.as_mut().push(File {
-                type_: FileType::Poster,
-                path: "poster.webp".to_string(),
+                type_: file_type,
+                path,
                 blur_hash: None,
+                size: None,
+                modified: None,
+                source: FileSource::Library,
             });
         }
 
-        if logo_file {
-            media.files.as_mut().push(File {
-                type_: FileType::Logo,
-                path: "logo.webp".to_string(),
-                blur_hash: None,
-            });
-        }
+        media
+            .files
+            .as_mut()
+            .extend(find_themes(&library, folder_path).await?);
 
-        if background_file {
-            media.files.as_mut().push(File {
-                type_: FileType::Background,
-                path: "background.webp".to_string(),
-                blur_hash: None,
-            });
-        }
+        media.update_metadata_status();
 
-        if thumbnail_file {
-            media.files.as_mut().push(File {
-                type_: FileType::Thumbnail,
-                path: "thumbnail.webp".to_string(),
-                blur_hash: None,
-            });
-        }
+        let existing = {
+            let mut connection = state.pool.get().await?;
+            repositories::media::find_by_path_and_parent_id(
+                &mut connection,
+                media.path.clone(),
+                None,
+            )
+            .await?
+        };
+
+        let previous_files = existing
+            .as_ref()
+            .map(|e| e.files.0.clone())
+            .unwrap_or_default();
+        keep_metadata_files(
+            media.files.as_mut(),
+            &previous_files,
+            existing
+                .as_ref()
+                .map(|e| e.locked_fields.as_slice())
+                .unwrap_or_default(),
+        );
+        fill_blur_hashes(folder_path, media.files.as_mut(), &previous_files).await;
 
         let media = {
             let mut connection = state.pool.get().await?;
-            repositories::media::create(&mut connection, &media).await?
+
+            let media = match existing {
+                Some(mut existing) => {
+                    if has_nfo {
+                        existing.apply(&media);
+                    } else {
+                        existing.files = media.files.clone();
+                    }
+                    repositories::media::update(&mut connection, &existing).await?
+                }
+                None => repositories::media::create(&mut connection, &media).await?,
+            };
+
+            let extras: Vec<InsertableExtra> = extras
+                .into_iter()
+                .map(|extra| extra.into_insertable(media.id))
+                .collect();
+            repositories::extra::replace_for_media(&mut connection, media.id, &extras).await?;
+
+            media
         };
 
-        state.queue.send(Box::new(FetchArtwork::new(
-            state.clone(),
-            FetchArtworkPayload::new(media.id),
-        )))?;
+        collections::sync_media(&state, &media).await?;
+        people::sync_credits(&state, &media).await?;
+        queue_metadata_jobs(&state, &media)?;
 
         Ok(())
     }
@@ -0,0 +1,107 @@
+use crate::factories::library_scanner::is_video;
+use crate::ignore_rules::IgnoreRules;
+use crate::models::{File, FileSource, FileType, Library};
+use std::path::Path;
+use tokio::fs;
+
+pub const AUDIO_EXTENSIONS: [&str; 7] = ["mp3", "flac", "ogg", "opus", "m4a", "aac", "wav"];
+
+const THEME_MUSIC_FOLDER: &str = "theme-music";
+
+const THEME_VIDEO_FOLDER: &str = "backdrops";
+
+pub fn is_audio(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| AUDIO_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+fn is_theme_music(path: &Path) -> bool {
+    is_audio(path)
+        && path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| stem.eq_ignore_ascii_case("theme"))
+}
+
+/// A `theme.mp3` next to the media wins over the first song in `theme-music/`.
+pub async fn find_themes(
+    library: &Library,
+    folder_path: &Path,
+) -> Result<Vec<File>, anyhow::Error> {
+    let ignore_rules = IgnoreRules::for_dir(library, folder_path).await?;
+
+    let mut theme_music = None;
+    let mut theme_video = None;
+
+    let mut dir = fs::read_dir(folder_path).await?;
+    while let Some(entry) = dir.next_entry().await? {
+        let path = entry.path();
+
+        if ignore_rules.is_ignored(&path, path.is_dir()) {
+            continue;
+        }
+
+        let file_name = entry.file_name().to_str().unwrap().to_string();
+
+        if path.is_file() && is_theme_music(&path) {
+            theme_music = Some(file_name);
+        } else if path.is_dir() && theme_music.is_none() && file_name == THEME_MUSIC_FOLDER {
+            theme_music = first_in_folder(library, &path, |path| async move { is_audio(&path) })
+                .await?
+                .map(|name| format!("{file_name}/{name}"));
+        } else if path.is_dir() && file_name == THEME_VIDEO_FOLDER {
+            theme_video =
+                first_in_folder(library, &path, |path| async move { is_video(&path).await })
+                    .await?
+                    .map(|name| format!("{file_name}/{name}"));
+        }
+    }
+
+    Ok([
+        (FileType::ThemeMusic, theme_music),
+        (FileType::ThemeVideo, theme_video),
+    ]
+    .into_iter()
+    .filter_map(|(type_, path)| {
+        path.map(|path| File {
+            type_,
+            path,
+            blur_hash: None,
+            size: None,
+            modified: None,
+            source: FileSource::Library,
+        })
+    })
+    .collect())
+}
+
+async fn first_in_folder<F, Fut>(
+    library: &Library,
+    folder_path: &Path,
+    predicate: F,
+) -> Result<Option<String>, anyhow::Error>
+where
+    F: Fn(std::path::PathBuf) -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let ignore_rules = IgnoreRules::for_dir(library, folder_path).await?;
+
+    let mut names = Vec::new();
+
+    let mut dir = fs::read_dir(folder_path).await?;
+    while let Some(entry) = dir.next_entry().await? {
+        let path = entry.path();
+
+        if !path.is_file() || ignore_rules.is_ignored(&path, false) {
+            continue;
+        }
+
+        if predicate(path).await {
+            names.push(entry.file_name().to_str().unwrap().to_string());
+        }
+    }
+
+    names.sort();
+    Ok(names.into_iter().next())
+}
@@ -0,0 +1,494 @@
+use crate::clients::http::HttpClient;
+use crate::clients::tmdb::{TmdbService, TMDB_BASE_URL, TMDB_IMAGE_BASE_URL};
+use crate::clients::tvdb::{TvdbService, TVDB_BASE_URL};
+use crate::config;
+use crate::models::{
+    Credit, EpisodeOrdering, FileType, Media, MediaSet, Person, ProviderIds, Translation,
+};
+use async_trait::async_trait;
+use chrono::{Datelike, NaiveDate};
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tracing::warn;
+
+pub mod tmdb;
+pub mod tvdb;
+
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    pub id: String,
+    pub title: String,
+    pub original_title: Option<String>,
+    pub year: Option<i32>,
+    pub overview: Option<String>,
+    pub poster_url: Option<String>,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MediaMetadata {
+    pub provider_ids: ProviderIds,
+    pub title: String,
+    pub original_title: Option<String>,
+    pub plot: Option<String>,
+    pub tagline: Option<String>,
+    pub genres: Vec<String>,
+    pub premiered: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub status: Option<String>,
+    pub runtime: Option<i32>,
+    pub rating: Option<f64>,
+    pub studios: Vec<String>,
+    pub countries: Vec<String>,
+    pub collection: Option<MediaSet>,
+    pub credits: Vec<Credit>,
+    pub translations: BTreeMap<String, Translation>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EpisodeMetadata {
+    pub provider_ids: ProviderIds,
+    pub season: i32,
+    pub episode: i32,
+    pub title: Option<String>,
+    pub plot: Option<String>,
+    pub aired: Option<NaiveDate>,
+    pub runtime: Option<i32>,
+    pub rating: Option<f64>,
+    pub thumb_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SeasonMetadata {
+    pub season: i32,
+    pub title: Option<String>,
+    pub plot: Option<String>,
+    pub premiered: Option<NaiveDate>,
+    pub poster_url: Option<String>,
+    pub episodes: Vec<EpisodeMetadata>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RemoteImage {
+    pub file_type: FileType,
+    pub url: String,
+    pub language: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PersonMetadata {
+    pub provider_ids: ProviderIds,
+    pub name: String,
+    pub biography: Option<String>,
+    pub birthday: Option<NaiveDate>,
+    pub deathday: Option<NaiveDate>,
+    pub place_of_birth: Option<String>,
+    pub thumb_url: Option<String>,
+}
+
+/// `id`s are the provider's own, as stored under `name()` in `ProviderIds`.
+#[async_trait]
+pub trait MetadataProvider {
+    fn name(&self) -> &'static str;
+
+    async fn search(
+        &self,
+        media_type: &str,
+        title: &str,
+        year: Option<i32>,
+    ) -> Result<Vec<SearchResult>, anyhow::Error>;
+
+    async fn get_movie(&self, id: &str) -> Result<MediaMetadata, anyhow::Error>;
+
+    async fn get_series(&self, id: &str) -> Result<MediaMetadata, anyhow::Error>;
+
+    async fn get_season(
+        &self,
+        series_id: &str,
+        season: i32,
+        ordering: EpisodeOrdering,
+    ) -> Result<SeasonMetadata, anyhow::Error>;
+
+    async fn get_episodes(
+        &self,
+        series_id: &str,
+        ordering: EpisodeOrdering,
+    ) -> Result<Vec<EpisodeMetadata>, anyhow::Error>;
+
+    /// For providers whose seasons are nothing but their episodes, saves fetching them per season.
+    fn season_from_episodes(
+        &self,
+        _season: i32,
+        _episodes: &[EpisodeMetadata],
+    ) -> Option<SeasonMetadata> {
+        None
+    }
+
+    async fn get_collection(&self, id: &str) -> Result<MediaMetadata, anyhow::Error>;
+
+    async fn get_images(
+        &self,
+        media_type: &str,
+        id: &str,
+    ) -> Result<Vec<RemoteImage>, anyhow::Error>;
+
+    async fn get_translations(
+        &self,
+        media_type: &str,
+        id: &str,
+        languages: &[String],
+    ) -> Result<BTreeMap<String, Translation>, anyhow::Error>;
+
+    async fn get_person(&self, id: &str) -> Result<PersonMetadata, anyhow::Error>;
+}
+
+pub struct MetadataProviderFactory {
+    pub metadata_providers: HashMap<String, Box<dyn MetadataProvider + Send + Sync>>,
+}
+
+impl MetadataProviderFactory {
+    pub fn new(http_client: Arc<HttpClient>) -> Self {
+        let mut metadata_providers = HashMap::new();
+
+        match config::secret("TMDB_API_KEY") {
+            Some(api_key) => {
+                let service = TmdbService::new(
+                    http_client.clone(),
+                    std::env::var("TMDB_BASE_URL").unwrap_or(TMDB_BASE_URL.to_string()),
+                    std::env::var("TMDB_IMAGE_BASE_URL").unwrap_or(TMDB_IMAGE_BASE_URL.to_string()),
+                    api_key,
+                );
+                metadata_providers.insert(
+                    ProviderIds::TMDB.to_string(),
+                    Box::new(tmdb::TmdbProvider::new(service))
+                        as Box<dyn MetadataProvider + Send + Sync>,
+                );
+            }
+            None => warn!("TMDB_API_KEY not set, TMDB metadata provider disabled"),
+        }
+
+        match config::secret("TVDB_API_KEY") {
+            Some(api_key) => {
+                let service = TvdbService::new(
+                    http_client,
+                    std::env::var("TVDB_BASE_URL").unwrap_or(TVDB_BASE_URL.to_string()),
+                    api_key,
+                    config::secret("TVDB_PIN"),
+                );
+                metadata_providers.insert(
+                    ProviderIds::TVDB.to_string(),
+                    Box::new(tvdb::TvdbProvider::new(service))
+                        as Box<dyn MetadataProvider + Send + Sync>,
+                );
+            }
+            None => warn!("TVDB_API_KEY not set, TVDB metadata provider disabled"),
+        }
+
+        Self { metadata_providers }
+    }
+
+    pub fn get_provider(&self, name: &str) -> Option<&(dyn MetadataProvider + Send + Sync)> {
+        self.metadata_providers
+            .get(name)
+            .map(std::convert::AsRef::as_ref)
+    }
+}
+
+pub fn rank_search_results(
+    mut results: Vec<SearchResult>,
+    title: &str,
+    year: Option<i32>,
+) -> Vec<SearchResult> {
+    let rank = |result: &SearchResult| {
+        let year_matches = year.is_some() && result.year == year;
+        (!title_matches(result, title), !year_matches)
+    };
+
+    results.sort_by(|a, b| rank(a).cmp(&rank(b)).then(b.score.total_cmp(&a.score)));
+    results
+}
+
+pub fn best_match(
+    results: Vec<SearchResult>,
+    title: &str,
+    year: Option<i32>,
+) -> Option<SearchResult> {
+    rank_search_results(results, title, year)
+        .into_iter()
+        .next()
+        .filter(|result| title_matches(result, title))
+        .filter(|result| year.is_none() || result.year == year)
+}
+
+fn title_matches(result: &SearchResult, title: &str) -> bool {
+    result.title.eq_ignore_ascii_case(title)
+        || result
+            .original_title
+            .as_ref()
+            .is_some_and(|original_title| original_title.eq_ignore_ascii_case(title))
+}
+
+const PROVIDER_ATTRIBUTES: [&str; 14] = [
+    "title",
+    "originalTitle",
+    "plot",
+    "tagline",
+    "genre",
+    "premiered",
+    "aired",
+    "year",
+    "endDate",
+    "status",
+    "runtime",
+    "rating",
+    "studio",
+    "country",
+];
+
+pub fn clear(media: &mut Media) {
+    clear_values(media);
+
+    if let Some(attributes) = media.attributes.as_object_mut() {
+        attributes.remove("nfoId");
+    }
+
+    if !media.is_locked("providerIds") {
+        media.provider_ids = diesel_json::Json::new(ProviderIds::default());
+    }
+}
+
+pub fn clear_values(media: &mut Media) {
+    if let Some(attributes) = media.attributes.as_object_mut() {
+        for key in PROVIDER_ATTRIBUTES {
+            if !media.locked_fields.iter().any(|field| field == key) {
+                attributes.remove(key);
+            }
+        }
+    }
+
+    if !media.is_locked("credits") {
+        media.credits.0.clear();
+    }
+    if !media.is_locked("collection") {
+        media.collection = None;
+    }
+    media.translations.clear();
+}
+
+fn fill_attribute(media: &mut Media, key: &str, value: serde_json::Value) {
+    if value.is_null() || media.is_locked(key) {
+        return;
+    }
+
+    let attributes = &mut media.attributes;
+    if !attributes.is_object() {
+        *attributes = json!({});
+    }
+
+    let attributes = attributes.as_object_mut().unwrap();
+    if attributes.get(key).is_none_or(serde_json::Value::is_null) {
+        attributes.insert(key.to_string(), value);
+    }
+}
+
+fn fill_title(media: &mut Media, title: Option<&String>) {
+    if let Some(title) = title
+        && !media.is_locked("title")
+        && !media.is_locked("sortTitle")
+        && media
+            .attributes
+            .get("title")
+            .is_none_or(serde_json::Value::is_null)
+    {
+        media.title.clone_from(title);
+    }
+}
+
+fn fill_provider_ids(media: &mut Media, provider_ids: &ProviderIds) {
+    if media.is_locked("providerIds") {
+        return;
+    }
+
+    for (provider, id) in provider_ids.iter() {
+        if media.provider_ids.get(provider).is_none() {
+            media.provider_ids.insert(provider, id.clone());
+        }
+    }
+}
+
+impl MediaMetadata {
+    pub fn fill(&self, media: &mut Media) {
+        fill_provider_ids(media, &self.provider_ids);
+
+        fill_title(media, Some(&self.title));
+
+        fill_attribute(media, "title", json!(self.title));
+        fill_attribute(media, "originalTitle", json!(self.original_title));
+        fill_attribute(media, "plot", json!(self.plot));
+        fill_attribute(media, "tagline", json!(self.tagline));
+        if !self.genres.is_empty() {
+            fill_attribute(media, "genre", json!(self.genres));
+        }
+        fill_attribute(media, "premiered", json!(self.premiered));
+        fill_attribute(media, "year", json!(self.premiered.map(|p| p.year())));
+        fill_attribute(media, "endDate", json!(self.end_date));
+        fill_attribute(media, "status", json!(self.status));
+        fill_attribute(media, "runtime", json!(self.runtime));
+        fill_attribute(media, "rating", json!(self.rating));
+        if !self.studios.is_empty() {
+            fill_attribute(media, "studio", json!(self.studios.join(" / ")));
+        }
+        if !self.countries.is_empty() {
+            fill_attribute(media, "country", json!(self.countries.join(" / ")));
+        }
+
+        if media.credits.is_empty() && !media.is_locked("credits") {
+            media.credits.0.clone_from(&self.credits);
+        }
+
+        if media.collection.is_none() && !media.is_locked("collection") {
+            media.collection = self.collection.clone().map(diesel_json::Json::new);
+        }
+
+        for (language, translation) in &self.translations {
+            media
+                .translations
+                .entry(language.clone())
+                .or_default()
+                .fill(translation);
+        }
+    }
+}
+
+impl SeasonMetadata {
+    pub fn fill(&self, media: &mut Media) {
+        fill_title(media, self.title.as_ref());
+
+        fill_attribute(media, "title", json!(self.title));
+        fill_attribute(media, "plot", json!(self.plot));
+        fill_attribute(media, "premiered", json!(self.premiered));
+        fill_attribute(media, "year", json!(self.premiered.map(|p| p.year())));
+    }
+
+    pub fn episode(&self, episode: i32) -> Option<&EpisodeMetadata> {
+        self.episodes.iter().find(|e| e.episode == episode)
+    }
+}
+
+impl EpisodeMetadata {
+    pub fn fill(&self, media: &mut Media) {
+        fill_provider_ids(media, &self.provider_ids);
+
+        fill_title(media, self.title.as_ref());
+
+        fill_attribute(media, "title", json!(self.title));
+        fill_attribute(media, "plot", json!(self.plot));
+        fill_attribute(media, "aired", json!(self.aired));
+        fill_attribute(media, "runtime", json!(self.runtime));
+        fill_attribute(media, "rating", json!(self.rating));
+    }
+}
+
+impl PersonMetadata {
+    /// The headshot replaces thumbs that are not URLs, like the paths Kodi writes for `.actors`.
+    pub fn fill(&self, person: &mut Person) {
+        for (provider, id) in self.provider_ids.iter() {
+            if person.provider_ids.get(provider).is_none() {
+                person.provider_ids.insert(provider, id.clone());
+            }
+        }
+
+        person.biography = self.biography.clone().or(person.biography.take());
+        person.birthday = self.birthday.or(person.birthday);
+        person.deathday = self.deathday.or(person.deathday);
+        person.place_of_birth = self.place_of_birth.clone().or(person.place_of_birth.take());
+
+        if person
+            .thumb
+            .as_ref()
+            .is_none_or(|thumb| !thumb.starts_with("http"))
+            && self.thumb_url.is_some()
+        {
+            person.thumb.clone_from(&self.thumb_url);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: &str, title: &str, year: Option<i32>, score: f64) -> SearchResult {
+        SearchResult {
+            id: id.to_string(),
+            title: title.to_string(),
+            original_title: None,
+            year,
+            overview: None,
+            poster_url: None,
+            score,
+        }
+    }
+
+    fn ids(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|result| result.id.as_str()).collect()
+    }
+
+    #[test]
+    fn exact_title_and_year_rank_first() {
+        let results = vec![
+            result("1", "Alien: Romulus", Some(2024), 90.0),
+            result("2", "Alien", Some(2023), 50.0),
+            result("3", "alien", Some(1979), 10.0),
+            result("4", "Aliens", Some(1979), 80.0),
+        ];
+
+        let ranked = rank_search_results(results, "Alien", Some(1979));
+
+        assert_eq!(ids(&ranked), ["3", "2", "4", "1"]);
+    }
+
+    #[test]
+    fn ties_are_broken_by_score() {
+        let results = vec![
+            result("1", "The Office", Some(2001), 20.0),
+            result("2", "The Office", Some(2005), 70.0),
+        ];
+
+        let ranked = rank_search_results(results, "The Office", None);
+
+        assert_eq!(ids(&ranked), ["2", "1"]);
+    }
+
+    #[test]
+    fn best_match_accepts_the_original_title() {
+        let mut amelie = result("1", "Amélie", Some(2001), 10.0);
+        amelie.original_title = Some("Le Fabuleux Destin d'Amélie Poulain".to_string());
+
+        let best = best_match(
+            vec![amelie],
+            "le fabuleux destin d'Amélie Poulain",
+            Some(2001),
+        );
+
+        assert_eq!(best.unwrap().id, "1");
+    }
+
+    #[test]
+    fn best_match_requires_title_and_known_year() {
+        let results = || {
+            vec![
+                result("1", "Dune", Some(1984), 40.0),
+                result("2", "Dune: Part Two", Some(2024), 90.0),
+            ]
+        };
+
+        assert!(best_match(results(), "Dune", Some(2021)).is_none());
+        assert!(best_match(results(), "Dune Part Two", None).is_none());
+        assert_eq!(best_match(results(), "Dune", None).unwrap().id, "1");
+    }
+}
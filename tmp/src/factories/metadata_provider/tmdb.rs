@@ -0,0 +1,365 @@
+use crate::clients::tmdb::{
+    TmdbCredits, TmdbImage, TmdbSearchResult, TmdbService, TmdbTranslation,
+};
+use crate::factories::metadata_provider::{
+    EpisodeMetadata, MediaMetadata, MetadataProvider, PersonMetadata, RemoteImage, SearchResult,
+    SeasonMetadata,
+};
+use crate::models::{
+    Credit, CreditRole, EpisodeOrdering, FileType, MediaSet, ProviderIds, Translation,
+};
+use async_trait::async_trait;
+use chrono::{Datelike, NaiveDate};
+use std::collections::BTreeMap;
+
+pub struct TmdbProvider {
+    service: TmdbService,
+}
+
+impl TmdbProvider {
+    pub fn new(service: TmdbService) -> Self {
+        Self { service }
+    }
+
+    fn search_result(&self, result: TmdbSearchResult) -> SearchResult {
+        let date = result.release_date.or(result.first_air_date);
+
+        SearchResult {
+            id: result.id.to_string(),
+            title: result.title.or(result.name).unwrap_or_default(),
+            original_title: result.original_title.or(result.original_name),
+            year: parse_date(date.as_deref()).map(|date| date.year()),
+            overview: result.overview,
+            poster_url: result.poster_path.map(|path| self.service.image_url(&path)),
+            score: result.popularity,
+        }
+    }
+
+    fn credits(&self, credits: TmdbCredits) -> Vec<Credit> {
+        let person_ids = |id: i64| {
+            let mut provider_ids = ProviderIds::default();
+            provider_ids.insert(ProviderIds::TMDB, id.to_string());
+            provider_ids
+        };
+
+        let cast = credits.cast.into_iter().map(|cast| Credit {
+            name: cast.name,
+            role: CreditRole::Actor,
+            character: cast.character,
+            order: cast.order,
+            thumb: cast.profile_path.map(|path| self.service.image_url(&path)),
+            provider_ids: person_ids(cast.id),
+        });
+
+        let crew = credits.crew.into_iter().filter_map(|crew| {
+            let role = match crew.job.as_str() {
+                "Director" => CreditRole::Director,
+                "Screenplay" | "Writer" | "Story" | "Novel" => CreditRole::Writer,
+                "Producer" | "Executive Producer" => CreditRole::Producer,
+                _ => return None,
+            };
+
+            Some(Credit {
+                name: crew.name,
+                role,
+                character: None,
+                order: None,
+                thumb: crew.profile_path.map(|path| self.service.image_url(&path)),
+                provider_ids: person_ids(crew.id),
+            })
+        });
+
+        cast.chain(crew).collect()
+    }
+
+    fn images(&self, file_type: FileType, images: Vec<TmdbImage>) -> Vec<RemoteImage> {
+        images
+            .into_iter()
+            .map(|image| RemoteImage {
+                file_type: file_type.clone(),
+                url: self.service.image_url(&image.file_path),
+                language: image.iso_639_1,
+                width: Some(image.width),
+                height: Some(image.height),
+                score: image.vote_average,
+            })
+            .collect()
+    }
+}
+
+fn parse_date(date: Option<&str>) -> Option<NaiveDate> {
+    date.filter(|date| !date.is_empty())
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.trim().is_empty())
+}
+
+#[async_trait]
+impl MetadataProvider for TmdbProvider {
+    fn name(&self) -> &'static str {
+        ProviderIds::TMDB
+    }
+
+    async fn search(
+        &self,
+        media_type: &str,
+        title: &str,
+        year: Option<i32>,
+    ) -> Result<Vec<SearchResult>, anyhow::Error> {
+        let page = match media_type {
+            "movie" => self.service.search_movie(title, year).await?,
+            "tvshow" => self.service.search_tv(title, year).await?,
+            "collection" => self.service.search_collection(title).await?,
+            _ => {
+                return Err(anyhow::Error::msg(format!(
+                    "TMDB does not support media type {media_type}"
+                )));
+            }
+        };
+
+        Ok(page
+            .results
+            .into_iter()
+            .map(|result| self.search_result(result))
+            .collect())
+    }
+
+    async fn get_movie(&self, id: &str) -> Result<MediaMetadata, anyhow::Error> {
+        let movie = self.service.fetch_movie(id).await?;
+
+        let mut provider_ids = ProviderIds::default();
+        provider_ids.insert(ProviderIds::TMDB, movie.id.to_string());
+        if let Some(imdb_id) = non_empty(movie.imdb_id) {
+            provider_ids.insert(ProviderIds::IMDB, imdb_id);
+        }
+
+        Ok(MediaMetadata {
+            provider_ids,
+            title: movie.title,
+            original_title: movie.original_title,
+            plot: non_empty(movie.overview),
+            tagline: non_empty(movie.tagline),
+            genres: movie.genres.into_iter().map(|genre| genre.name).collect(),
+            premiered: parse_date(movie.release_date.as_deref()),
+            runtime: movie.runtime.filter(|runtime| *runtime > 0),
+            rating: movie.vote_average,
+            studios: movie
+                .production_companies
+                .into_iter()
+                .map(|company| company.name)
+                .collect(),
+            countries: movie
+                .production_countries
+                .into_iter()
+                .map(|country| country.name)
+                .collect(),
+            collection: movie.belongs_to_collection.map(|collection| MediaSet {
+                name: collection.name,
+                overview: None,
+            }),
+            credits: self.credits(movie.credits),
+            ..Default::default()
+        })
+    }
+
+    async fn get_series(&self, id: &str) -> Result<MediaMetadata, anyhow::Error> {
+        let series = self.service.fetch_tv(id).await?;
+
+        let mut provider_ids = ProviderIds::default();
+        provider_ids.insert(ProviderIds::TMDB, series.id.to_string());
+        if let Some(imdb_id) = non_empty(series.external_ids.imdb_id) {
+            provider_ids.insert(ProviderIds::IMDB, imdb_id);
+        }
+        if let Some(tvdb_id) = series.external_ids.tvdb_id {
+            provider_ids.insert(ProviderIds::TVDB, tvdb_id.to_string());
+        }
+
+        let status = series.status.clone();
+        let ended = matches!(status.as_deref(), Some("Ended" | "Canceled"));
+
+        Ok(MediaMetadata {
+            provider_ids,
+            title: series.name,
+            original_title: series.original_name,
+            plot: non_empty(series.overview),
+            tagline: non_empty(series.tagline),
+            genres: series.genres.into_iter().map(|genre| genre.name).collect(),
+            premiered: parse_date(series.first_air_date.as_deref()),
+            end_date: parse_date(series.last_air_date.as_deref()).filter(|_| ended),
+            status,
+            runtime: series.episode_run_time.first().copied(),
+            rating: series.vote_average,
+            studios: series
+                .networks
+                .into_iter()
+                .map(|network| network.name)
+                .collect(),
+            countries: series
+                .production_countries
+                .into_iter()
+                .map(|country| country.name)
+                .collect(),
+            credits: self.credits(series.credits),
+            ..Default::default()
+        })
+    }
+
+    async fn get_season(
+        &self,
+        series_id: &str,
+        season: i32,
+        ordering: EpisodeOrdering,
+    ) -> Result<SeasonMetadata, anyhow::Error> {
+        if ordering != EpisodeOrdering::Aired {
+            return Err(anyhow::Error::msg(format!(
+                "TMDB does not support {} episode ordering",
+                ordering.as_str()
+            )));
+        }
+
+        let season = self.service.fetch_tv_season(series_id, season).await?;
+
+        Ok(SeasonMetadata {
+            season: season.season_number,
+            title: non_empty(season.name),
+            plot: non_empty(season.overview),
+            premiered: parse_date(season.air_date.as_deref()),
+            poster_url: season.poster_path.map(|path| self.service.image_url(&path)),
+            episodes: season
+                .episodes
+                .into_iter()
+                .map(|episode| {
+                    let mut provider_ids = ProviderIds::default();
+                    provider_ids.insert(ProviderIds::TMDB, episode.id.to_string());
+
+                    EpisodeMetadata {
+                        provider_ids,
+                        season: episode.season_number,
+                        episode: episode.episode_number,
+                        title: non_empty(episode.name),
+                        plot: non_empty(episode.overview),
+                        aired: parse_date(episode.air_date.as_deref()),
+                        runtime: episode.runtime,
+                        rating: episode.vote_average,
+                        thumb_url: episode.still_path.map(|path| self.service.image_url(&path)),
+                    }
+                })
+                .collect(),
+        })
+    }
+
+    async fn get_episodes(
+        &self,
+        series_id: &str,
+        ordering: EpisodeOrdering,
+    ) -> Result<Vec<EpisodeMetadata>, anyhow::Error> {
+        let series = self.service.fetch_tv(series_id).await?;
+
+        let mut episodes = Vec::new();
+        for season in series.seasons {
+            episodes.extend(
+                self.get_season(series_id, season.season_number, ordering)
+                    .await?
+                    .episodes,
+            );
+        }
+        Ok(episodes)
+    }
+
+    async fn get_collection(&self, id: &str) -> Result<MediaMetadata, anyhow::Error> {
+        let collection = self.service.fetch_collection(id).await?;
+
+        let mut provider_ids = ProviderIds::default();
+        provider_ids.insert(ProviderIds::TMDB, collection.id.to_string());
+
+        Ok(MediaMetadata {
+            provider_ids,
+            title: collection.name,
+            plot: non_empty(collection.overview),
+            ..Default::default()
+        })
+    }
+
+    async fn get_images(
+        &self,
+        media_type: &str,
+        id: &str,
+    ) -> Result<Vec<RemoteImage>, anyhow::Error> {
+        let images = match media_type {
+            "movie" => self.service.fetch_movie_images(id).await?,
+            "tvshow" => self.service.fetch_tv_images(id).await?,
+            "collection" => self.service.fetch_collection_images(id).await?,
+            _ => {
+                return Err(anyhow::Error::msg(format!(
+                    "TMDB does not support media type {media_type}"
+                )));
+            }
+        };
+
+        let mut remote_images = self.images(FileType::Poster, images.posters);
+        remote_images.extend(self.images(FileType::Background, images.backdrops));
+        remote_images.extend(self.images(FileType::Logo, images.logos));
+        Ok(remote_images)
+    }
+
+    async fn get_translations(
+        &self,
+        media_type: &str,
+        id: &str,
+        languages: &[String],
+    ) -> Result<BTreeMap<String, Translation>, anyhow::Error> {
+        let translations = match media_type {
+            "movie" => self.service.fetch_movie_translations(id).await?,
+            "tvshow" => self.service.fetch_tv_translations(id).await?,
+            "collection" => self.service.fetch_collection_translations(id).await?,
+            _ => {
+                return Err(anyhow::Error::msg(format!(
+                    "TMDB does not support media type {media_type}"
+                )));
+            }
+        };
+
+        // Regional variants (pt-BR, pt-PT) share a language, the first one listed wins
+        let mut by_language = BTreeMap::new();
+        for TmdbTranslation { iso_639_1, data } in translations.translations {
+            if !languages.contains(&iso_639_1) || by_language.contains_key(&iso_639_1) {
+                continue;
+            }
+
+            let translation = Translation {
+                title: non_empty(data.title.or(data.name)),
+                plot: non_empty(data.overview),
+                tagline: non_empty(data.tagline),
+            };
+            if translation != Translation::default() {
+                by_language.insert(iso_639_1, translation);
+            }
+        }
+
+        Ok(by_language)
+    }
+
+    async fn get_person(&self, id: &str) -> Result<PersonMetadata, anyhow::Error> {
+        let person = self.service.fetch_person(id).await?;
+
+        let mut provider_ids = ProviderIds::default();
+        provider_ids.insert(ProviderIds::TMDB, person.id.to_string());
+        if let Some(imdb_id) = non_empty(person.imdb_id) {
+            provider_ids.insert(ProviderIds::IMDB, imdb_id);
+        }
+
+        Ok(PersonMetadata {
+            provider_ids,
+            name: person.name,
+            biography: non_empty(person.biography),
+            birthday: parse_date(person.birthday.as_deref()),
+            deathday: parse_date(person.deathday.as_deref()),
+            place_of_birth: non_empty(person.place_of_birth),
+            thumb_url: person
+                .profile_path
+                .map(|path| self.service.image_url(&path)),
+        })
+    }
+}
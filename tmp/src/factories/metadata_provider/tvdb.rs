@@ -0,0 +1,456 @@
+use crate::clients::tvdb::{TvdbArtwork, TvdbCharacter, TvdbRemoteId, TvdbService};
+use crate::factories::metadata_provider::{
+    EpisodeMetadata, MediaMetadata, MetadataProvider, PersonMetadata, RemoteImage, SearchResult,
+    SeasonMetadata,
+};
+use crate::models::{Credit, CreditRole, EpisodeOrdering, FileType, ProviderIds, Translation};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+const LANGUAGE: &str = "eng";
+
+fn tvdb_language(language: &str) -> Option<&'static str> {
+    Some(match language {
+        "ar" => "ara",
+        "cs" => "ces",
+        "da" => "dan",
+        "de" => "deu",
+        "el" => "ell",
+        "en" => "eng",
+        "es" => "spa",
+        "fi" => "fin",
+        "fr" => "fra",
+        "he" => "heb",
+        "hi" => "hin",
+        "hu" => "hun",
+        "it" => "ita",
+        "ja" => "jpn",
+        "ko" => "kor",
+        "nl" => "nld",
+        "no" => "nor",
+        "pl" => "pol",
+        "pt" => "por",
+        "ru" => "rus",
+        "sv" => "swe",
+        "th" => "tha",
+        "tr" => "tur",
+        "uk" => "ukr",
+        "zh" => "zho",
+        _ => return None,
+    })
+}
+
+pub struct TvdbProvider {
+    service: TvdbService,
+}
+
+impl TvdbProvider {
+    pub fn new(service: TvdbService) -> Self {
+        Self { service }
+    }
+
+    async fn episodes(
+        &self,
+        series_id: &str,
+        ordering: EpisodeOrdering,
+    ) -> Result<Vec<EpisodeMetadata>, anyhow::Error> {
+        let season_type = match ordering {
+            EpisodeOrdering::Aired => "default",
+            EpisodeOrdering::Dvd => "dvd",
+            EpisodeOrdering::Absolute => "absolute",
+        };
+
+        Ok(self
+            .service
+            .fetch_series_episodes(series_id, season_type, LANGUAGE)
+            .await?
+            .into_iter()
+            .map(|episode| {
+                // Absolute ordering puts every regular episode in season 1, specials stay in 0
+                let (season, number) = match ordering {
+                    EpisodeOrdering::Absolute if episode.season_number != 0 => {
+                        (1, episode.absolute_number.unwrap_or(episode.number))
+                    }
+                    _ => (episode.season_number, episode.number),
+                };
+
+                let mut provider_ids = ProviderIds::default();
+                provider_ids.insert(ProviderIds::TVDB, episode.id.to_string());
+
+                EpisodeMetadata {
+                    provider_ids,
+                    season,
+                    episode: number,
+                    title: non_empty(episode.name),
+                    plot: non_empty(episode.overview),
+                    aired: parse_date(episode.aired.as_deref()),
+                    runtime: episode.runtime.filter(|runtime| *runtime > 0),
+                    rating: None,
+                    thumb_url: non_empty(episode.image),
+                }
+            })
+            .collect())
+    }
+}
+
+fn parse_date(date: Option<&str>) -> Option<NaiveDate> {
+    date.filter(|date| !date.is_empty())
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.trim().is_empty())
+}
+
+fn provider_ids(id: i64, remote_ids: &[TvdbRemoteId]) -> ProviderIds {
+    let mut provider_ids = ProviderIds::default();
+    provider_ids.insert(ProviderIds::TVDB, id.to_string());
+
+    for remote_id in remote_ids {
+        match remote_id.source_name.as_str() {
+            "IMDB" => provider_ids.insert(ProviderIds::IMDB, remote_id.id.clone()),
+            "TheMovieDB.com" => provider_ids.insert(ProviderIds::TMDB, remote_id.id.clone()),
+            _ => {}
+        }
+    }
+
+    provider_ids
+}
+
+fn credits(characters: Vec<TvdbCharacter>) -> Vec<Credit> {
+    characters
+        .into_iter()
+        .filter_map(|character| {
+            let role = match character.people_type.as_deref() {
+                Some("Actor" | "Guest Star") => CreditRole::Actor,
+                Some("Director") => CreditRole::Director,
+                Some("Writer") => CreditRole::Writer,
+                Some("Producer" | "Executive Producer") => CreditRole::Producer,
+                _ => return None,
+            };
+
+            let mut provider_ids = ProviderIds::default();
+            if let Some(people_id) = character.people_id {
+                provider_ids.insert(ProviderIds::TVDB, people_id.to_string());
+            }
+
+            Some(Credit {
+                name: character.person_name?,
+                character: non_empty(character.name).filter(|_| role == CreditRole::Actor),
+                role,
+                order: character.sort,
+                thumb: non_empty(character.person_img_url),
+                provider_ids,
+            })
+        })
+        .collect()
+}
+
+/// Maps English to the ISO 639-1 code the other image sources use so it is preferred the same way.
+fn language(language: Option<String>) -> Option<String> {
+    language.map(|language| match language.as_str() {
+        "eng" => "en".to_string(),
+        _ => language,
+    })
+}
+
+fn images(artworks: Vec<TvdbArtwork>, types: &[(i32, FileType)]) -> Vec<RemoteImage> {
+    artworks
+        .into_iter()
+        .filter_map(|artwork| {
+            let (_, file_type) = types.iter().find(|(id, _)| *id == artwork.type_)?;
+
+            Some(RemoteImage {
+                file_type: file_type.clone(),
+                url: artwork.image,
+                language: language(artwork.language),
+                width: Some(artwork.width),
+                height: Some(artwork.height),
+                score: artwork.score,
+            })
+        })
+        .collect()
+}
+
+#[async_trait]
+impl MetadataProvider for TvdbProvider {
+    fn name(&self) -> &'static str {
+        ProviderIds::TVDB
+    }
+
+    async fn search(
+        &self,
+        media_type: &str,
+        title: &str,
+        year: Option<i32>,
+    ) -> Result<Vec<SearchResult>, anyhow::Error> {
+        let type_ = match media_type {
+            "movie" => "movie",
+            "tvshow" => "series",
+            _ => {
+                return Err(anyhow::Error::msg(format!(
+                    "TVDB does not support media type {media_type}"
+                )));
+            }
+        };
+
+        let results = self.service.search(title, type_, year).await?;
+        let count = results.len();
+
+        // Results come sorted by relevance without a score of their own
+        Ok(results
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, result)| {
+                Some(SearchResult {
+                    id: result.tvdb_id?,
+                    title: result.name,
+                    original_title: None,
+                    year: result.year.and_then(|year| year.parse().ok()),
+                    overview: non_empty(result.overview),
+                    poster_url: non_empty(result.image_url),
+                    score: (count - index) as f64,
+                })
+            })
+            .collect())
+    }
+
+    async fn get_movie(&self, id: &str) -> Result<MediaMetadata, anyhow::Error> {
+        let movie = self.service.fetch_movie(id).await?;
+        let translation = self
+            .service
+            .fetch_movie_translation(id, LANGUAGE)
+            .await
+            .ok();
+
+        let (title, plot, tagline) = match translation {
+            Some(translation) => (
+                non_empty(translation.name),
+                non_empty(translation.overview),
+                non_empty(translation.tagline),
+            ),
+            None => (None, None, None),
+        };
+        let title = title.unwrap_or(movie.name.clone());
+
+        Ok(MediaMetadata {
+            provider_ids: provider_ids(movie.id, &movie.remote_ids),
+            original_title: Some(movie.name).filter(|name| *name != title),
+            title,
+            plot,
+            tagline,
+            genres: movie
+                .genres
+                .into_iter()
+                .filter_map(|genre| genre.name)
+                .collect(),
+            premiered: parse_date(
+                movie
+                    .first_release
+                    .and_then(|release| release.date)
+                    .as_deref(),
+            ),
+            runtime: movie.runtime.filter(|runtime| *runtime > 0),
+            studios: movie
+                .studios
+                .into_iter()
+                .filter_map(|studio| studio.name)
+                .collect(),
+            credits: credits(movie.characters),
+            ..Default::default()
+        })
+    }
+
+    async fn get_series(&self, id: &str) -> Result<MediaMetadata, anyhow::Error> {
+        let series = self.service.fetch_series(id).await?;
+        let translation = self
+            .service
+            .fetch_series_translation(id, LANGUAGE)
+            .await
+            .ok();
+
+        let (title, plot) = match translation {
+            Some(translation) => (non_empty(translation.name), non_empty(translation.overview)),
+            None => (None, None),
+        };
+        let title = title.unwrap_or(series.name.clone());
+
+        let status = series.status.and_then(|status| status.name);
+        let ended = status.as_deref() == Some("Ended");
+
+        Ok(MediaMetadata {
+            provider_ids: provider_ids(series.id, &series.remote_ids),
+            original_title: Some(series.name).filter(|name| *name != title),
+            title,
+            plot,
+            tagline: None,
+            genres: series
+                .genres
+                .into_iter()
+                .filter_map(|genre| genre.name)
+                .collect(),
+            premiered: parse_date(series.first_aired.as_deref()),
+            end_date: parse_date(series.last_aired.as_deref()).filter(|_| ended),
+            status,
+            runtime: series.average_runtime.filter(|runtime| *runtime > 0),
+            rating: None,
+            studios: series
+                .original_network
+                .and_then(|network| network.name)
+                .into_iter()
+                .collect(),
+            credits: credits(series.characters),
+            ..Default::default()
+        })
+    }
+
+    async fn get_season(
+        &self,
+        series_id: &str,
+        season: i32,
+        ordering: EpisodeOrdering,
+    ) -> Result<SeasonMetadata, anyhow::Error> {
+        let episodes = self.episodes(series_id, ordering).await?;
+
+        self.season_from_episodes(season, &episodes)
+            .ok_or(anyhow::Error::msg(format!(
+                "TVDB has no season {season} in {} ordering for series {series_id}",
+                ordering.as_str()
+            )))
+    }
+
+    async fn get_episodes(
+        &self,
+        series_id: &str,
+        ordering: EpisodeOrdering,
+    ) -> Result<Vec<EpisodeMetadata>, anyhow::Error> {
+        self.episodes(series_id, ordering).await
+    }
+
+    fn season_from_episodes(
+        &self,
+        season: i32,
+        episodes: &[EpisodeMetadata],
+    ) -> Option<SeasonMetadata> {
+        let episodes: Vec<EpisodeMetadata> = episodes
+            .iter()
+            .filter(|episode| episode.season == season)
+            .cloned()
+            .collect();
+
+        if episodes.is_empty() {
+            return None;
+        }
+
+        Some(SeasonMetadata {
+            season,
+            title: None,
+            plot: None,
+            premiered: episodes.iter().filter_map(|episode| episode.aired).min(),
+            poster_url: None,
+            episodes,
+        })
+    }
+
+    async fn get_collection(&self, id: &str) -> Result<MediaMetadata, anyhow::Error> {
+        Err(anyhow::Error::msg(format!(
+            "TVDB does not support collections, cannot get {id}"
+        )))
+    }
+
+    async fn get_images(
+        &self,
+        media_type: &str,
+        id: &str,
+    ) -> Result<Vec<RemoteImage>, anyhow::Error> {
+        match media_type {
+            "movie" => Ok(images(
+                self.service.fetch_movie(id).await?.artworks,
+                &[
+                    (14, FileType::Poster),
+                    (15, FileType::Background),
+                    (25, FileType::Logo),
+                ],
+            )),
+            "tvshow" => Ok(images(
+                self.service.fetch_series(id).await?.artworks,
+                &[
+                    (2, FileType::Poster),
+                    (3, FileType::Background),
+                    (23, FileType::Logo),
+                ],
+            )),
+            _ => Err(anyhow::Error::msg(format!(
+                "TVDB does not support media type {media_type}"
+            ))),
+        }
+    }
+
+    async fn get_translations(
+        &self,
+        media_type: &str,
+        id: &str,
+        languages: &[String],
+    ) -> Result<BTreeMap<String, Translation>, anyhow::Error> {
+        if media_type != "movie" && media_type != "tvshow" {
+            return Err(anyhow::Error::msg(format!(
+                "TVDB does not support media type {media_type}"
+            )));
+        }
+
+        let mut translations = BTreeMap::new();
+        for language in languages {
+            let Some(tvdb_language) = tvdb_language(language) else {
+                continue;
+            };
+
+            let translation = match media_type {
+                "movie" => {
+                    self.service
+                        .fetch_movie_translation(id, tvdb_language)
+                        .await
+                }
+                _ => {
+                    self.service
+                        .fetch_series_translation(id, tvdb_language)
+                        .await
+                }
+            };
+            let Ok(translation) = translation else {
+                continue;
+            };
+
+            let translation = Translation {
+                title: non_empty(translation.name),
+                plot: non_empty(translation.overview),
+                tagline: non_empty(translation.tagline),
+            };
+            if translation != Translation::default() {
+                translations.insert(language.clone(), translation);
+            }
+        }
+
+        Ok(translations)
+    }
+
+    async fn get_person(&self, id: &str) -> Result<PersonMetadata, anyhow::Error> {
+        let person = self.service.fetch_person(id).await?;
+
+        let biography = person
+            .biographies
+            .into_iter()
+            .find(|biography| biography.language.as_deref() == Some(LANGUAGE))
+            .and_then(|biography| non_empty(biography.biography));
+
+        Ok(PersonMetadata {
+            provider_ids: provider_ids(person.id, &person.remote_ids),
+            name: person.name,
+            biography,
+            birthday: parse_date(person.birth.as_deref()),
+            deathday: parse_date(person.death.as_deref()),
+            place_of_birth: non_empty(person.birth_place),
+            thumb_url: non_empty(person.image),
+        })
+    }
+}
@@ -1,2 +1,3 @@
 pub mod artwork_fetcher;
 pub mod library_scanner;
+pub mod metadata_provider;
@@ -0,0 +1,271 @@
+use crate::models::FileType;
+use image::imageops::FilterType;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::{Output, Stdio};
+use std::time::Duration;
+use tokio::fs;
+use tokio::process::Command;
+use tracing::debug;
+
+/// Fractions of the runtime, the intro is usually over by then and the ending is not spoiled.
+const FRAME_POSITIONS: [f64; 3] = [0.1, 0.15, 0.2];
+const MIN_BRIGHTNESS: f64 = 24.0;
+const MIN_CONTRAST: f64 = 12.0;
+const PROCESS_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Default, Deserialize)]
+struct ProbeOutput {
+    #[serde(default)]
+    streams: Vec<ProbeStream>,
+    format: Option<ProbeFormat>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProbeStream {
+    index: usize,
+    codec_type: Option<String>,
+    #[serde(default)]
+    disposition: HashMap<String, i32>,
+    #[serde(default)]
+    tags: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProbeFormat {
+    duration: Option<String>,
+}
+
+enum Cover {
+    AttachedPicture(usize),
+    Attachment(usize),
+}
+
+pub struct Ffmpeg {
+    ffmpeg: String,
+    ffprobe: String,
+}
+
+impl Ffmpeg {
+    pub fn new(ffmpeg: String, ffprobe: String) -> Self {
+        Self { ffmpeg, ffprobe }
+    }
+
+    async fn probe(&self, path: &Path) -> Result<ProbeOutput, anyhow::Error> {
+        let output = run(Command::new(&self.ffprobe)
+            .args([
+                "-v",
+                "error",
+                "-print_format",
+                "json",
+                "-show_format",
+                "-show_streams",
+            ])
+            .arg(path))
+        .await?;
+
+        if !output.status.success() {
+            return Err(anyhow::Error::msg(format!(
+                "ffprobe failed for {path:?}: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        Ok(serde_json::from_slice(&output.stdout)?)
+    }
+
+    pub async fn extract_covers(
+        &self,
+        path: &Path,
+    ) -> Result<Vec<(FileType, Vec<u8>)>, anyhow::Error> {
+        let probe = self.probe(path).await?;
+
+        let mut covers = Vec::new();
+        for stream in &probe.streams {
+            let cover = match stream.codec_type.as_deref() {
+                Some("video") if stream.disposition.get("attached_pic") == Some(&1) => {
+                    (FileType::Poster, Cover::AttachedPicture(stream.index))
+                }
+                Some("attachment")
+                    if stream
+                        .tags
+                        .get("mimetype")
+                        .is_some_and(|mimetype| mimetype.starts_with("image/")) =>
+                {
+                    let filename = stream
+                        .tags
+                        .get("filename")
+                        .map(|filename| filename.to_lowercase())
+                        .unwrap_or_default();
+                    let file_type = if filename.starts_with("cover_land") {
+                        FileType::Thumbnail
+                    } else if filename.starts_with("cover") {
+                        FileType::Poster
+                    } else {
+                        continue;
+                    };
+                    (file_type, Cover::Attachment(stream.index))
+                }
+                _ => continue,
+            };
+            covers.push(cover);
+        }
+
+        let mut images = Vec::new();
+        for (file_type, cover) in covers {
+            if images.iter().any(|(t, _)| *t == file_type) {
+                continue;
+            }
+
+            let bytes = match cover {
+                Cover::AttachedPicture(index) => self.extract_stream(path, index).await?,
+                Cover::Attachment(index) => self.dump_attachment(path, index).await?,
+            };
+            if !bytes.is_empty() {
+                images.push((file_type, bytes));
+            }
+        }
+
+        Ok(images)
+    }
+
+    async fn extract_stream(&self, path: &Path, index: usize) -> Result<Vec<u8>, anyhow::Error> {
+        self.run_ffmpeg(&[
+            "-i".as_ref(),
+            path.as_os_str(),
+            "-map".as_ref(),
+            format!("0:{index}").as_ref(),
+            "-frames:v".as_ref(),
+            "1".as_ref(),
+            "-c".as_ref(),
+            "copy".as_ref(),
+            "-f".as_ref(),
+            "image2pipe".as_ref(),
+            "pipe:1".as_ref(),
+        ])
+        .await
+    }
+
+    async fn dump_attachment(&self, path: &Path, index: usize) -> Result<Vec<u8>, anyhow::Error> {
+        let target =
+            std::env::temp_dir().join(format!("sfls-attachment-{:016x}", rand::random::<u64>()));
+
+        // ffmpeg complains about the missing output after dumping, the file tells if it worked
+        let result = run(Command::new(&self.ffmpeg)
+            .args(["-v", "error", "-y"])
+            .arg(format!("-dump_attachment:{index}"))
+            .arg(&target)
+            .arg("-i")
+            .arg(path))
+        .await;
+
+        let bytes = fs::read(&target).await.unwrap_or_default();
+        fs::remove_file(&target).await.ok();
+        result?;
+        Ok(bytes)
+    }
+
+    /// The brightest frame is used when all of them are dark.
+    pub async fn extract_frame(&self, path: &Path) -> Result<Option<Vec<u8>>, anyhow::Error> {
+        let probe = self.probe(path).await?;
+        let Some(duration) = probe
+            .format
+            .and_then(|format| format.duration)
+            .and_then(|duration| duration.parse::<f64>().ok())
+            .filter(|duration| *duration > 0.0)
+        else {
+            return Err(anyhow::Error::msg(format!(
+                "Duration of {path:?} is unknown"
+            )));
+        };
+
+        let mut best: Option<(f64, Vec<u8>)> = None;
+        for position in FRAME_POSITIONS {
+            let seconds = format!("{:.3}", duration * position);
+            let frame = self
+                .run_ffmpeg(&[
+                    "-ss".as_ref(),
+                    seconds.as_ref(),
+                    "-i".as_ref(),
+                    path.as_os_str(),
+                    "-frames:v".as_ref(),
+                    "1".as_ref(),
+                    "-f".as_ref(),
+                    "image2pipe".as_ref(),
+                    "-c:v".as_ref(),
+                    "png".as_ref(),
+                    "pipe:1".as_ref(),
+                ])
+                .await?;
+
+            if frame.is_empty() {
+                continue;
+            }
+
+            let (brightness, contrast) = {
+                let frame = frame.clone();
+                tokio::task::spawn_blocking(move || brightness_and_contrast(&frame)).await??
+            };
+            debug!(
+                "Frame at {}s of {:?} has brightness {:.1} and contrast {:.1}",
+                seconds, path, brightness, contrast
+            );
+
+            if brightness >= MIN_BRIGHTNESS && contrast >= MIN_CONTRAST {
+                return Ok(Some(frame));
+            }
+
+            if best.as_ref().is_none_or(|(b, _)| brightness > *b) {
+                best = Some((brightness, frame));
+            }
+        }
+
+        Ok(best.map(|(_, frame)| frame))
+    }
+
+    async fn run_ffmpeg(&self, args: &[&std::ffi::OsStr]) -> Result<Vec<u8>, anyhow::Error> {
+        let output = run(Command::new(&self.ffmpeg).args(["-v", "error"]).args(args)).await?;
+
+        if !output.status.success() {
+            return Err(anyhow::Error::msg(format!(
+                "ffmpeg failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        Ok(output.stdout)
+    }
+}
+
+async fn run(command: &mut Command) -> Result<Output, anyhow::Error> {
+    let program = command.as_std().get_program().to_string_lossy().to_string();
+    tokio::time::timeout(
+        PROCESS_TIMEOUT,
+        command.stdin(Stdio::null()).kill_on_drop(true).output(),
+    )
+    .await
+    .map_err(|_| {
+        anyhow::Error::msg(format!(
+            "{program} timed out after {}s",
+            PROCESS_TIMEOUT.as_secs()
+        ))
+    })?
+    .map_err(|e| anyhow::Error::msg(format!("Failed to run {program}: {e}")))
+}
+
+fn brightness_and_contrast(bytes: &[u8]) -> Result<(f64, f64), anyhow::Error> {
+    let luma = image::load_from_memory(bytes)?
+        .resize(64, 64, FilterType::Triangle)
+        .to_luma8();
+
+    let count = f64::from(luma.width() * luma.height()).max(1.0);
+    let mean = luma.pixels().map(|p| f64::from(p.0[0])).sum::<f64>() / count;
+    let variance = luma
+        .pixels()
+        .map(|p| (f64::from(p.0[0]) - mean).powi(2))
+        .sum::<f64>()
+        / count;
+
+    Ok((mean, variance.sqrt()))
+}
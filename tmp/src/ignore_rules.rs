@@ -0,0 +1,131 @@
+use crate::models::Library;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+pub const IGNORE_FILE_NAME: &str = ".sflsignore";
+
+/// Rules from deeper `.sflsignore` files take precedence, as they do with `.gitignore`.
+pub struct IgnoreRules {
+    matchers: Vec<Gitignore>,
+}
+
+impl IgnoreRules {
+    pub async fn for_dir(library: &Library, dir: &Path) -> Result<Self, anyhow::Error> {
+        let root = Path::new(&library.path);
+
+        let mut matchers = Vec::new();
+
+        let mut builder = GitignoreBuilder::new(root);
+        for pattern in &library.ignore_patterns {
+            builder.add_line(None, pattern)?;
+        }
+        matchers.push(builder.build()?);
+
+        let mut dirs = vec![root.to_path_buf()];
+        if let Ok(relative) = dir.strip_prefix(root) {
+            let mut current = PathBuf::from(root);
+            for component in relative.components() {
+                current.push(component);
+                dirs.push(current.clone());
+            }
+        }
+
+        for dir in dirs {
+            let file = dir.join(IGNORE_FILE_NAME);
+            if !file.is_file() {
+                continue;
+            }
+
+            let mut builder = GitignoreBuilder::new(&dir);
+            for line in fs::read_to_string(&file).await?.lines() {
+                builder.add_line(Some(file.clone()), line)?;
+            }
+            matchers.push(builder.build()?);
+        }
+
+        Ok(Self { matchers })
+    }
+
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if path
+            .file_name()
+            .is_some_and(|file_name| file_name == IGNORE_FILE_NAME)
+        {
+            return true;
+        }
+
+        for matcher in self.matchers.iter().rev() {
+            if !path.starts_with(matcher.path()) {
+                continue;
+            }
+
+            match matcher.matched_path_or_any_parents(path, is_dir) {
+                Match::None => continue,
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn library(ignore_patterns: &[&str], files: &[(&str, &str)]) -> Library {
+        let root = std::env::temp_dir().join(format!("sfls-ignore-{:016x}", rand::random::<u64>()));
+        for (path, content) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).await.unwrap();
+            fs::write(path, content).await.unwrap();
+        }
+        fs::create_dir_all(&root).await.unwrap();
+
+        Library {
+            path: root.to_str().unwrap().to_string(),
+            ignore_patterns: ignore_patterns.iter().map(|p| p.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn library_patterns_apply_below_the_root() {
+        let library = library(&["*.sample.mkv", "Extras/"], &[]).await;
+        let root = Path::new(&library.path);
+        let rules = IgnoreRules::for_dir(&library, &root.join("Movie"))
+            .await
+            .unwrap();
+
+        assert!(rules.is_ignored(&root.join("Movie/movie.sample.mkv"), false));
+        assert!(rules.is_ignored(&root.join("Movie/Extras"), true));
+        assert!(rules.is_ignored(&root.join("Movie/Extras/clip.mkv"), false));
+        assert!(!rules.is_ignored(&root.join("Movie/movie.mkv"), false));
+
+        fs::remove_dir_all(root).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn deeper_ignore_files_take_precedence() {
+        let library = library(
+            &[],
+            &[
+                (IGNORE_FILE_NAME, "*.mkv\n"),
+                ("Show/Season 1/.sflsignore", "!episode.mkv\n"),
+            ],
+        )
+        .await;
+        let root = Path::new(&library.path);
+        let dir = root.join("Show/Season 1");
+        let rules = IgnoreRules::for_dir(&library, &dir).await.unwrap();
+
+        assert!(rules.is_ignored(&dir.join("other.mkv"), false));
+        assert!(!rules.is_ignored(&dir.join("episode.mkv"), false));
+        assert!(rules.is_ignored(&dir.join(IGNORE_FILE_NAME), false));
+
+        fs::remove_dir_all(root).await.unwrap();
+    }
+}
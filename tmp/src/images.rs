@@ -0,0 +1,305 @@
+use image::codecs::avif::AvifEncoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageReader};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{BufWriter, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+pub const DEFAULT_QUALITY: u8 = 80;
+/// rav1e speed, 1 is slowest/smallest and 10 fastest.
+const AVIF_SPEED: u8 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Avif,
+    Webp,
+    Jpeg,
+    Png,
+}
+
+impl FromStr for ImageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "avif" => Ok(Self::Avif),
+            "webp" => Ok(Self::Webp),
+            "jpeg" | "jpg" => Ok(Self::Jpeg),
+            "png" => Ok(Self::Png),
+            _ => Err(format!("Invalid image format: {}", s)),
+        }
+    }
+}
+
+impl ImageFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Avif => "avif",
+            Self::Webp => "webp",
+            Self::Jpeg => "jpeg",
+            Self::Png => "png",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Avif => "image/avif",
+            Self::Webp => "image/webp",
+            Self::Jpeg => "image/jpeg",
+            Self::Png => "image/png",
+        }
+    }
+
+    /// Images with an alpha channel get WebP or PNG instead of JPEG, which has no transparency.
+    pub fn negotiate(accept: Option<&str>, alpha: bool) -> Self {
+        if accepts(accept, Self::Avif.content_type(), false) {
+            Self::Avif
+        } else if !alpha {
+            Self::Jpeg
+        } else if accepts(accept, Self::Webp.content_type(), false) {
+            Self::Webp
+        } else {
+            Self::Png
+        }
+    }
+}
+
+/// Wildcards only count when `wildcards` is set, so `*/*` does not make every client an AVIF one.
+pub fn accepts(accept: Option<&str>, content_type: &str, wildcards: bool) -> bool {
+    let Some(accept) = accept else {
+        return wildcards;
+    };
+
+    accept.split(',').any(|range| {
+        let mut parameters = range.split(';').map(str::trim);
+        let media_range = parameters.next().unwrap_or_default();
+        let rejected = parameters.any(|parameter| {
+            parameter
+                .strip_prefix("q=")
+                .and_then(|q| q.parse::<f32>().ok())
+                .is_some_and(|q| q == 0.0)
+        });
+
+        let matches = media_range.eq_ignore_ascii_case(content_type)
+            || (wildcards && (media_range == "*/*" || media_range == "image/*"));
+        matches && !rejected
+    })
+}
+
+pub async fn has_alpha(source: &Path) -> bool {
+    let source = source.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        ImageReader::open(source)?
+            .with_guessed_format()?
+            .into_decoder()
+            .map(|decoder| decoder.color_type().has_alpha())
+            .map_err(anyhow::Error::from)
+    })
+    .await
+    .ok()
+    .and_then(Result::ok)
+    .unwrap_or_default()
+}
+
+#[derive(Debug, Clone)]
+pub struct ImageOptions {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub quality: u8,
+    pub format: ImageFormat,
+}
+
+pub struct ImageCache {
+    dir: PathBuf,
+    max_size: u64,
+}
+
+impl ImageCache {
+    pub fn new(dir: PathBuf, max_size: u64) -> Self {
+        Self { dir, max_size }
+    }
+
+    pub async fn get_or_create(
+        &self,
+        source: &Path,
+        options: &ImageOptions,
+    ) -> Result<PathBuf, anyhow::Error> {
+        let modified = tokio::fs::metadata(source).await?.modified()?;
+
+        let mut hasher = Sha256::new();
+        hasher.update(source.as_os_str().as_encoded_bytes());
+        hasher.update(
+            modified
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+                .to_le_bytes(),
+        );
+        hasher.update(format!(
+            "{:?} {:?} {}",
+            options.width, options.height, options.quality
+        ));
+        let path = self.dir.join(format!(
+            "{:x}.{}",
+            hasher.finalize(),
+            options.format.as_str()
+        ));
+
+        if path.is_file() {
+            if let Err(e) = fs::File::options()
+                .write(true)
+                .open(&path)
+                .and_then(|file| file.set_modified(SystemTime::now()))
+            {
+                warn!("Failed to touch cached image {:?}: {}", path, e);
+            }
+            return Ok(path);
+        }
+
+        let source = source.to_path_buf();
+        let dir = self.dir.clone();
+        let max_size = self.max_size;
+        let options = options.clone();
+        tokio::task::spawn_blocking(move || {
+            fs::create_dir_all(&dir)?;
+            derive(&source, &path, &options)?;
+            evict(&dir, max_size, &path)?;
+            Ok(path)
+        })
+        .await?
+    }
+}
+
+fn derive(source: &Path, path: &Path, options: &ImageOptions) -> Result<(), anyhow::Error> {
+    let mut image = ImageReader::open(source)?
+        .with_guessed_format()?
+        .decode()
+        .map_err(|e| anyhow::Error::msg(format!("Failed to decode {source:?}: {e}")))?;
+
+    let width = options.width.unwrap_or(u32::MAX);
+    let height = options.height.unwrap_or(u32::MAX);
+    if image.width() > width || image.height() > height {
+        image = image.resize(width, height, FilterType::Lanczos3);
+    }
+
+    // The same image may be requested twice at once
+    let partial = path.with_extension(format!("{:016x}.partial", rand::random::<u64>()));
+    let writer = BufWriter::new(fs::File::create(&partial)?);
+    let result = match options.format {
+        ImageFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(writer, options.quality)),
+        ImageFormat::Webp => DynamicImage::ImageRgba8(image.to_rgba8())
+            .write_with_encoder(WebPEncoder::new_lossless(writer)),
+        ImageFormat::Avif => DynamicImage::ImageRgba8(image.to_rgba8()).write_with_encoder(
+            AvifEncoder::new_with_speed_quality(writer, AVIF_SPEED, options.quality),
+        ),
+        ImageFormat::Png => {
+            DynamicImage::ImageRgba8(image.to_rgba8()).write_with_encoder(PngEncoder::new(writer))
+        }
+    };
+
+    if let Err(e) = result {
+        fs::remove_file(&partial).ok();
+        return Err(anyhow::Error::msg(format!(
+            "Failed to encode {source:?} as {}: {e}",
+            options.format.as_str()
+        )));
+    }
+
+    fs::rename(&partial, path)?;
+    Ok(())
+}
+
+fn evict(dir: &Path, max_size: u64, created: &Path) -> Result<(), anyhow::Error> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_file() && entry.path().extension().is_none_or(|e| e != "partial") {
+            entries.push((metadata.modified()?, metadata.len(), entry.path()));
+        }
+    }
+
+    let mut size: u64 = entries.iter().map(|(_, len, _)| len).sum();
+    if size <= max_size {
+        return Ok(());
+    }
+
+    entries.sort_by_key(|(modified, _, _)| *modified);
+    for (_, len, path) in entries {
+        if size <= max_size {
+            break;
+        }
+        if path == created {
+            continue;
+        }
+
+        info!("Evicting cached image {:?}", path);
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+            _ => size -= len,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_follows_the_accept_header() {
+        let accept = Some("image/avif,image/webp;q=0.9,image/png;q=0,*/*;q=0.8");
+
+        assert!(accepts(accept, "image/avif", false));
+        assert!(accepts(accept, "image/WEBP", false));
+        assert!(!accepts(accept, "image/png", false));
+        assert!(!accepts(accept, "image/jpeg", false));
+        assert!(accepts(accept, "image/jpeg", true));
+    }
+
+    #[test]
+    fn accepts_anything_without_a_header_only_with_wildcards() {
+        assert!(accepts(None, "image/jpeg", true));
+        assert!(!accepts(None, "image/avif", false));
+        assert!(accepts(Some("image/*"), "image/jpeg", true));
+        assert!(!accepts(Some("image/*"), "image/avif", false));
+    }
+
+    #[test]
+    fn negotiate_prefers_avif_then_jpeg() {
+        assert_eq!(
+            ImageFormat::negotiate(Some("image/avif,image/webp,*/*"), false),
+            ImageFormat::Avif
+        );
+        assert_eq!(
+            ImageFormat::negotiate(Some("image/webp,*/*"), false),
+            ImageFormat::Jpeg
+        );
+        assert_eq!(ImageFormat::negotiate(None, false), ImageFormat::Jpeg);
+    }
+
+    #[test]
+    fn negotiate_keeps_transparency() {
+        assert_eq!(
+            ImageFormat::negotiate(Some("image/avif,*/*"), true),
+            ImageFormat::Avif
+        );
+        assert_eq!(
+            ImageFormat::negotiate(Some("image/webp,*/*"), true),
+            ImageFormat::Webp
+        );
+        assert_eq!(ImageFormat::negotiate(Some("*/*"), true), ImageFormat::Png);
+        assert_eq!(ImageFormat::negotiate(None, true), ImageFormat::Png);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::jobs::Job;
+use crate::repositories;
+use crate::state::AppState;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::fs;
+use tracing::info;
+
+/// Entries are keyed by media id, those in the metadata store's `people` folder by person id,
+/// anything else is left alone.
+pub struct CleanCache {
+    pub state: AppState,
+}
+
+impl CleanCache {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Job for CleanCache {
+    async fn run(&self) -> Result<(), anyhow::Error> {
+        info!("Cleaning cache: {:?}", self.state.cache_dir);
+
+        {
+            let mut connection = self.state.pool.get().await?;
+            let removed = repositories::person::delete_uncredited(&mut connection).await?;
+            if removed > 0 {
+                info!("Removed {} people no longer credited", removed);
+            }
+        }
+
+        let mut media_entries = entries(&[
+            self.state.cache_dir.as_path(),
+            self.state.metadata_store.root(),
+        ])
+        .await?;
+        let mut people_entries = entries(&[&self.state.metadata_store.people_root()]).await?;
+
+        let (media_ids, person_ids) = {
+            let mut connection = self.state.pool.get().await?;
+            let media_ids = repositories::media::find_ids_in(
+                &mut connection,
+                media_entries.keys().copied().collect(),
+            )
+            .await?;
+            let person_ids = repositories::person::find_ids_in(
+                &mut connection,
+                people_entries.keys().copied().collect(),
+            )
+            .await?;
+            (media_ids, person_ids)
+        };
+
+        for id in media_ids {
+            media_entries.remove(&id);
+        }
+        for id in person_ids {
+            people_entries.remove(&id);
+        }
+
+        for (id, paths) in media_entries.into_iter().chain(people_entries) {
+            info!("Removing orphaned cache entry for {}", id);
+
+            for path in paths {
+                if path.is_dir() {
+                    fs::remove_dir_all(path).await?;
+                } else {
+                    fs::remove_file(path).await?;
+                }
+            }
+        }
+
+        self.state
+            .http_client
+            .clean_cache(Duration::from_secs(60 * 60 * 24 * 30))
+            .await?;
+
+        info!("Finished cleaning cache: {:?}", self.state.cache_dir);
+        Ok(())
+    }
+}
+
+async fn entries(roots: &[&Path]) -> Result<HashMap<i64, Vec<PathBuf>>, anyhow::Error> {
+    let mut entries: HashMap<i64, Vec<PathBuf>> = HashMap::new();
+    for root in roots {
+        if !root.is_dir() {
+            continue;
+        }
+
+        let mut dir = fs::read_dir(root).await?;
+        while let Some(entry) = dir.next_entry().await? {
+            if let Some(id) = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.parse::<i64>().ok())
+            {
+                entries.entry(id).or_default().push(entry.path());
+            }
+        }
+    }
+
+    Ok(entries)
+}
@@ -0,0 +1,60 @@
+use crate::jobs::export_nfo::{ExportNfo, ExportNfoPayload};
+use crate::jobs::Job;
+use crate::repositories;
+use crate::repositories::media::MediaCriteria;
+use crate::state::AppState;
+use async_trait::async_trait;
+use tracing::info;
+
+pub struct ExportLibraryNfoPayload {
+    pub library_id: i64,
+}
+
+impl ExportLibraryNfoPayload {
+    pub fn new(library_id: i64) -> Self {
+        Self { library_id }
+    }
+}
+
+pub struct ExportLibraryNfo {
+    pub state: AppState,
+    pub payload: ExportLibraryNfoPayload,
+}
+
+impl ExportLibraryNfo {
+    pub fn new(state: AppState, payload: ExportLibraryNfoPayload) -> Self {
+        Self { state, payload }
+    }
+}
+
+#[async_trait]
+impl Job for ExportLibraryNfo {
+    async fn run(&self) -> Result<(), anyhow::Error> {
+        info!("Exporting nfo for library: {}", self.payload.library_id);
+
+        let media = {
+            let mut connection = self.state.pool.get().await?;
+            repositories::media::find_all(
+                &mut connection,
+                MediaCriteria {
+                    library_id: Some(self.payload.library_id),
+                    ..Default::default()
+                },
+            )
+            .await?
+        };
+
+        for media in media {
+            self.state.queue.send(Box::new(ExportNfo::new(
+                self.state.clone(),
+                ExportNfoPayload::new(media.id),
+            )))?;
+        }
+
+        info!(
+            "Finished queueing nfo export for library: {}",
+            self.payload.library_id
+        );
+        Ok(())
+    }
+}
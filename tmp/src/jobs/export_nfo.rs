@@ -0,0 +1,70 @@
+use crate::jobs::Job;
+use crate::nfo;
+use crate::repositories;
+use crate::repositories::media::MediaCriteria;
+use crate::state::AppState;
+use async_trait::async_trait;
+use tracing::info;
+
+pub struct ExportNfoPayload {
+    pub media_id: i64,
+}
+
+impl ExportNfoPayload {
+    pub fn new(media_id: i64) -> Self {
+        Self { media_id }
+    }
+}
+
+pub struct ExportNfo {
+    pub state: AppState,
+    pub payload: ExportNfoPayload,
+}
+
+impl ExportNfo {
+    pub fn new(state: AppState, payload: ExportNfoPayload) -> Self {
+        Self { state, payload }
+    }
+}
+
+#[async_trait]
+impl Job for ExportNfo {
+    async fn run(&self) -> Result<(), anyhow::Error> {
+        info!("Exporting nfo for Media {}", self.payload.media_id);
+
+        let (media, children) = {
+            let mut connection = self.state.pool.get().await?;
+
+            let media = repositories::media::find_by_id(&mut connection, self.payload.media_id)
+                .await?
+                .ok_or(anyhow::Error::msg(format!(
+                    "Media with id {} not found",
+                    self.payload.media_id
+                )))?;
+
+            let children = repositories::media::find_all(
+                &mut connection,
+                MediaCriteria {
+                    parent_id: Some(media.id),
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+            (media, children)
+        };
+
+        if let Some(path) = nfo::write(&media).await? {
+            info!("Wrote nfo for Media {} to {:?}", media.id, path);
+        }
+
+        for child in children {
+            self.state.queue.send(Box::new(ExportNfo::new(
+                self.state.clone(),
+                ExportNfoPayload::new(child.id),
+            )))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+use crate::jobs::generate_blur_hashes::media_folder;
+use crate::jobs::Job;
+use crate::models::{FileType, Media};
+use crate::repositories;
+use crate::state::AppState;
+use async_trait::async_trait;
+use axum::body::Bytes;
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+pub struct ExtractArtworkPayload {
+    pub media_id: i64,
+}
+
+impl ExtractArtworkPayload {
+    pub fn new(media_id: i64) -> Self {
+        Self { media_id }
+    }
+}
+
+/// Episodes also use the thumbnail as their poster, like the scanner does with `-thumb` images.
+pub struct ExtractArtwork {
+    pub state: AppState,
+    pub payload: ExtractArtworkPayload,
+}
+
+impl ExtractArtwork {
+    pub fn new(state: AppState, payload: ExtractArtworkPayload) -> Self {
+        Self { state, payload }
+    }
+
+    fn is_missing(media: &Media, file_type: &FileType) -> bool {
+        !media.is_locked(file_type.as_str())
+            && !media.files.iter().any(|file| file.type_ == *file_type)
+    }
+
+    async fn save(
+        &self,
+        media: &mut Media,
+        file_type: FileType,
+        bytes: Bytes,
+    ) -> Result<(), anyhow::Error> {
+        let file = self
+            .state
+            .metadata_store
+            .save_image(media.id, file_type, bytes)
+            .await?;
+        media.files.as_mut().push(file);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Job for ExtractArtwork {
+    async fn run(&self) -> Result<(), anyhow::Error> {
+        let mut connection = self.state.pool.get().await?;
+        let mut media = repositories::media::find_by_id(&mut connection, self.payload.media_id)
+            .await?
+            .ok_or(anyhow::Error::msg(format!(
+                "Media with id {} not found",
+                self.payload.media_id
+            )))?;
+
+        let Some(video) = media
+            .files
+            .iter()
+            .find(|file| file.type_ == FileType::Video)
+            .map(|file| file.path.clone())
+        else {
+            return Ok(());
+        };
+
+        let file_types = match media.episode {
+            Some(_) => vec![FileType::Thumbnail],
+            None => vec![FileType::Poster, FileType::Thumbnail],
+        };
+        if !file_types
+            .iter()
+            .any(|file_type| Self::is_missing(&media, file_type))
+        {
+            return Ok(());
+        }
+
+        let mut ancestors = HashMap::new();
+        let mut parent_id = media.parent_id;
+        while let Some(id) = parent_id {
+            let parent = repositories::media::find_by_id(&mut connection, id)
+                .await?
+                .ok_or(anyhow::Error::msg(format!("Media with id {id} not found")))?;
+            parent_id = parent.parent_id;
+            ancestors.insert(id, parent);
+        }
+        drop(connection);
+
+        let path = media_folder(&media, &ancestors).join(video);
+        info!("Extracting artwork for Media {} from {:?}", media.id, path);
+
+        for (file_type, bytes) in self.state.ffmpeg.extract_covers(&path).await? {
+            if Self::is_missing(&media, &file_type) {
+                self.save(&mut media, file_type, Bytes::from(bytes)).await?;
+            }
+        }
+
+        if Self::is_missing(&media, &FileType::Thumbnail) {
+            match self.state.ffmpeg.extract_frame(&path).await? {
+                Some(frame) => {
+                    let frame = Bytes::from(frame);
+                    self.save(&mut media, FileType::Thumbnail, frame.clone())
+                        .await?;
+
+                    // Stored twice so either can be replaced or reset on its own
+                    if media.episode.is_some() && Self::is_missing(&media, &FileType::Poster) {
+                        self.save(&mut media, FileType::Poster, frame).await?;
+                    }
+                }
+                None => warn!("No frame could be extracted from {:?}", path),
+            }
+        }
+
+        {
+            let mut connection = self.state.pool.get().await?;
+            repositories::media::update(&mut connection, &media).await?;
+        }
+
+        info!("Finished extracting artwork for Media {}", media.id);
+        Ok(())
+    }
+}
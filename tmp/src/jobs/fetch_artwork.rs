@@ -1,4 +1,7 @@
+use crate::factories::artwork_fetcher::season::fetch_season_artwork;
+use crate::jobs::extract_artwork::{ExtractArtwork, ExtractArtworkPayload};
 use crate::jobs::Job;
+use crate::models::FileType;
 use crate::repositories;
 use crate::state::AppState;
 use tracing::info;
@@ -39,13 +42,35 @@ impl Job for FetchArtwork {
                 self.payload.media_id
             )))?;
 
-        self.state
+        drop(connection);
+
+        let artwork_fetcher = self
+            .state
             .artwork_fetcher_factory
             .artwork_fetchers
-            .get(&media.type_)
-            .ok_or(anyhow::Error::msg("Unknown media type"))?
-            .fetch_artwork(self.state.clone(), media.id)
-            .await?;
+            .get(&media.type_);
+
+        let result = if media.parent_id.is_some() {
+            fetch_season_artwork(&self.state, media.id).await
+        } else if let Some(artwork_fetcher) = artwork_fetcher {
+            artwork_fetcher
+                .fetch_artwork(self.state.clone(), media.id)
+                .await
+        } else {
+            info!(
+                "No artwork fetcher configured for {}, skipping Media {}",
+                media.type_, media.id
+            );
+            Ok(())
+        };
+
+        if media.files.iter().any(|file| file.type_ == FileType::Video) {
+            self.state.queue.send(Box::new(ExtractArtwork::new(
+                self.state.clone(),
+                ExtractArtworkPayload::new(media.id),
+            )))?;
+        }
+        result?;
 
         info!(
             "Finished downloading artwork for media: {}",
@@ -0,0 +1,478 @@
+use crate::collections;
+use crate::factories::artwork_fetcher::rank_images;
+use crate::factories::metadata_provider::{self, EpisodeMetadata, MetadataProvider};
+use crate::jobs::extract_artwork::{ExtractArtwork, ExtractArtworkPayload};
+use crate::jobs::fetch_artwork::{FetchArtwork, FetchArtworkPayload};
+use crate::jobs::refresh_episodes::fetch_episode_list;
+use crate::jobs::Job;
+use crate::models::{EpisodeOrdering, FileType, Library, Media, MediaAttributes, MetadataStatus};
+use crate::people;
+use crate::repositories;
+use crate::repositories::media::MediaCriteria;
+use crate::state::AppState;
+use async_trait::async_trait;
+use chrono::Utc;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use tracing::{info, warn};
+
+pub struct FetchMetadataPayload {
+    pub media_id: i64,
+    pub refresh: bool,
+    pub episodes: Option<Vec<EpisodeMetadata>>,
+}
+
+impl FetchMetadataPayload {
+    pub fn new(media_id: i64) -> Self {
+        Self {
+            media_id,
+            refresh: false,
+            episodes: None,
+        }
+    }
+
+    pub fn refresh(media_id: i64) -> Self {
+        Self {
+            media_id,
+            refresh: true,
+            episodes: None,
+        }
+    }
+}
+
+pub struct FetchMetadata {
+    pub state: AppState,
+    pub payload: FetchMetadataPayload,
+}
+
+impl FetchMetadata {
+    pub fn new(state: AppState, payload: FetchMetadataPayload) -> Self {
+        Self { state, payload }
+    }
+
+    async fn find_media(&self, media_id: i64) -> Result<Media, anyhow::Error> {
+        let mut connection = self.state.pool.get().await?;
+        repositories::media::find_by_id(&mut connection, media_id)
+            .await?
+            .ok_or(anyhow::Error::msg(format!(
+                "Media with id {} not found",
+                media_id
+            )))
+    }
+
+    async fn find_library(&self, library_id: i64) -> Result<Library, anyhow::Error> {
+        let mut connection = self.state.pool.get().await?;
+        repositories::library::find_by_id(&mut connection, library_id)
+            .await?
+            .ok_or(anyhow::Error::msg(format!(
+                "Library with id {} not found",
+                library_id
+            )))
+    }
+
+    async fn find_children(&self, media_id: i64) -> Result<Vec<Media>, anyhow::Error> {
+        let mut connection = self.state.pool.get().await?;
+        Ok(repositories::media::find_all(
+            &mut connection,
+            MediaCriteria {
+                parent_id: Some(media_id),
+                ..Default::default()
+            },
+        )
+        .await?)
+    }
+
+    async fn save(&self, media: &Media) -> Result<(), anyhow::Error> {
+        let mut connection = self.state.pool.get().await?;
+        repositories::media::update(&mut connection, media).await?;
+        Ok(())
+    }
+
+    fn prepare_fill(&self, media: &mut Media) {
+        if self.payload.refresh && !media.has_nfo() {
+            metadata_provider::clear_values(media);
+        }
+        media.metadata_refreshed_at = Some(Utc::now().naive_utc());
+    }
+
+    async fn fetch_item(
+        &self,
+        provider: &(dyn MetadataProvider + Send + Sync),
+        mut media: Media,
+        languages: &[String],
+        ordering: EpisodeOrdering,
+    ) -> Result<(), anyhow::Error> {
+        let id = match media.provider_ids.get(provider.name()) {
+            Some(id) => id.clone(),
+            None => {
+                let attributes = MediaAttributes::from(&media.attributes);
+                let title = attributes.title.unwrap_or(media.title.clone());
+
+                let results = provider
+                    .search(&media.type_, &title, attributes.year)
+                    .await?;
+
+                let Some(result) = metadata_provider::best_match(results, &title, attributes.year)
+                else {
+                    warn!(
+                        "No {} match found for Media {} ({})",
+                        provider.name(),
+                        media.id,
+                        title
+                    );
+                    media.metadata_status = MetadataStatus::Unmatched.as_str().to_string();
+                    media.metadata_refreshed_at = Some(Utc::now().naive_utc());
+                    self.save(&media).await?;
+                    return self.queue_artwork_jobs(&media).await;
+                };
+
+                info!(
+                    "Matched Media {} ({}) to {} {} ({})",
+                    media.id,
+                    title,
+                    provider.name(),
+                    result.id,
+                    result.title
+                );
+                result.id
+            }
+        };
+
+        let mut metadata = match media.type_.as_str() {
+            "movie" => provider.get_movie(&id).await?,
+            "tvshow" => provider.get_series(&id).await?,
+            "collection" => provider.get_collection(&id).await?,
+            type_ => {
+                return Err(anyhow::Error::msg(format!("Unknown media type {type_}")));
+            }
+        };
+
+        metadata.translations = provider
+            .get_translations(&media.type_, &id, languages)
+            .await
+            .unwrap_or_else(|e| {
+                warn!(
+                    "Failed to fetch {} translations for Media {}: {}",
+                    provider.name(),
+                    media.id,
+                    e
+                );
+                BTreeMap::new()
+            });
+
+        self.prepare_fill(&mut media);
+        metadata.fill(&mut media);
+        media.metadata_status = MetadataStatus::Complete.as_str().to_string();
+        self.save(&media).await?;
+        collections::sync_media(&self.state, &media).await?;
+        people::sync_credits(&self.state, &media).await?;
+
+        let episodes = if media.type_ == "tvshow" {
+            fetch_episode_list(&self.state, provider, &id, media.id, ordering)
+                .await
+                .inspect_err(|e| {
+                    warn!(
+                        "Failed to fetch {} episodes for Media {}: {}",
+                        provider.name(),
+                        media.id,
+                        e
+                    )
+                })
+                .ok()
+        } else {
+            None
+        };
+
+        if let Some(artwork_fetcher) = self
+            .state
+            .artwork_fetcher_factory
+            .artwork_fetchers
+            .get(&media.type_)
+            && let Err(e) = artwork_fetcher
+                .fetch_artwork(self.state.clone(), media.id)
+                .await
+        {
+            warn!("Failed to fetch artwork for Media {}: {}", media.id, e);
+        }
+
+        if let Err(e) = self
+            .fetch_missing_images(provider, &id, media.id, languages)
+            .await
+        {
+            warn!(
+                "Failed to fetch {} images for Media {}: {}",
+                provider.name(),
+                media.id,
+                e
+            );
+        }
+
+        self.queue_artwork_jobs(&media).await?;
+
+        for child in self.find_children(media.id).await? {
+            if self.payload.refresh || child.metadata_status != MetadataStatus::Complete.as_str() {
+                self.state.queue.send(Box::new(FetchMetadata::new(
+                    self.state.clone(),
+                    FetchMetadataPayload {
+                        media_id: child.id,
+                        refresh: self.payload.refresh,
+                        episodes: episodes.as_ref().map(|episodes| {
+                            episodes
+                                .iter()
+                                .filter(|episode| Some(episode.season) == child.season)
+                                .cloned()
+                                .collect()
+                        }),
+                    },
+                )))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn fetch_missing_images(
+        &self,
+        provider: &(dyn MetadataProvider + Send + Sync),
+        id: &str,
+        media_id: i64,
+        languages: &[String],
+    ) -> Result<(), anyhow::Error> {
+        let mut media = self.find_media(media_id).await?;
+
+        let missing: Vec<FileType> = [FileType::Poster, FileType::Background]
+            .into_iter()
+            .filter(|file_type| !media.files.iter().any(|f| f.type_ == *file_type))
+            .collect();
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let images = provider.get_images(&media.type_, id).await?;
+
+        for file_type in missing {
+            let Some(image) = rank_images(
+                images
+                    .iter()
+                    .filter(|image| image.file_type == file_type)
+                    .collect(),
+                &file_type,
+                languages,
+                |image| image.language.as_deref(),
+                |image| image.score,
+            )
+            .into_iter()
+            .next() else {
+                continue;
+            };
+
+            let bytes = self.state.http_client.get_bytes(&image.url).await?;
+
+            let file = self
+                .state
+                .metadata_store
+                .save_image(media.id, file_type, bytes)
+                .await?;
+            media.files.as_mut().push(file);
+        }
+
+        self.save(&media).await
+    }
+
+    async fn series_id(
+        &self,
+        provider: &(dyn MetadataProvider + Send + Sync),
+        media: &Media,
+    ) -> Result<Option<String>, anyhow::Error> {
+        let mut parent_id = media.parent_id;
+        let mut series = None;
+        while let Some(id) = parent_id {
+            let parent = self.find_media(id).await?;
+            parent_id = parent.parent_id;
+            series = Some(parent);
+        }
+
+        Ok(series.and_then(|series| series.provider_ids.get(provider.name()).cloned()))
+    }
+
+    async fn fetch_season(
+        &self,
+        provider: &(dyn MetadataProvider + Send + Sync),
+        mut media: Media,
+        season: i32,
+        ordering: EpisodeOrdering,
+    ) -> Result<(), anyhow::Error> {
+        let Some(series_id) = self.series_id(provider, &media).await? else {
+            info!(
+                "Show of Media {} is not matched to {} yet",
+                media.id,
+                provider.name()
+            );
+            return Ok(());
+        };
+
+        let metadata = match self
+            .payload
+            .episodes
+            .as_deref()
+            .and_then(|episodes| provider.season_from_episodes(season, episodes))
+        {
+            Some(metadata) => metadata,
+            None => provider.get_season(&series_id, season, ordering).await?,
+        };
+
+        self.prepare_fill(&mut media);
+        metadata.fill(&mut media);
+        media.metadata_status = MetadataStatus::Complete.as_str().to_string();
+        self.save(&media).await?;
+
+        for mut episode in self.find_children(media.id).await? {
+            if !self.payload.refresh && episode.metadata_status == MetadataStatus::Complete.as_str()
+            {
+                continue;
+            }
+
+            let Some(episode_metadata) = episode.episode.and_then(|e| metadata.episode(e)) else {
+                warn!(
+                    "No {} metadata for episode {:?} of season {}",
+                    provider.name(),
+                    episode.episode,
+                    season
+                );
+                continue;
+            };
+
+            self.prepare_fill(&mut episode);
+            episode_metadata.fill(&mut episode);
+            episode.metadata_status = MetadataStatus::Complete.as_str().to_string();
+            self.save(&episode).await?;
+        }
+
+        self.queue_artwork_jobs(&media).await
+    }
+
+    async fn fetch_episode(
+        &self,
+        provider: &(dyn MetadataProvider + Send + Sync),
+        mut media: Media,
+        season: i32,
+        episode: i32,
+        ordering: EpisodeOrdering,
+    ) -> Result<(), anyhow::Error> {
+        let Some(series_id) = self.series_id(provider, &media).await? else {
+            info!(
+                "Show of Media {} is not matched to {} yet",
+                media.id,
+                provider.name()
+            );
+            return Ok(());
+        };
+
+        let metadata = provider.get_season(&series_id, season, ordering).await?;
+
+        let Some(episode_metadata) = metadata.episode(episode) else {
+            return Err(anyhow::Error::msg(format!(
+                "No {} metadata for episode {} of season {}",
+                provider.name(),
+                episode,
+                season
+            )));
+        };
+
+        self.prepare_fill(&mut media);
+        episode_metadata.fill(&mut media);
+        media.metadata_status = MetadataStatus::Complete.as_str().to_string();
+        self.save(&media).await?;
+
+        self.queue_artwork_jobs(&media).await
+    }
+
+    /// Movies and shows download theirs in `fetch_item`, so only what is still missing is
+    /// extracted from the video.
+    async fn queue_artwork_jobs(&self, media: &Media) -> Result<(), anyhow::Error> {
+        if media.parent_id.is_none() {
+            if media.files.iter().any(|file| file.type_ == FileType::Video) {
+                self.state.queue.send(Box::new(ExtractArtwork::new(
+                    self.state.clone(),
+                    ExtractArtworkPayload::new(media.id),
+                )))?;
+            }
+            return Ok(());
+        }
+
+        let mut media_ids = vec![media.id];
+        if media.episode.is_none() {
+            media_ids.extend(
+                self.find_children(media.id)
+                    .await?
+                    .into_iter()
+                    .map(|episode| episode.id),
+            );
+        }
+
+        for media_id in media_ids {
+            self.state.queue.send(Box::new(FetchArtwork::new(
+                self.state.clone(),
+                FetchArtworkPayload::new(media_id),
+            )))?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Job for FetchMetadata {
+    async fn run(&self) -> Result<(), anyhow::Error> {
+        info!("Fetching metadata for Media {}", self.payload.media_id);
+
+        let media = self.find_media(self.payload.media_id).await?;
+        let library = self.find_library(media.library_id).await?;
+
+        let Some(provider) = self
+            .state
+            .metadata_provider_factory
+            .get_provider(&library.metadata_provider)
+        else {
+            info!(
+                "Metadata provider {} of Library {} is not configured, skipping Media {}",
+                library.metadata_provider, library.id, self.payload.media_id
+            );
+            return self.queue_artwork_jobs(&media).await;
+        };
+        let ordering =
+            EpisodeOrdering::from_str(&library.episode_ordering).map_err(anyhow::Error::msg)?;
+
+        let result = match (media.parent_id, media.season, media.episode) {
+            (None, _, _) => {
+                self.fetch_item(provider, media, &library.preferred_languages, ordering)
+                    .await
+            }
+            (Some(_), Some(season), Some(episode)) => {
+                self.fetch_episode(provider, media, season, episode, ordering)
+                    .await
+            }
+            (Some(_), Some(season), None) => {
+                self.fetch_season(provider, media, season, ordering).await
+            }
+            (Some(_), None, _) => {
+                return Err(anyhow::Error::msg(format!(
+                    "Media {} has a parent but no season",
+                    media.id
+                )));
+            }
+        };
+
+        if let Err(e) = result {
+            let media = self.find_media(self.payload.media_id).await?;
+            self.queue_artwork_jobs(&media).await?;
+            return Err(e);
+        }
+
+        info!(
+            "Finished fetching metadata for Media {}",
+            self.payload.media_id
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use crate::jobs::Job;
+use crate::repositories;
+use crate::state::AppState;
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel_json::Json;
+use tracing::{info, warn};
+
+pub struct FetchPersonPayload {
+    pub person_id: i64,
+}
+
+impl FetchPersonPayload {
+    pub fn new(person_id: i64) -> Self {
+        Self { person_id }
+    }
+}
+
+pub struct FetchPerson {
+    pub state: AppState,
+    pub payload: FetchPersonPayload,
+}
+
+impl FetchPerson {
+    pub fn new(state: AppState, payload: FetchPersonPayload) -> Self {
+        Self { state, payload }
+    }
+}
+
+#[async_trait]
+impl Job for FetchPerson {
+    async fn run(&self) -> Result<(), anyhow::Error> {
+        let mut person = {
+            let mut connection = self.state.pool.get().await?;
+            repositories::person::find_by_id(&mut connection, self.payload.person_id)
+                .await?
+                .ok_or(anyhow::Error::msg(format!(
+                    "Person with id {} not found",
+                    self.payload.person_id
+                )))?
+        };
+
+        info!("Fetching person {} ({})", person.id, person.name);
+
+        let provider_ids = person.provider_ids.0.clone();
+        for (name, id) in provider_ids.iter() {
+            let Some(provider) = self.state.metadata_provider_factory.get_provider(name) else {
+                continue;
+            };
+
+            match provider.get_person(id).await {
+                Ok(metadata) => {
+                    metadata.fill(&mut person);
+                    break;
+                }
+                Err(e) => warn!(
+                    "Failed to fetch person {} from {}: {:?}",
+                    person.id,
+                    provider.name(),
+                    e
+                ),
+            }
+        }
+
+        if person.headshot.is_none()
+            && let Some(url) = person
+                .thumb
+                .clone()
+                .filter(|thumb| thumb.starts_with("http"))
+        {
+            match self.state.http_client.get_bytes(&url).await {
+                Ok(bytes) => {
+                    let file = self
+                        .state
+                        .metadata_store
+                        .save_headshot(person.id, bytes)
+                        .await?;
+                    person.headshot = Some(Json::new(file));
+                }
+                Err(e) => warn!("Failed to download headshot {}: {:?}", url, e),
+            }
+        }
+
+        person.metadata_refreshed_at = Some(Utc::now().naive_utc());
+        person.updated_at = Utc::now().naive_utc();
+        {
+            let mut connection = self.state.pool.get().await?;
+            repositories::person::update(&mut connection, &person).await?;
+        }
+
+        info!("Finished fetching person {}", person.id);
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use crate::factories::library_scanner::artwork::{fill_blur_hashes, is_image};
+use crate::jobs::Job;
+use crate::models::{FileSource, Media};
+use crate::repositories;
+use crate::state::AppState;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+pub struct GenerateBlurHashes {
+    pub state: AppState,
+}
+
+impl GenerateBlurHashes {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+pub fn media_folder(media: &Media, all: &HashMap<i64, Media>) -> PathBuf {
+    let mut components = vec![media.path.clone()];
+    let mut parent_id = media.parent_id;
+    while let Some(parent) = parent_id.and_then(|id| all.get(&id)) {
+        components.push(parent.path.clone());
+        parent_id = parent.parent_id;
+    }
+
+    components
+        .into_iter()
+        .rev()
+        .flatten()
+        .fold(PathBuf::new(), |acc, component| acc.join(component))
+}
+
+#[async_trait]
+impl Job for GenerateBlurHashes {
+    async fn run(&self) -> Result<(), anyhow::Error> {
+        info!("Generating missing BlurHashes");
+
+        let all: HashMap<i64, Media> = {
+            let mut connection = self.state.pool.get().await?;
+            repositories::media::find_all_unfiltered(&mut connection)
+                .await?
+                .into_iter()
+                .map(|media| (media.id, media))
+                .collect()
+        };
+
+        let mut updated = 0;
+        for media in all.values() {
+            if !media.files.iter().any(|file| {
+                file.source == FileSource::Library
+                    && file.blur_hash.is_none()
+                    && is_image(Path::new(&file.path))
+            }) {
+                continue;
+            }
+
+            let mut connection = self.state.pool.get().await?;
+            let Some(mut current) =
+                repositories::media::find_by_id(&mut connection, media.id).await?
+            else {
+                continue;
+            };
+            drop(connection);
+
+            fill_blur_hashes(&media_folder(media, &all), current.files.as_mut(), &[]).await;
+
+            let mut connection = self.state.pool.get().await?;
+            repositories::media::update(&mut connection, &current).await?;
+            updated += 1;
+        }
+
+        info!("Finished generating BlurHashes for {} media items", updated);
+        Ok(())
+    }
+}
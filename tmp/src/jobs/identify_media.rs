@@ -0,0 +1,156 @@
+use crate::factories::metadata_provider;
+use crate::jobs::fetch_metadata::{FetchMetadata, FetchMetadataPayload};
+use crate::jobs::Job;
+use crate::models::{File, FileSource, Media, MetadataStatus, ProviderIds};
+use crate::repositories;
+use crate::repositories::media::MediaCriteria;
+use crate::state::AppState;
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel_json::Json;
+use tracing::{info, warn};
+
+pub struct IdentifyMediaPayload {
+    pub media_id: i64,
+    pub provider_id: String,
+}
+
+impl IdentifyMediaPayload {
+    pub fn new(media_id: i64, provider_id: String) -> Self {
+        Self {
+            media_id,
+            provider_id,
+        }
+    }
+}
+
+pub struct IdentifyMedia {
+    pub state: AppState,
+    pub payload: IdentifyMediaPayload,
+}
+
+impl IdentifyMedia {
+    pub fn new(state: AppState, payload: IdentifyMediaPayload) -> Self {
+        Self { state, payload }
+    }
+
+    async fn find_children(&self, media_id: i64) -> Result<Vec<Media>, anyhow::Error> {
+        let mut connection = self.state.pool.get().await?;
+        Ok(repositories::media::find_all(
+            &mut connection,
+            MediaCriteria {
+                parent_id: Some(media_id),
+                ..Default::default()
+            },
+        )
+        .await?)
+    }
+
+    async fn reset(&self, mut media: Media) -> Result<Media, anyhow::Error> {
+        metadata_provider::clear(&mut media);
+        media.metadata_status = MetadataStatus::Incomplete.as_str().to_string();
+
+        let locked_fields = &media.locked_fields;
+        let (removed, kept): (Vec<File>, Vec<File>) = std::mem::take(&mut media.files.0)
+            .into_iter()
+            .partition(|file| {
+                file.source == FileSource::Metadata
+                    && !locked_fields
+                        .iter()
+                        .any(|field| field == file.type_.as_str())
+            });
+        media.files.0 = kept;
+
+        let media = {
+            let mut connection = self.state.pool.get().await?;
+            repositories::media::update(&mut connection, &media).await?
+        };
+
+        for file in removed {
+            if !media.files.iter().any(|f| f.path == file.path)
+                && let Err(e) = self.state.metadata_store.delete(&file).await
+            {
+                warn!(
+                    "Failed to delete {} of Media {}: {}",
+                    file.path, media.id, e
+                );
+            }
+        }
+
+        Ok(media)
+    }
+}
+
+#[async_trait]
+impl Job for IdentifyMedia {
+    async fn run(&self) -> Result<(), anyhow::Error> {
+        info!(
+            "Identifying Media {} as {}",
+            self.payload.media_id, self.payload.provider_id
+        );
+
+        let (media, library) = {
+            let mut connection = self.state.pool.get().await?;
+            let media = repositories::media::find_by_id(&mut connection, self.payload.media_id)
+                .await?
+                .ok_or(anyhow::Error::msg(format!(
+                    "Media with id {} not found",
+                    self.payload.media_id
+                )))?;
+            let library = repositories::library::find_by_id(&mut connection, media.library_id)
+                .await?
+                .ok_or(anyhow::Error::msg(format!(
+                    "Library with id {} not found",
+                    media.library_id
+                )))?;
+            (media, library)
+        };
+
+        let mut media = self.reset(media).await?;
+        // Replaced even if locked, they are locked again below
+        media.provider_ids = Json::new(ProviderIds::default());
+        media
+            .provider_ids
+            .insert(&library.metadata_provider, self.payload.provider_id.clone());
+        media.locked_fields.retain(|field| field != "providerIds");
+        {
+            let mut connection = self.state.pool.get().await?;
+            repositories::media::update(&mut connection, &media).await?;
+        }
+
+        for season in self.find_children(media.id).await? {
+            let season = self.reset(season).await?;
+            for episode in self.find_children(season.id).await? {
+                self.reset(episode).await?;
+            }
+        }
+
+        let fetched = FetchMetadata::new(
+            self.state.clone(),
+            FetchMetadataPayload::new(self.payload.media_id),
+        )
+        .run()
+        .await;
+
+        // So the next scan does not put back the ids of the NFO
+        {
+            let mut connection = self.state.pool.get().await?;
+            if let Some(mut media) =
+                repositories::media::find_by_id(&mut connection, self.payload.media_id).await?
+                && !media.is_locked("providerIds")
+            {
+                media.locked_fields.push("providerIds".to_string());
+                media.locked_fields.sort();
+                media.updated_at = Utc::now().naive_utc();
+                repositories::media::update(&mut connection, &media).await?;
+            }
+        }
+        fetched?;
+
+        info!(
+            "Finished identifying Media {} as {}",
+            self.payload.media_id, self.payload.provider_id
+        );
+        Ok(())
+    }
+}
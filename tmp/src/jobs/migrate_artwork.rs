@@ -0,0 +1,157 @@
+use crate::factories::library_scanner::artwork::local_artwork;
+use crate::jobs::generate_blur_hashes::media_folder;
+use crate::jobs::Job;
+use crate::models::{FileSource, Media};
+use crate::repositories;
+use crate::state::AppState;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::collections::HashMap;
+use std::path::Path;
+use tokio::fs;
+use tracing::{info, warn};
+
+const DOWNLOADED_ARTWORK: [&str; 4] = [
+    "poster.webp",
+    "logo.webp",
+    "thumbnail.webp",
+    "background.webp",
+];
+
+const DATA_MIGRATION: &str = "migrate_artwork";
+
+pub struct MigrateArtwork {
+    pub state: AppState,
+}
+
+impl MigrateArtwork {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+}
+
+#[async_trait]
+impl Job for MigrateArtwork {
+    async fn run(&self) -> Result<(), anyhow::Error> {
+        // Files changed after upgrading can't have been downloaded by an earlier version
+        let upgraded_at = {
+            let mut connection = self.state.pool.get().await?;
+            match repositories::data_migration::find_by_name(&mut connection, DATA_MIGRATION)
+                .await?
+            {
+                Some(data_migration) if data_migration.completed_at.is_none() => {
+                    data_migration.created_at
+                }
+                _ => return Ok(()),
+            }
+        };
+
+        let all: HashMap<i64, Media> = {
+            let mut connection = self.state.pool.get().await?;
+            repositories::media::find_all_unfiltered(&mut connection)
+                .await?
+                .into_iter()
+                .map(|media| (media.id, media))
+                .collect()
+        };
+
+        let mut migrated = 0;
+        for media in all.values() {
+            if !media.files.iter().any(|file| {
+                file.source == FileSource::Library
+                    && DOWNLOADED_ARTWORK.contains(&file.path.as_str())
+            }) {
+                continue;
+            }
+
+            let mut connection = self.state.pool.get().await?;
+            let Some(mut current) =
+                repositories::media::find_by_id(&mut connection, media.id).await?
+            else {
+                continue;
+            };
+            drop(connection);
+
+            let folder = media_folder(media, &all);
+            let mut changed = false;
+            for file in current.files.as_mut().iter_mut() {
+                if file.source != FileSource::Library
+                    || !DOWNLOADED_ARTWORK.contains(&file.path.as_str())
+                {
+                    continue;
+                }
+
+                let source = folder.join(&file.path);
+                let bytes = match downloaded_artwork(&folder, &source, upgraded_at).await {
+                    Ok(Some(bytes)) => bytes,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        warn!("Failed to read artwork {:?}: {}", source, e);
+                        continue;
+                    }
+                };
+
+                file.path = self
+                    .state
+                    .metadata_store
+                    .save(current.id, &bytes, "webp")
+                    .await?;
+                file.source = FileSource::Metadata;
+                file.size = None;
+                file.modified = None;
+                changed = true;
+            }
+
+            if changed {
+                let mut connection = self.state.pool.get().await?;
+                repositories::media::update(&mut connection, &current).await?;
+                migrated += 1;
+            }
+        }
+
+        let mut connection = self.state.pool.get().await?;
+        repositories::data_migration::complete(&mut connection, DATA_MIGRATION).await?;
+
+        if migrated > 0 {
+            info!(
+                "Copied downloaded artwork of {} media items to the metadata store",
+                migrated
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Artwork an earlier version downloaded is a WebP image last changed before upgrading, and the
+/// only image of its type in `folder`.
+async fn downloaded_artwork(
+    folder: &Path,
+    source: &Path,
+    upgraded_at: NaiveDateTime,
+) -> Result<Option<Vec<u8>>, anyhow::Error> {
+    let modified: DateTime<Utc> = fs::metadata(source).await?.modified()?.into();
+    if modified.naive_utc() >= upgraded_at {
+        return Ok(None);
+    }
+
+    let Some(artwork) = local_artwork(source) else {
+        return Ok(None);
+    };
+    let mut dir = fs::read_dir(folder).await?;
+    while let Some(entry) = dir.next_entry().await? {
+        let path = entry.path();
+        if path != source
+            && local_artwork(&path)
+                .is_some_and(|other| other.file_type == artwork.file_type && other.season.is_none())
+        {
+            return Ok(None);
+        }
+    }
+
+    let bytes = fs::read(source).await?;
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WEBP" {
+        return Ok(None);
+    }
+
+    Ok(Some(bytes))
+}
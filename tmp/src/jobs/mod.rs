@@ -1,4 +1,16 @@
+pub mod clean_cache;
+pub mod export_library_nfo;
+pub mod export_nfo;
+pub mod extract_artwork;
 pub mod fetch_artwork;
+pub mod fetch_metadata;
+pub mod fetch_person;
+pub mod generate_blur_hashes;
+pub mod identify_media;
+pub mod migrate_artwork;
+pub mod refresh_artwork;
+pub mod refresh_episodes;
+pub mod refresh_metadata;
 pub mod scan_folder;
 pub mod scan_library;
 pub mod scan_season_folder;
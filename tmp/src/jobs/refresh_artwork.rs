@@ -0,0 +1,63 @@
+use crate::jobs::fetch_artwork::{FetchArtwork, FetchArtworkPayload};
+use crate::jobs::Job;
+use crate::repositories;
+use crate::repositories::media::MediaCriteria;
+use crate::state::AppState;
+use async_trait::async_trait;
+use tracing::info;
+
+pub struct RefreshArtworkPayload {
+    pub library_id: i64,
+}
+
+impl RefreshArtworkPayload {
+    pub fn new(library_id: i64) -> Self {
+        Self { library_id }
+    }
+}
+
+pub struct RefreshArtwork {
+    pub state: AppState,
+    pub payload: RefreshArtworkPayload,
+}
+
+impl RefreshArtwork {
+    pub fn new(state: AppState, payload: RefreshArtworkPayload) -> Self {
+        Self { state, payload }
+    }
+}
+
+#[async_trait]
+impl Job for RefreshArtwork {
+    async fn run(&self) -> Result<(), anyhow::Error> {
+        info!(
+            "Refreshing artwork for library: {}",
+            self.payload.library_id
+        );
+
+        let media = {
+            let mut connection = self.state.pool.get().await?;
+            repositories::media::find_all(
+                &mut connection,
+                MediaCriteria {
+                    library_id: Some(self.payload.library_id),
+                    ..Default::default()
+                },
+            )
+            .await?
+        };
+
+        for media in media {
+            self.state.queue.send(Box::new(FetchArtwork::new(
+                self.state.clone(),
+                FetchArtworkPayload::new(media.id),
+            )))?;
+        }
+
+        info!(
+            "Finished refreshing artwork for library: {}",
+            self.payload.library_id
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use crate::factories::metadata_provider::{EpisodeMetadata, MetadataProvider};
+use crate::jobs::Job;
+use crate::models::{EpisodeOrdering, InsertableProviderEpisode};
+use crate::repositories;
+use crate::repositories::media::MediaCriteria;
+use crate::state::AppState;
+use async_trait::async_trait;
+use std::str::FromStr;
+use tracing::{info, warn};
+
+pub struct RefreshEpisodesPayload {
+    pub library_id: i64,
+}
+
+impl RefreshEpisodesPayload {
+    pub fn new(library_id: i64) -> Self {
+        Self { library_id }
+    }
+}
+
+pub struct RefreshEpisodes {
+    pub state: AppState,
+    pub payload: RefreshEpisodesPayload,
+}
+
+impl RefreshEpisodes {
+    pub fn new(state: AppState, payload: RefreshEpisodesPayload) -> Self {
+        Self { state, payload }
+    }
+}
+
+pub async fn fetch_episode_list(
+    state: &AppState,
+    provider: &(dyn MetadataProvider + Send + Sync),
+    id: &str,
+    media_id: i64,
+    ordering: EpisodeOrdering,
+) -> Result<Vec<EpisodeMetadata>, anyhow::Error> {
+    let episodes = provider.get_episodes(id, ordering).await?;
+
+    let provider_episodes: Vec<InsertableProviderEpisode> = episodes
+        .iter()
+        .map(|episode| InsertableProviderEpisode {
+            media_id,
+            season: episode.season,
+            episode: episode.episode,
+            title: episode.title.clone(),
+            plot: episode.plot.clone(),
+            aired: episode.aired,
+            provider_ids: diesel_json::Json::new(episode.provider_ids.clone()),
+        })
+        .collect();
+
+    let mut connection = state.pool.get().await?;
+    repositories::provider_episode::replace(&mut connection, media_id, &provider_episodes).await?;
+    Ok(episodes)
+}
+
+#[async_trait]
+impl Job for RefreshEpisodes {
+    async fn run(&self) -> Result<(), anyhow::Error> {
+        info!(
+            "Refreshing episodes for library: {}",
+            self.payload.library_id
+        );
+
+        let (library, shows) = {
+            let mut connection = self.state.pool.get().await?;
+            let library =
+                repositories::library::find_by_id(&mut connection, self.payload.library_id)
+                    .await?
+                    .ok_or(anyhow::Error::msg(format!(
+                        "Library with id {} not found",
+                        self.payload.library_id
+                    )))?;
+            let shows = repositories::media::find_all(
+                &mut connection,
+                MediaCriteria {
+                    types: Some(vec!["tvshow".to_string()]),
+                    library_id: Some(library.id),
+                    ..Default::default()
+                },
+            )
+            .await?;
+            (library, shows)
+        };
+
+        let Some(provider) = self
+            .state
+            .metadata_provider_factory
+            .get_provider(&library.metadata_provider)
+        else {
+            info!(
+                "Metadata provider {} of Library {} is not configured, skipping episodes",
+                library.metadata_provider, library.id
+            );
+            return Ok(());
+        };
+        let ordering =
+            EpisodeOrdering::from_str(&library.episode_ordering).map_err(anyhow::Error::msg)?;
+
+        let mut count = 0;
+        for show in shows.iter().filter(|show| show.is_continuing()) {
+            let Some(id) = show.provider_ids.get(provider.name()) else {
+                continue;
+            };
+
+            match fetch_episode_list(&self.state, provider, id, show.id, ordering).await {
+                Ok(_) => count += 1,
+                Err(e) => warn!(
+                    "Failed to fetch {} episodes for Media {}: {}",
+                    provider.name(),
+                    show.id,
+                    e
+                ),
+            }
+        }
+
+        info!(
+            "Finished refreshing episodes for library: {}, {} shows refreshed",
+            self.payload.library_id, count
+        );
+        Ok(())
+    }
+}
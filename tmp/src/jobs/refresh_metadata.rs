@@ -0,0 +1,91 @@
+use crate::jobs::fetch_metadata::{FetchMetadata, FetchMetadataPayload};
+use crate::jobs::Job;
+use crate::models::Media;
+use crate::repositories;
+use crate::repositories::media::MediaCriteria;
+use crate::state::AppState;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use tracing::info;
+
+/// Spreads a large library over several runs instead of flooding the metadata provider.
+const MAX_REFRESHES_PER_RUN: usize = 200;
+
+pub struct RefreshMetadataPayload {
+    pub library_id: i64,
+}
+
+impl RefreshMetadataPayload {
+    pub fn new(library_id: i64) -> Self {
+        Self { library_id }
+    }
+}
+
+/// Continuing shows go first as they change the most, then the least recently refreshed.
+pub struct RefreshMetadata {
+    pub state: AppState,
+    pub payload: RefreshMetadataPayload,
+}
+
+impl RefreshMetadata {
+    pub fn new(state: AppState, payload: RefreshMetadataPayload) -> Self {
+        Self { state, payload }
+    }
+}
+
+#[async_trait]
+impl Job for RefreshMetadata {
+    async fn run(&self) -> Result<(), anyhow::Error> {
+        info!(
+            "Refreshing metadata for library: {}",
+            self.payload.library_id
+        );
+
+        let (library, media) = {
+            let mut connection = self.state.pool.get().await?;
+            let library =
+                repositories::library::find_by_id(&mut connection, self.payload.library_id)
+                    .await?
+                    .ok_or(anyhow::Error::msg(format!(
+                        "Library with id {} not found",
+                        self.payload.library_id
+                    )))?;
+            let media = repositories::media::find_all(
+                &mut connection,
+                MediaCriteria {
+                    library_id: Some(library.id),
+                    ..Default::default()
+                },
+            )
+            .await?;
+            (library, media)
+        };
+
+        let stale_before =
+            Utc::now().naive_utc() - Duration::days(i64::from(library.metadata_refresh_days));
+
+        let mut stale: Vec<Media> = media
+            .into_iter()
+            .filter(|media| {
+                media
+                    .metadata_refreshed_at
+                    .is_none_or(|refreshed_at| refreshed_at < stale_before)
+            })
+            .collect();
+        stale.sort_by_key(|media| (!media.is_continuing(), media.metadata_refreshed_at));
+
+        let count = stale.len().min(MAX_REFRESHES_PER_RUN);
+        for media in stale.into_iter().take(MAX_REFRESHES_PER_RUN) {
+            self.state.queue.send(Box::new(FetchMetadata::new(
+                self.state.clone(),
+                FetchMetadataPayload::refresh(media.id),
+            )))?;
+        }
+
+        info!(
+            "Finished refreshing metadata for library: {}, {} media items queued",
+            self.payload.library_id, count
+        );
+        Ok(())
+    }
+}
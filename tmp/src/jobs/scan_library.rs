@@ -1,3 +1,4 @@
+use crate::ignore_rules::IgnoreRules;
 use crate::jobs::scan_folder::{ScanFolder, ScanFolderPayload};
 use crate::jobs::Job;
 use crate::repositories;
@@ -54,6 +55,8 @@ impl Job for ScanLibrary {
             return Err(anyhow::Error::msg("Path is not a directory"));
         }
 
+        let ignore_rules = IgnoreRules::for_dir(&library, path).await?;
+
         let mut dir = fs::read_dir(path).await?;
 
         while let Some(entry) = dir.next_entry().await? {
@@ -61,6 +64,11 @@ impl Job for ScanLibrary {
                 continue;
             }
 
+            if ignore_rules.is_ignored(&entry.path(), true) {
+                info!("Ignoring folder: {:?}", entry.path());
+                continue;
+            }
+
             self.state.queue.send(Box::new(ScanFolder::new(
                 self.state.clone(),
                 ScanFolderPayload::new(library.id, entry.path().to_str().unwrap().to_string()),
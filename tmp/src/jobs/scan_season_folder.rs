@@ -1,16 +1,34 @@
-use crate::factories::library_scanner::remove_empty_self_closing_tags;
+use crate::factories::library_scanner::artwork::{
+    fill_blur_hashes, keep_metadata_files, local_artwork, ArtworkCandidates,
+};
+use crate::factories::library_scanner::extras::{
+    extra_file_kind, extra_folder_kind, scan_extra_folder, LocalExtra,
+};
+use crate::factories::library_scanner::{
+    is_video, remove_empty_self_closing_tags, season_and_episode,
+};
+use crate::ignore_rules::IgnoreRules;
+use crate::jobs::fetch_artwork::{FetchArtwork, FetchArtworkPayload};
+use crate::jobs::fetch_metadata::{FetchMetadata, FetchMetadataPayload};
 use crate::jobs::Job;
-use crate::models::{File, FileType, InsertableMedia};
+use crate::models::{File, FileSource, FileType, InsertableExtra, InsertableMedia, MetadataStatus};
 use crate::nfo::Nfo;
+use crate::people;
 use crate::repositories;
 use crate::state::AppState;
 use async_trait::async_trait;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use tokio::fs::DirEntry;
-use tokio::io::AsyncReadExt;
 use tracing::{info, warn};
 
+#[derive(Debug, Default)]
+struct EpisodeFiles {
+    nfo: Option<PathBuf>,
+    video: Option<PathBuf>,
+    thumbnail: Option<(usize, PathBuf)>,
+}
+
 pub struct ScanSeasonFolderPayload {
     pub media_id: i64,
     pub season_folder: DirEntry,
@@ -67,135 +85,222 @@ impl Job for ScanSeasonFolder {
             }
         };
 
-        let media = InsertableMedia {
-            type_: parent.type_.clone(),
-            library_id: parent.library_id,
-            path: Some(season_file_name.to_string()),
-            title: season_file_name.to_string(),
-            season: Some(season),
-            parent_id: Some(parent.id),
-            ..Default::default()
+        let library = {
+            let mut connection = self.state.pool.get().await?;
+            match repositories::library::find_by_id(&mut connection, parent.library_id).await? {
+                Some(library) => library,
+                None => {
+                    return Err(anyhow::Error::msg(format!(
+                        "Library with id {} not found",
+                        parent.library_id
+                    )));
+                }
+            }
         };
 
-        let parent = {
-            let mut connection = self.state.pool.get().await?;
+        let ignore_rules = IgnoreRules::for_dir(&library, &season_folder).await?;
 
-            let existing = repositories::media::find_by_path_and_parent_id(
-                &mut connection,
-                media.path.clone(),
-                media.parent_id,
-            )
-            .await?;
+        let mut artwork = ArtworkCandidates::default();
 
-            match existing {
-                Some(mut existing) => {
-                    existing.apply(&media);
-                    repositories::media::update(&mut connection, &existing).await?
+        if let Some(show_folder) = &parent.path {
+            let mut dir = tokio::fs::read_dir(show_folder).await?;
+            while let Some(entry) = dir.next_entry().await? {
+                let path = entry.path();
+
+                if let Some(local_artwork) = local_artwork(&path)
+                    && local_artwork.season == Some(season)
+                    && path.is_file()
+                    && !ignore_rules.is_ignored(&path, false)
+                {
+                    artwork.insert(&local_artwork, path.to_str().unwrap().to_string());
                 }
-                None => repositories::media::create(&mut connection, &media).await?,
             }
-        };
+        }
 
-        let mut dir = tokio::fs::read_dir(season_folder).await?;
+        let mut dir = tokio::fs::read_dir(&season_folder).await?;
 
-        let mut map: HashMap<String, (Option<PathBuf>, Option<PathBuf>, Option<PathBuf>)> =
-            HashMap::new();
+        let mut map: HashMap<String, EpisodeFiles> = HashMap::new();
+        let mut extras = Vec::new();
 
         while let Some(entry) = dir.next_entry().await? {
             let path = entry.path();
 
-            if !path.is_file() {
+            if ignore_rules.is_ignored(&path, path.is_dir()) {
                 continue;
             }
 
-            let file_name_without_extension = entry.file_name().to_str().unwrap().replace(
-                format!(
-                    ".{}",
-                    if let Some(extension) = path.extension() {
-                        extension.to_str().unwrap()
-                    } else {
-                        warn!("File found without extension {:?}", entry);
-                        continue;
-                    }
-                )
-                .as_str(),
-                "",
-            );
-
-            if let Some(extension) = path.extension() {
-                if extension == "nfo" {
-                    let item = map.get(&file_name_without_extension.clone());
-                    map.insert(
-                        file_name_without_extension.clone(),
-                        (
-                            Some(path),
-                            item.and_then(|i| i.1.clone()),
-                            item.and_then(|i| i.2.clone()),
-                        ),
-                    );
-                    continue;
+            if path.is_dir() {
+                if let Some(kind) = extra_folder_kind(&path) {
+                    let folder_ignore_rules = IgnoreRules::for_dir(&library, &path).await?;
+                    extras.extend(scan_extra_folder(&path, kind, &folder_ignore_rules).await?);
                 }
+                continue;
+            }
+
+            if !path.is_file() {
+                continue;
             }
 
-            if let Ok(mut open) = tokio::fs::File::open(path.clone()).await {
-                let mut buffer = [0; 8192];
-                if let Ok(bytes_read) = open.read(&mut buffer[..]).await {
-                    if bytes_read > 0 {
-                        if let Some(kind) = infer::get(&buffer[..bytes_read]) {
-                            if kind.mime_type().starts_with("video/") {
-                                let item = map.get(&file_name_without_extension.clone());
-                                map.insert(
-                                    file_name_without_extension.clone(),
-                                    (
-                                        item.and_then(|i| i.0.clone()),
-                                        Some(path),
-                                        item.and_then(|i| i.2.clone()),
-                                    ),
-                                );
-                                continue;
-                            }
+            if let Some(local_artwork) = local_artwork(&path) {
+                match local_artwork.prefix {
+                    Some(prefix) if local_artwork.file_type == FileType::Thumbnail => {
+                        let item = map.entry(prefix).or_default();
+                        if item
+                            .thumbnail
+                            .as_ref()
+                            .is_none_or(|(priority, _)| *priority > local_artwork.priority)
+                        {
+                            item.thumbnail = Some((local_artwork.priority, path));
                         }
                     }
+                    None if local_artwork.season.is_none() => {
+                        artwork.insert(
+                            &local_artwork,
+                            entry.file_name().to_str().unwrap().to_string(),
+                        );
+                    }
+                    _ => {}
                 }
+                continue;
             }
 
-            if let Some(file_name) = entry.file_name().to_str() {
-                if file_name.ends_with("-thumb.jpg") {
-                    let file_name_without_extension = file_name.replace("-thumb.jpg", "");
-                    let item = map.get(&file_name_without_extension.clone());
-                    map.insert(
-                        file_name_without_extension.clone(),
-                        (
-                            item.and_then(|i| i.0.clone()),
-                            item.and_then(|i| i.1.clone()),
-                            Some(path),
-                        ),
-                    );
-                }
+            let Some(file_name_without_extension) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .map(str::to_string)
+                .filter(|_| path.extension().is_some())
+            else {
+                warn!("File found without extension {:?}", entry);
+                continue;
+            };
+
+            if let Some(extension) = path.extension()
+                && extension == "nfo"
+            {
+                map.entry(file_name_without_extension).or_default().nfo = Some(path);
+                continue;
             }
-        }
 
-        for (file_name, (nfo_file, video_file, thumbnail_file)) in map {
-            let Some(nfo_file) = nfo_file else {
-                warn!("No nfo file found for entry: {:?}", file_name);
+            if !is_video(&path).await {
                 continue;
-            };
+            }
 
-            let Some(video_file) = video_file else {
-                warn!("No video file found for entry: {:?}", file_name);
+            if let Some((kind, title)) = extra_file_kind(&path) {
+                extras.push(LocalExtra { kind, title, path });
                 continue;
+            }
+
+            map.entry(file_name_without_extension).or_default().video = Some(path);
+        }
+
+        let mut media = InsertableMedia {
+            type_: parent.type_.clone(),
+            library_id: parent.library_id,
+            path: Some(season_file_name.to_string()),
+            title: season_file_name.to_string(),
+            season: Some(season),
+            parent_id: Some(parent.id),
+            ..Default::default()
+        };
+
+        for (file_type, path) in artwork.into_files() {
+            media.files.as_mut().push(File {
+                type_: file_type,
+                path,
+                blur_hash: None,
+                size: None,
+                modified: None,
+                source: FileSource::Library,
+            });
+        }
+
+        media.update_metadata_status();
+
+        let mut created = Vec::new();
+
+        let existing = {
+            let mut connection = self.state.pool.get().await?;
+            repositories::media::find_by_path_and_parent_id(
+                &mut connection,
+                media.path.clone(),
+                media.parent_id,
+            )
+            .await?
+        };
+
+        let previous_files = existing
+            .as_ref()
+            .map(|e| e.files.0.clone())
+            .unwrap_or_default();
+        keep_metadata_files(
+            media.files.as_mut(),
+            &previous_files,
+            existing
+                .as_ref()
+                .map(|e| e.locked_fields.as_slice())
+                .unwrap_or_default(),
+        );
+        fill_blur_hashes(&season_folder, media.files.as_mut(), &previous_files).await;
+
+        let parent = {
+            let mut connection = self.state.pool.get().await?;
+
+            let season = match existing {
+                Some(mut existing) => {
+                    // Seasons have no NFO, keep the metadata a provider filled in
+                    existing.files = media.files.clone();
+                    repositories::media::update(&mut connection, &existing).await?
+                }
+                None => {
+                    let season = repositories::media::create(&mut connection, &media).await?;
+                    created.push(season.id);
+                    season
+                }
             };
 
-            let Some(thumbnail_file) = thumbnail_file else {
-                warn!("No thumbnail file found for entry: {:?}", file_name);
+            let extras: Vec<InsertableExtra> = extras
+                .into_iter()
+                .map(|extra| extra.into_insertable(season.id))
+                .collect();
+            repositories::extra::replace_for_media(&mut connection, season.id, &extras).await?;
+
+            season
+        };
+
+        let mut incomplete = parent.metadata_status != MetadataStatus::Complete.as_str();
+
+        for (file_name, files) in map {
+            let Some(video_file) = files.video else {
+                warn!("No video file found for entry: {:?}", file_name);
                 continue;
             };
 
-            let nfo_string =
-                remove_empty_self_closing_tags(tokio::fs::read_to_string(nfo_file).await?.as_str());
-            let nfo: Nfo = quick_xml::de::from_str(nfo_string.as_str())?;
+            let has_nfo = files.nfo.is_some();
+            let mut media = match files.nfo {
+                Some(nfo_file) => {
+                    let nfo_string = remove_empty_self_closing_tags(
+                        tokio::fs::read_to_string(nfo_file).await?.as_str(),
+                    );
+                    let nfo: Nfo = quick_xml::de::from_str(nfo_string.as_str())?;
+                    let mut media = InsertableMedia::from(nfo);
+                    media.translate(library.preferred_languages.first());
+                    media
+                }
+                None => {
+                    let Some((episode_season, episode)) = season_and_episode(&file_name) else {
+                        warn!(
+                            "No nfo file or episode number found for entry: {:?}",
+                            file_name
+                        );
+                        continue;
+                    };
 
-            let mut media = InsertableMedia::from(nfo);
+                    let mut media = InsertableMedia::from_file_name(file_name.clone(), None);
+                    media.season = Some(episode_season);
+                    media.episode = Some(episode);
+                    media
+                }
+            };
             media.type_.clone_from(&parent.type_);
             media.library_id = parent.library_id;
             media.parent_id = Some(parent.id);
@@ -204,17 +309,104 @@ impl Job for ScanSeasonFolder {
                 type_: FileType::Video,
                 path: video_file.to_str().unwrap().to_string(),
                 blur_hash: None,
+                size: None,
+                modified: None,
+                source: FileSource::Library,
             });
 
-            media.files.as_mut().push(File {
-                type_: FileType::Poster,
-                path: thumbnail_file.to_str().unwrap().to_string(),
-                blur_hash: None,
-            });
+            if let Some((_, thumbnail_file)) = files.thumbnail {
+                let thumbnail_file = thumbnail_file.to_str().unwrap().to_string();
 
-            {
+                media.files.as_mut().push(File {
+                    type_: FileType::Thumbnail,
+                    path: thumbnail_file.clone(),
+                    blur_hash: None,
+                    size: None,
+                    modified: None,
+                    source: FileSource::Library,
+                });
+
+                // Episode thumbnails have always been served as the poster, keep that working
+                media.files.as_mut().push(File {
+                    type_: FileType::Poster,
+                    path: thumbnail_file,
+                    blur_hash: None,
+                    size: None,
+                    modified: None,
+                    source: FileSource::Library,
+                });
+            } else {
+                info!(
+                    "No thumbnail file found for entry, it will be extracted from the video: {:?}",
+                    file_name
+                );
+            }
+
+            media.update_metadata_status();
+
+            let existing = match media.episode {
+                Some(episode) => {
+                    let mut connection = self.state.pool.get().await?;
+                    repositories::media::find_by_episode_and_parent_id(
+                        &mut connection,
+                        episode,
+                        parent.id,
+                    )
+                    .await?
+                }
+                None => None,
+            };
+
+            let previous_files = existing
+                .as_ref()
+                .map(|e| e.files.0.clone())
+                .unwrap_or_default();
+            keep_metadata_files(
+                media.files.as_mut(),
+                &previous_files,
+                existing
+                    .as_ref()
+                    .map(|e| e.locked_fields.as_slice())
+                    .unwrap_or_default(),
+            );
+            fill_blur_hashes(&season_folder, media.files.as_mut(), &previous_files).await;
+
+            let episode = {
                 let mut connection = self.state.pool.get().await?;
-                repositories::media::create(&mut connection, &media).await?;
+
+                match existing {
+                    Some(mut existing) => {
+                        if has_nfo {
+                            existing.apply(&media);
+                        } else {
+                            existing.files = media.files.clone();
+                        }
+                        repositories::media::update(&mut connection, &existing).await?
+                    }
+                    None => {
+                        let episode = repositories::media::create(&mut connection, &media).await?;
+                        created.push(episode.id);
+                        episode
+                    }
+                }
+            };
+
+            people::sync_credits(&self.state, &episode).await?;
+            incomplete |= episode.metadata_status != MetadataStatus::Complete.as_str();
+        }
+
+        // Fetching metadata queues the artwork once it is done
+        if incomplete {
+            self.state.queue.send(Box::new(FetchMetadata::new(
+                self.state.clone(),
+                FetchMetadataPayload::new(parent.id),
+            )))?;
+        } else {
+            for media_id in created {
+                self.state.queue.send(Box::new(FetchArtwork::new(
+                    self.state.clone(),
+                    FetchArtworkPayload::new(media_id),
+                )))?;
             }
         }
 
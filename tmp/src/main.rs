@@ -1,12 +1,19 @@
+use crate::clients::http::HttpClient;
 use crate::factories::artwork_fetcher::ArtworkFetcherFactory;
 use crate::factories::library_scanner::ScannerFactory;
+use crate::factories::metadata_provider::MetadataProviderFactory;
+use crate::ffmpeg::Ffmpeg;
+use crate::images::ImageCache;
+use crate::jobs::migrate_artwork::MigrateArtwork;
 use crate::jobs::Job;
+use crate::metadata_store::MetadataStore;
 use crate::state::AppState;
 use axum::Router;
 use diesel_async::pooled_connection::deadpool::Pool;
 use diesel_async::pooled_connection::AsyncDieselConnectionManager;
 use diesel_async::AsyncPgConnection;
 use rand::Rng;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::net::TcpListener;
 use tokio::sync::{mpsc, Semaphore};
@@ -14,20 +21,30 @@ use tower_http::trace::TraceLayer;
 use tracing::info;
 use tracing_subscriber::EnvFilter;
 
+mod calendar;
 mod clients;
+mod collections;
+mod config;
 mod errors;
 mod factories;
+mod ffmpeg;
+mod ignore_rules;
+mod images;
 mod jobs;
+mod metadata_store;
 mod middlware;
 mod models;
 mod nfo;
+mod people;
 mod repositories;
 mod routes;
+mod scheduler;
 mod schema;
 mod state;
+mod streaming;
 mod views;
 
-const MIGRATIONS: diesel_async_migrations::EmbeddedMigrations =
+static MIGRATIONS: diesel_async_migrations::EmbeddedMigrations =
     diesel_async_migrations::embed_migrations!();
 
 #[tokio::main]
@@ -79,14 +96,44 @@ async fn main() {
         }
     });
 
+    let cache_dir = PathBuf::from(std::env::var("CACHE_DIR").unwrap_or("cache".to_string()));
+    let http_client = Arc::new(HttpClient::new(cache_dir.join("http")));
+    let image_cache_size_mb: u64 = std::env::var("IMAGE_CACHE_SIZE_MB")
+        .ok()
+        .and_then(|size| size.parse().ok())
+        .unwrap_or(1024);
+    let image_cache = Arc::new(ImageCache::new(
+        cache_dir.join("images"),
+        image_cache_size_mb * 1024 * 1024,
+    ));
+
     let state = AppState {
         secret,
         pool,
         queue: tx,
-        artwork_fetcher_factory: Arc::new(ArtworkFetcherFactory::default()),
+        artwork_fetcher_factory: Arc::new(ArtworkFetcherFactory::new(http_client.clone())),
         scanner_factory: Arc::new(ScannerFactory::default()),
+        metadata_provider_factory: Arc::new(MetadataProviderFactory::new(http_client.clone())),
+        http_client,
+        image_cache,
+        metadata_store: Arc::new(MetadataStore::new(PathBuf::from(
+            std::env::var("METADATA_DIR").unwrap_or("metadata".to_string()),
+        ))),
+        ffmpeg: Arc::new(Ffmpeg::new(
+            std::env::var("FFMPEG_PATH").unwrap_or("ffmpeg".to_string()),
+            std::env::var("FFPROBE_PATH").unwrap_or("ffprobe".to_string()),
+        )),
+        cache_dir,
     };
 
+    state
+        .queue
+        .send(Box::new(MigrateArtwork::new(state.clone())))
+        .unwrap();
+
+    info!("Starting scheduler");
+    tokio::spawn(scheduler::run(state.clone()));
+
     info!("Starting server");
     let app = Router::new()
         .merge(routes::routes())
@@ -0,0 +1,123 @@
+use crate::factories::library_scanner::artwork::blur_hash;
+use crate::models::{File, FileSource, FileType};
+use axum::body::Bytes;
+use image::codecs::webp::WebPEncoder;
+use image::ImageReader;
+use sha2::{Digest, Sha256};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+const PEOPLE: &str = "people";
+
+pub struct MetadataStore {
+    root: PathBuf,
+}
+
+impl MetadataStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn people_root(&self) -> PathBuf {
+        self.root.join(PEOPLE)
+    }
+
+    pub fn path(&self, file: &File) -> PathBuf {
+        self.root.join(&file.path)
+    }
+
+    pub async fn save(
+        &self,
+        media_id: i64,
+        bytes: &[u8],
+        extension: &str,
+    ) -> Result<String, anyhow::Error> {
+        self.save_in(&media_id.to_string(), bytes, extension).await
+    }
+
+    async fn save_in(
+        &self,
+        folder: &str,
+        bytes: &[u8],
+        extension: &str,
+    ) -> Result<String, anyhow::Error> {
+        let path = format!("{folder}/{:x}.{extension}", Sha256::digest(bytes));
+
+        let absolute = self.root.join(&path);
+        if !absolute.is_file() {
+            fs::create_dir_all(self.root.join(folder)).await?;
+            fs::write(&absolute, bytes).await?;
+        }
+
+        Ok(path)
+    }
+
+    pub async fn save_image(
+        &self,
+        media_id: i64,
+        file_type: FileType,
+        bytes: Bytes,
+    ) -> Result<File, anyhow::Error> {
+        self.save_image_in(&media_id.to_string(), file_type, bytes)
+            .await
+    }
+
+    pub async fn save_headshot(&self, person_id: i64, bytes: Bytes) -> Result<File, anyhow::Error> {
+        self.save_image_in(&format!("{PEOPLE}/{person_id}"), FileType::Poster, bytes)
+            .await
+    }
+
+    async fn save_image_in(
+        &self,
+        folder: &str,
+        file_type: FileType,
+        bytes: Bytes,
+    ) -> Result<File, anyhow::Error> {
+        let (webp, blur_hash) = tokio::task::spawn_blocking(move || {
+            let image = ImageReader::new(Cursor::new(&bytes))
+                .with_guessed_format()
+                .map_err(|e| anyhow::Error::msg(format!("Failed to guess image format: {e}")))?
+                .decode()
+                .map_err(|e| anyhow::Error::msg(format!("Failed to decode image: {e}")))?;
+
+            let mut webp = Vec::new();
+            image
+                .to_rgba8()
+                .write_with_encoder(WebPEncoder::new_lossless(&mut webp))
+                .map_err(|e| anyhow::Error::msg(format!("Failed to encode image: {e}")))?;
+
+            Ok::<_, anyhow::Error>((webp, blur_hash(&image)))
+        })
+        .await??;
+
+        Ok(File {
+            type_: file_type,
+            path: self.save_in(folder, &webp, "webp").await?,
+            blur_hash,
+            size: None,
+            modified: None,
+            source: FileSource::Metadata,
+        })
+    }
+
+    pub async fn delete(&self, file: &File) -> Result<(), anyhow::Error> {
+        let path = self.path(file);
+        if path.is_file() {
+            fs::remove_file(path).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn remove(&self, media_id: i64) -> Result<(), anyhow::Error> {
+        let path = self.root.join(media_id.to_string());
+        if path.is_dir() {
+            fs::remove_dir_all(path).await?;
+        }
+        Ok(())
+    }
+}
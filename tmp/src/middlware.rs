@@ -129,6 +129,14 @@ where
 
 pub struct OptionalAuthUser(pub Option<AuthUser>);
 
+impl OptionalAuthUser {
+    pub fn metadata_languages(&self) -> &[String] {
+        self.0
+            .as_ref()
+            .map_or(&[], |user| user.metadata_languages.as_slice())
+    }
+}
+
 impl<S> FromRequestParts<S> for OptionalAuthUser
 where
     AppState: FromRef<S>,
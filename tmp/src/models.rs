@@ -1,9 +1,11 @@
-use crate::nfo::Nfo;
+use crate::nfo::{FileInfo, Nfo, Rating};
 use diesel::{AsChangeset, Insertable, Queryable, QueryableByName, Selectable};
 use diesel_json::Json;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::BTreeMap;
 use std::str::FromStr;
+use tracing::warn;
 
 #[derive(Debug, Default, Serialize, Queryable, Selectable, Insertable)]
 #[serde(rename_all = "camelCase")]
@@ -14,9 +16,14 @@ pub struct InsertableLibrary {
     pub name: String,
     pub path: String,
     pub media_type: String,
+    pub ignore_patterns: Vec<String>,
+    pub metadata_provider: String,
+    pub episode_ordering: String,
+    pub preferred_languages: Vec<String>,
+    pub metadata_refresh_days: i32,
 }
 
-#[derive(Debug, Default, Serialize, Queryable, Selectable)]
+#[derive(Debug, Default, Serialize, Queryable, Selectable, AsChangeset)]
 #[serde(rename_all = "camelCase")]
 #[diesel(table_name = crate::schema::libraries)]
 pub struct Library {
@@ -28,15 +35,25 @@ pub struct Library {
     pub name: String,
     pub path: String,
     pub media_type: String,
+    pub ignore_patterns: Vec<String>,
+    pub metadata_provider: String,
+    pub episode_ordering: String,
+    pub preferred_languages: Vec<String>,
+    pub metadata_refresh_days: i32,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum FileType {
     Video,
     Poster,
     Logo,
     Thumbnail,
     Background,
+    Banner,
+    ClearArt,
+    Disc,
+    ThemeMusic,
+    ThemeVideo,
 }
 
 impl FromStr for FileType {
@@ -49,16 +66,420 @@ impl FromStr for FileType {
             "logo" => Ok(Self::Logo),
             "thumbnail" => Ok(Self::Thumbnail),
             "background" => Ok(Self::Background),
+            "banner" => Ok(Self::Banner),
+            "clearart" => Ok(Self::ClearArt),
+            "disc" => Ok(Self::Disc),
+            "thememusic" => Ok(Self::ThemeMusic),
+            "themevideo" => Ok(Self::ThemeVideo),
             _ => Err(format!("Invalid file type: {}", s)),
         }
     }
 }
 
+impl FileType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Video => "video",
+            Self::Poster => "poster",
+            Self::Logo => "logo",
+            Self::Thumbnail => "thumbnail",
+            Self::Background => "background",
+            Self::Banner => "banner",
+            Self::ClearArt => "clearart",
+            Self::Disc => "disc",
+            Self::ThemeMusic => "thememusic",
+            Self::ThemeVideo => "themevideo",
+        }
+    }
+
+    pub fn is_artwork(&self) -> bool {
+        !matches!(self, Self::Video | Self::ThemeMusic | Self::ThemeVideo)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtraKind {
+    Trailer,
+    Featurette,
+    BehindTheScenes,
+    DeletedScene,
+    Interview,
+    Scene,
+    Short,
+    Other,
+}
+
+impl FromStr for ExtraKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "trailer" => Ok(Self::Trailer),
+            "featurette" => Ok(Self::Featurette),
+            "behindTheScenes" => Ok(Self::BehindTheScenes),
+            "deletedScene" => Ok(Self::DeletedScene),
+            "interview" => Ok(Self::Interview),
+            "scene" => Ok(Self::Scene),
+            "short" => Ok(Self::Short),
+            "other" => Ok(Self::Other),
+            _ => Err(format!("Invalid extra kind: {}", s)),
+        }
+    }
+}
+
+impl ExtraKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Trailer => "trailer",
+            Self::Featurette => "featurette",
+            Self::BehindTheScenes => "behindTheScenes",
+            Self::DeletedScene => "deletedScene",
+            Self::Interview => "interview",
+            Self::Scene => "scene",
+            Self::Short => "short",
+            Self::Other => "other",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataStatus {
+    Unmatched,
+    Incomplete,
+    Complete,
+}
+
+impl FromStr for MetadataStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "unmatched" => Ok(Self::Unmatched),
+            "incomplete" => Ok(Self::Incomplete),
+            "complete" => Ok(Self::Complete),
+            _ => Err(format!("Invalid metadata status: {}", s)),
+        }
+    }
+}
+
+impl MetadataStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Unmatched => "unmatched",
+            Self::Incomplete => "incomplete",
+            Self::Complete => "complete",
+        }
+    }
+
+    /// Top level items without provider ids are unmatched, seasons and episodes are looked up
+    /// through their show.
+    pub fn of(
+        parent_id: Option<i64>,
+        provider_ids: &ProviderIds,
+        attributes: &serde_json::Value,
+    ) -> Self {
+        if parent_id.is_none() && provider_ids.0.is_empty() {
+            return Self::Unmatched;
+        }
+
+        if MediaAttributes::from(attributes).plot.is_none() {
+            return Self::Incomplete;
+        }
+
+        Self::Complete
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EpisodeOrdering {
+    Aired,
+    Dvd,
+    /// Episodes numbered across the whole show, all in season 1.
+    Absolute,
+}
+
+impl FromStr for EpisodeOrdering {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "aired" => Ok(Self::Aired),
+            "dvd" => Ok(Self::Dvd),
+            "absolute" => Ok(Self::Absolute),
+            _ => Err(format!("Invalid episode ordering: {}", s)),
+        }
+    }
+}
+
+impl EpisodeOrdering {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Aired => "aired",
+            Self::Dvd => "dvd",
+            Self::Absolute => "absolute",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FileSource {
+    /// Found in the library, relative to the media item's folder (and those of its parents).
+    #[default]
+    Library,
+    /// Downloaded or generated, relative to the metadata directory.
+    Metadata,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct File {
     pub type_: FileType,
     pub path: String,
     pub blur_hash: Option<String>,
+    #[serde(default)]
+    pub source: FileSource,
+    /// Size of the library image `blur_hash` was computed from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// Modification time of that image, in milliseconds since the epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProviderIds(pub BTreeMap<String, String>);
+
+impl ProviderIds {
+    pub const TMDB: &'static str = "tmdb";
+    pub const IMDB: &'static str = "imdb";
+    pub const TVDB: &'static str = "tvdb";
+
+    pub fn get(&self, provider: &str) -> Option<&String> {
+        self.0.get(provider)
+    }
+
+    pub fn insert(&mut self, provider: &str, id: String) {
+        self.0.insert(provider.to_lowercase(), id);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.0.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<&Nfo> for ProviderIds {
+    fn from(value: &Nfo) -> Self {
+        let mut provider_ids = Self::default();
+
+        if let Some(id) = value.id.as_ref().filter(|id| id.starts_with("tt")) {
+            provider_ids.insert(Self::IMDB, id.clone());
+        }
+
+        for unique_id in &value.unique_id {
+            let id = unique_id.value.trim();
+            if id.is_empty() {
+                continue;
+            }
+
+            match &unique_id.type_ {
+                Some(type_) => provider_ids.insert(type_, id.to_string()),
+                None if id.starts_with("tt") => provider_ids.insert(Self::IMDB, id.to_string()),
+                None => continue,
+            }
+        }
+
+        provider_ids
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CreditRole {
+    Actor,
+    Director,
+    Writer,
+    Producer,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Credit {
+    pub name: String,
+    pub role: CreditRole,
+    pub character: Option<String>,
+    pub order: Option<i32>,
+    pub thumb: Option<String>,
+    #[serde(default, skip_serializing_if = "ProviderIds::is_empty")]
+    pub provider_ids: ProviderIds,
+}
+
+impl CreditRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Actor => "actor",
+            Self::Director => "director",
+            Self::Writer => "writer",
+            Self::Producer => "producer",
+        }
+    }
+}
+
+impl FromStr for CreditRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "actor" => Ok(Self::Actor),
+            "director" => Ok(Self::Director),
+            "writer" => Ok(Self::Writer),
+            "producer" => Ok(Self::Producer),
+            _ => Err(format!("Invalid credit role: {}", s)),
+        }
+    }
+}
+
+/// Provider ids in the `<profile>` URL Kodi writes for actors.
+fn profile_provider_ids(profile: Option<&String>) -> ProviderIds {
+    let mut provider_ids = ProviderIds::default();
+
+    let Some(profile) = profile else {
+        return provider_ids;
+    };
+
+    let segments: Vec<&str> = profile.trim_end_matches('/').split('/').collect();
+    for pair in segments.windows(2) {
+        let id = pair[1].split(['-', '?']).next().unwrap_or_default();
+        match pair[0] {
+            "person" if profile.contains("themoviedb.org") && !id.is_empty() => {
+                provider_ids.insert(ProviderIds::TMDB, id.to_string());
+            }
+            "name" if profile.contains("imdb.com") && id.starts_with("nm") => {
+                provider_ids.insert(ProviderIds::IMDB, id.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    provider_ids
+}
+
+impl Credit {
+    pub fn from_nfo(value: &Nfo) -> Vec<Self> {
+        let actors = value.actor.iter().map(|actor| Self {
+            name: actor.name.clone(),
+            role: CreditRole::Actor,
+            character: actor.role.clone(),
+            order: actor.order,
+            thumb: actor.thumb.clone(),
+            provider_ids: profile_provider_ids(actor.profile.as_ref()),
+        });
+
+        let crew = value
+            .director
+            .iter()
+            .map(|name| (name, CreditRole::Director))
+            .chain(value.credits.iter().map(|name| (name, CreditRole::Writer)))
+            .map(|(name, role)| Self {
+                name: name.clone(),
+                role,
+                character: None,
+                order: None,
+                thumb: None,
+                provider_ids: ProviderIds::default(),
+            });
+
+        actors.chain(crew).collect()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Translation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plot: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tagline: Option<String>,
+}
+
+impl Translation {
+    pub fn attributes(&self) -> [(&'static str, Option<&String>); 3] {
+        [
+            ("title", self.title.as_ref()),
+            ("plot", self.plot.as_ref()),
+            ("tagline", self.tagline.as_ref()),
+        ]
+    }
+
+    pub fn fill(&mut self, other: &Translation) {
+        if self.title.is_none() {
+            self.title.clone_from(&other.title);
+        }
+        if self.plot.is_none() {
+            self.plot.clone_from(&other.plot);
+        }
+        if self.tagline.is_none() {
+            self.tagline.clone_from(&other.tagline);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MediaSet {
+    pub name: String,
+    pub overview: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MediaAttributes {
+    pub title: Option<String>,
+    pub original_title: Option<String>,
+    pub sort_title: Option<String>,
+    pub show_title: Option<String>,
+    pub display_season: Option<i32>,
+    pub display_episode: Option<i32>,
+    pub display_after_season: Option<i32>,
+    pub ratings: Option<Vec<Rating>>,
+    pub rating: Option<f64>,
+    pub critic_rating: Option<i32>,
+    pub user_rating: Option<i32>,
+    pub top250: Option<i32>,
+    pub outline: Option<String>,
+    pub plot: Option<String>,
+    pub tagline: Option<String>,
+    pub runtime: Option<i32>,
+    pub mpaa: Option<String>,
+    pub nfo_id: Option<serde_json::Value>,
+    pub genre: Option<Vec<String>>,
+    pub country: Option<String>,
+    pub status: Option<String>,
+    pub premiered: Option<chrono::NaiveDate>,
+    pub aired: Option<chrono::NaiveDate>,
+    pub end_date: Option<chrono::NaiveDate>,
+    pub year: Option<i32>,
+    pub studio: Option<String>,
+    pub trailer: Option<String>,
+    pub named_seasons: BTreeMap<String, String>,
+    pub file_info: Option<FileInfo>,
+}
+
+impl From<&serde_json::Value> for MediaAttributes {
+    fn from(value: &serde_json::Value) -> Self {
+        if value.is_null() {
+            return Self::default();
+        }
+
+        serde_json::from_value(value.clone()).unwrap_or_else(|e| {
+            warn!("Failed to read media attributes: {}", e);
+            Self::default()
+        })
+    }
 }
 
 #[derive(Debug, Default, Insertable)]
@@ -73,6 +494,12 @@ pub struct InsertableMedia {
     pub files: Json<Vec<File>>,
     pub attributes: serde_json::Value,
     pub parent_id: Option<i64>,
+    pub provider_ids: Json<ProviderIds>,
+    pub credits: Json<Vec<Credit>>,
+    pub tags: Vec<String>,
+    pub collection: Option<Json<MediaSet>>,
+    pub metadata_status: String,
+    pub translations: Json<BTreeMap<String, Translation>>,
 }
 
 impl From<Nfo> for InsertableMedia {
@@ -81,10 +508,25 @@ impl From<Nfo> for InsertableMedia {
             title: value.sort_title.clone().unwrap_or(value.title.clone()),
             season: value.season,
             episode: value.episode,
+            provider_ids: Json::new(ProviderIds::from(&value)),
+            credits: Json::new(Credit::from_nfo(&value)),
+            tags: value.tag.clone(),
+            collection: value.set.as_ref().and_then(|set| {
+                set.name().map(|name| {
+                    Json::new(MediaSet {
+                        name,
+                        overview: set.overview.clone(),
+                    })
+                })
+            }),
             attributes: json!({
                 "title": value.title.clone(),
                 "originalTitle": value.original_title.clone(),
                 "sortTitle": value.sort_title.clone(),
+                "showTitle": value.show_title.clone(),
+                "displaySeason": value.display_season,
+                "displayEpisode": value.display_episode,
+                "displayAfterSeason": value.display_after_season,
                 "ratings": value.ratings.clone().map(|r| r.content),
                 "rating": value.rating.clone(),
                 "criticRating": value.critic_rating.clone(),
@@ -97,13 +539,15 @@ impl From<Nfo> for InsertableMedia {
                 "mpaa": value.mpaa.clone(),
                 "nfoId": value.id.clone(),
                 "genre": value.genre.clone(),
-                "country": value.country.clone(),
+                "country": (!value.country.is_empty()).then(|| value.country.join(" / ")),
                 "status": value.status.clone(),
                 "premiered": value.premiered.clone(),
+                "aired": value.aired.clone(),
                 "endDate": value.end_date.clone(),
                 "year": value.year.clone(),
-                "studio": value.studio.clone(),
+                "studio": (!value.studio.is_empty()).then(|| value.studio.join(" / ")),
                 "trailer": value.trailer.clone(),
+                "namedSeasons": value.named_season.iter().map(|s| (s.number.to_string(), s.name.clone())).collect::<BTreeMap<_, _>>(),
                 "fileInfo": value.file_info.clone(),
             }),
             ..Default::default()
@@ -111,8 +555,51 @@ impl From<Nfo> for InsertableMedia {
     }
 }
 
+impl InsertableMedia {
+    /// The `title` attribute is left unset so a metadata provider can replace the title.
+    pub fn from_file_name(title: String, year: Option<i32>) -> Self {
+        Self {
+            title,
+            attributes: json!({
+                "year": year,
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Also files the title, plot and tagline as the translation in the library's NFO language.
+    pub fn translate(&mut self, language: Option<&String>) {
+        let Some(language) = language else {
+            return;
+        };
+
+        let attribute = |key: &str| {
+            self.attributes
+                .get(key)
+                .and_then(|value| value.as_str())
+                .map(str::to_string)
+        };
+        let translation = Translation {
+            title: attribute("title"),
+            plot: attribute("plot"),
+            tagline: attribute("tagline"),
+        };
+
+        if translation != Translation::default() {
+            self.translations.insert(language.clone(), translation);
+        }
+    }
+
+    pub fn update_metadata_status(&mut self) {
+        self.metadata_status =
+            MetadataStatus::of(self.parent_id, &self.provider_ids, &self.attributes)
+                .as_str()
+                .to_string();
+    }
+}
+
 #[derive(Debug, Default, Queryable, QueryableByName, Selectable, AsChangeset)]
-#[diesel(table_name = crate::schema::media)]
+#[diesel(table_name = crate::schema::media, treat_none_as_null = true)]
 pub struct Media {
     pub id: i64,
     pub created_at: chrono::NaiveDateTime,
@@ -126,20 +613,311 @@ pub struct Media {
     pub files: Json<Vec<File>>,
     pub attributes: serde_json::Value,
     pub parent_id: Option<i64>,
+    pub provider_ids: Json<ProviderIds>,
+    pub credits: Json<Vec<Credit>>,
+    pub tags: Vec<String>,
+    pub collection: Option<Json<MediaSet>>,
+    pub metadata_status: String,
+    pub locked_fields: Vec<String>,
+    pub metadata_refreshed_at: Option<chrono::NaiveDateTime>,
+    pub translations: Json<BTreeMap<String, Translation>>,
 }
 
+/// Fields that can be edited through the API and locked against scans and metadata providers.
+/// `title` covers the title column and attribute, the others are attributes except for `tags`,
+/// `credits`, `collection`, `providerIds` and the artwork types, which lock the chosen image.
+pub const LOCKABLE_FIELDS: [&str; 30] = [
+    "title",
+    "originalTitle",
+    "sortTitle",
+    "plot",
+    "outline",
+    "tagline",
+    "genre",
+    "year",
+    "premiered",
+    "aired",
+    "endDate",
+    "runtime",
+    "mpaa",
+    "studio",
+    "country",
+    "status",
+    "rating",
+    "userRating",
+    "trailer",
+    "tags",
+    "credits",
+    "collection",
+    "providerIds",
+    "poster",
+    "logo",
+    "thumbnail",
+    "background",
+    "banner",
+    "clearart",
+    "disc",
+];
+
 impl Media {
+    pub fn is_locked(&self, field: &str) -> bool {
+        self.locked_fields.iter().any(|locked| locked == field)
+    }
+
+    pub fn is_continuing(&self) -> bool {
+        self.type_ == "tvshow"
+            && self.parent_id.is_none()
+            && MediaAttributes::from(&self.attributes)
+                .status
+                .is_some_and(|status| {
+                    !["ended", "canceled", "cancelled"].contains(&status.to_lowercase().as_str())
+                })
+    }
+
+    pub fn nfo_id(&self) -> Option<String> {
+        match self.attributes.get("nfoId")? {
+            serde_json::Value::String(id) => Some(id.clone()),
+            serde_json::Value::Number(id) => Some(id.to_string()),
+            _ => None,
+        }
+    }
+
+    /// NFO scans always set the `nfoId` attribute, if only to null.
+    pub fn has_nfo(&self) -> bool {
+        self.attributes
+            .as_object()
+            .is_some_and(|attributes| attributes.contains_key("nfoId"))
+    }
+
+    /// The title, plot and tagline in the first of `languages` each is known in. Edited (locked)
+    /// ones are left out, they are shown as edited in every language.
+    pub fn translated(&self, languages: &[String]) -> Translation {
+        let mut translated = Translation::default();
+        for language in languages {
+            if let Some(translation) = self.translations.get(language) {
+                translated.fill(translation);
+            }
+        }
+
+        if self.is_locked("title") {
+            translated.title = None;
+        }
+        if self.is_locked("plot") {
+            translated.plot = None;
+        }
+        if self.is_locked("tagline") {
+            translated.tagline = None;
+        }
+
+        translated
+    }
+
+    /// Replaces everything with what was scanned, except for locked fields.
     pub fn apply(&mut self, insertable: &InsertableMedia) {
         self.type_.clone_from(&insertable.type_);
         self.library_id = insertable.library_id;
         self.path.clone_from(&insertable.path);
-        self.title.clone_from(&insertable.title);
+        if !self.is_locked("title") && !self.is_locked("sortTitle") {
+            self.title.clone_from(&insertable.title);
+        }
         self.season = insertable.season;
         self.episode = insertable.episode;
         self.files = insertable.files.clone();
-        self.attributes = insertable.attributes.clone();
+        self.attributes = self.keep_locked_attributes(insertable.attributes.clone());
         self.parent_id = insertable.parent_id;
+        if !self.is_locked("providerIds") {
+            self.provider_ids = insertable.provider_ids.clone();
+        }
+        if !self.is_locked("credits") {
+            self.credits = insertable.credits.clone();
+        }
+        if !self.is_locked("tags") {
+            self.tags.clone_from(&insertable.tags);
+        }
+        if !self.is_locked("collection") {
+            self.collection.clone_from(&insertable.collection);
+        }
+        for (language, translation) in insertable.translations.iter() {
+            self.translations
+                .insert(language.clone(), translation.clone());
+        }
+        self.update_metadata_status();
     }
+
+    pub fn update_metadata_status(&mut self) {
+        self.metadata_status =
+            MetadataStatus::of(self.parent_id, &self.provider_ids, &self.attributes)
+                .as_str()
+                .to_string();
+    }
+
+    fn keep_locked_attributes(&self, mut attributes: serde_json::Value) -> serde_json::Value {
+        if !attributes.is_object() {
+            attributes = json!({});
+        }
+
+        let object = attributes.as_object_mut().unwrap();
+        for field in &self.locked_fields {
+            match self.attributes.get(field) {
+                Some(value) => object.insert(field.clone(), value.clone()),
+                None => object.remove(field),
+            };
+        }
+
+        attributes
+    }
+}
+
+#[derive(Debug, Clone, Default, Insertable)]
+#[diesel(table_name = crate::schema::extras)]
+pub struct InsertableExtra {
+    pub media_id: i64,
+    pub kind: String,
+    pub title: String,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Default, Queryable, Selectable)]
+#[diesel(table_name = crate::schema::extras)]
+pub struct Extra {
+    pub id: i64,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+    pub media_id: i64,
+    pub kind: String,
+    pub title: String,
+    pub path: String,
+}
+
+#[derive(Debug, Default, Insertable)]
+#[diesel(table_name = crate::schema::people)]
+pub struct InsertablePerson {
+    pub name: String,
+    pub provider_ids: Json<ProviderIds>,
+    pub thumb: Option<String>,
+    pub match_key: Option<String>,
+}
+
+/// `thumb` is the headshot URL credits gave, `headshot` the image downloaded from it or the
+/// metadata provider.
+#[derive(Debug, Clone, Default, Queryable, Selectable, AsChangeset)]
+#[diesel(table_name = crate::schema::people, treat_none_as_null = true)]
+pub struct Person {
+    pub id: i64,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+    pub name: String,
+    pub provider_ids: Json<ProviderIds>,
+    pub biography: Option<String>,
+    pub birthday: Option<chrono::NaiveDate>,
+    pub deathday: Option<chrono::NaiveDate>,
+    pub place_of_birth: Option<String>,
+    pub thumb: Option<String>,
+    pub headshot: Option<Json<File>>,
+    pub metadata_refreshed_at: Option<chrono::NaiveDateTime>,
+    /// See `people::match_key`, missing for people created before it was introduced.
+    pub match_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Insertable)]
+#[diesel(table_name = crate::schema::media_people)]
+pub struct InsertableMediaPerson {
+    pub media_id: i64,
+    pub person_id: i64,
+    pub role: String,
+    pub character: Option<String>,
+    pub sort_order: Option<i32>,
+}
+
+#[derive(Debug, Clone, Default, Queryable, Selectable)]
+#[diesel(table_name = crate::schema::media_people)]
+pub struct MediaPerson {
+    pub media_id: i64,
+    pub person_id: i64,
+    pub role: String,
+    pub character: Option<String>,
+    pub sort_order: Option<i32>,
+}
+
+/// Memberships built from the item's `collection` carry the name of that set, those added by
+/// hand have none.
+#[derive(Debug, Clone, Default, Insertable)]
+#[diesel(table_name = crate::schema::collection_items)]
+pub struct InsertableCollectionItem {
+    pub collection_id: i64,
+    pub media_id: i64,
+    pub set_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Queryable, Selectable)]
+#[diesel(table_name = crate::schema::collection_items)]
+pub struct CollectionItem {
+    pub collection_id: i64,
+    pub media_id: i64,
+    pub set_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Insertable)]
+#[diesel(table_name = crate::schema::provider_episodes)]
+pub struct InsertableProviderEpisode {
+    pub media_id: i64,
+    pub season: i32,
+    pub episode: i32,
+    pub title: Option<String>,
+    pub plot: Option<String>,
+    pub aired: Option<chrono::NaiveDate>,
+    pub provider_ids: Json<ProviderIds>,
+}
+
+#[derive(Debug, Clone, Default, Queryable, QueryableByName, Selectable)]
+#[diesel(table_name = crate::schema::provider_episodes)]
+pub struct ProviderEpisode {
+    pub id: i64,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+    pub media_id: i64,
+    pub season: i32,
+    pub episode: i32,
+    pub title: Option<String>,
+    pub plot: Option<String>,
+    pub aired: Option<chrono::NaiveDate>,
+    pub provider_ids: Json<ProviderIds>,
+}
+
+#[derive(Debug, Default, Insertable)]
+#[diesel(table_name = crate::schema::schedules)]
+pub struct InsertableSchedule {
+    pub created_by: String,
+    pub updated_by: String,
+    pub task: String,
+    pub library_id: Option<i64>,
+    pub cron: String,
+    pub next_run_at: Option<chrono::NaiveDateTime>,
+}
+
+/// A one-off change to data that can't be done in SQL, created by the migration adding it and
+/// completed by the job carrying it out.
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = crate::schema::data_migrations)]
+pub struct DataMigration {
+    pub created_at: chrono::NaiveDateTime,
+    pub completed_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Default, Queryable, Selectable, AsChangeset)]
+#[diesel(table_name = crate::schema::schedules, treat_none_as_null = true)]
+pub struct Schedule {
+    pub id: i64,
+    pub created_at: chrono::NaiveDateTime,
+    pub created_by: String,
+    pub updated_at: chrono::NaiveDateTime,
+    pub updated_by: String,
+    pub task: String,
+    pub library_id: Option<i64>,
+    pub cron: String,
+    pub enabled: bool,
+    pub last_run_at: Option<chrono::NaiveDateTime>,
+    pub next_run_at: Option<chrono::NaiveDateTime>,
 }
 
 #[derive(Debug, Default, Insertable)]
@@ -151,11 +929,11 @@ pub struct InsertableUser {
     pub password: String,
     pub name: String,
     pub is_admin: bool,
+    pub metadata_languages: Vec<String>,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Queryable, Selectable, AsChangeset)]
-#[serde(rename_all = "camelCase")]
-#[diesel(table_name = crate::schema::users)]
+#[derive(Debug, Clone, Default, Queryable, Selectable, AsChangeset)]
+#[diesel(table_name = crate::schema::users, treat_none_as_null = true)]
 pub struct User {
     pub id: i64,
     pub created_at: chrono::NaiveDateTime,
@@ -166,6 +944,8 @@ pub struct User {
     pub password: String,
     pub name: String,
     pub is_admin: bool,
+    pub metadata_languages: Vec<String>,
+    pub calendar_token: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Insertable)]
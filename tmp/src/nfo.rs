@@ -1,47 +1,147 @@
+use crate::factories::library_scanner::remove_empty_self_closing_tags;
+use crate::models::{CreditRole, FileType, Media, MediaAttributes, ProviderIds};
+use quick_xml::events::Event;
+use quick_xml::Reader;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::fs;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Nfo {
     pub title: String,
-    #[serde(rename = "originaltitle")]
+    #[serde(rename = "originaltitle", skip_serializing_if = "Option::is_none")]
     pub original_title: Option<String>,
-    #[serde(rename = "sorttitle")]
+    #[serde(rename = "sorttitle", skip_serializing_if = "Option::is_none")]
     pub sort_title: Option<String>,
+    #[serde(rename = "showtitle", skip_serializing_if = "Option::is_none")]
+    pub show_title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub season: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub episode: Option<i32>,
+    #[serde(rename = "displayseason", skip_serializing_if = "Option::is_none")]
+    pub display_season: Option<i32>,
+    #[serde(rename = "displayepisode", skip_serializing_if = "Option::is_none")]
+    pub display_episode: Option<i32>,
+    #[serde(rename = "displayafterseason", skip_serializing_if = "Option::is_none")]
+    pub display_after_season: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub ratings: Option<Ratings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub rating: Option<f64>,
-    #[serde(rename = "criticrating")]
+    #[serde(rename = "criticrating", skip_serializing_if = "Option::is_none")]
     pub critic_rating: Option<i32>,
-    #[serde(rename = "userrating")]
+    #[serde(rename = "userrating", skip_serializing_if = "Option::is_none")]
     pub user_rating: Option<i32>,
-    #[serde(rename = "top250")]
+    #[serde(rename = "top250", skip_serializing_if = "Option::is_none")]
     pub top_250: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub outline: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub plot: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub tagline: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub runtime: Option<i32>,
-    pub thumb: Option<Thumb>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub thumb: Vec<Thumb>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub fanart: Option<Fanart>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub mpaa: Option<String>,
-    pub id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "uniqueid", default, skip_serializing_if = "Vec::is_empty")]
+    pub unique_id: Vec<UniqueId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub genre: Option<Vec<String>>,
-    pub country: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tag: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub set: Option<Set>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub country: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub status: Option<String>,
-    // pub credits: String,
-    // pub director: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub credits: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub director: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actor: Vec<Actor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub premiered: Option<chrono::NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aired: Option<chrono::NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub end_date: Option<chrono::NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub year: Option<i32>,
-    pub studio: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub studio: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub trailer: Option<String>,
-    #[serde(rename = "fileinfo")]
+    #[serde(rename = "namedseason", default, skip_serializing_if = "Vec::is_empty")]
+    pub named_season: Vec<NamedSeason>,
+    #[serde(rename = "fileinfo", skip_serializing_if = "Option::is_none")]
     pub file_info: Option<FileInfo>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct Ratings {
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UniqueId {
+    #[serde(rename = "@type", skip_serializing_if = "Option::is_none")]
+    pub type_: Option<String>,
+    #[serde(rename = "@default", default)]
+    pub default: bool,
+    #[serde(rename = "$value", default)]
+    pub value: String,
+}
+
+/// Kodi writes sets either as `<set>Name</set>` or as `<set><name>Name</name></set>`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Set {
+    #[serde(rename = "$text", skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overview: Option<String>,
+}
+
+impl Set {
+    pub fn name(&self) -> Option<String> {
+        self.name
+            .clone()
+            .or(self.text.clone())
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Actor {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumb: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedSeason {
+    #[serde(rename = "@number")]
+    pub number: i32,
     #[serde(rename = "$value")]
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ratings {
+    #[serde(rename(serialize = "rating", deserialize = "$value"))]
     pub content: Vec<Rating>,
 }
 
@@ -59,17 +159,18 @@ pub struct Rating {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Thumb {
-    #[serde(rename = "@aspect")]
+    #[serde(rename = "@aspect", skip_serializing_if = "Option::is_none")]
     pub aspect: Option<String>,
-    #[serde(rename = "@preview")]
-    pub preview: String,
+    #[serde(rename = "@preview", skip_serializing_if = "Option::is_none")]
+    pub preview: Option<String>,
     #[serde(rename = "$value")]
     pub content: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Fanart {
-    pub thumb: Thumb,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub thumb: Vec<Thumb>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -113,3 +214,445 @@ pub struct Audio {
 pub struct Subtitle {
     pub language: Option<String>,
 }
+
+impl From<&Media> for Nfo {
+    fn from(value: &Media) -> Self {
+        let attributes = MediaAttributes::from(&value.attributes);
+
+        let default_provider = if value.type_ == "movie" {
+            ProviderIds::TMDB
+        } else {
+            ProviderIds::TVDB
+        };
+
+        let credits_named = |role: CreditRole| {
+            value
+                .credits
+                .iter()
+                .filter(|credit| credit.role == role)
+                .map(|credit| credit.name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let split = |value: Option<String>| {
+            value.map_or(Vec::new(), |value| {
+                value.split(" / ").map(str::to_string).collect()
+            })
+        };
+
+        Self {
+            title: attributes.title.unwrap_or(value.title.clone()),
+            original_title: attributes.original_title,
+            sort_title: attributes.sort_title,
+            show_title: attributes.show_title,
+            season: value.season,
+            episode: value.episode,
+            display_season: attributes.display_season,
+            display_episode: attributes.display_episode,
+            display_after_season: attributes.display_after_season,
+            ratings: attributes.ratings.map(|content| Ratings { content }),
+            rating: attributes.rating,
+            critic_rating: attributes.critic_rating,
+            user_rating: attributes.user_rating,
+            top_250: attributes.top250,
+            outline: attributes.outline,
+            plot: attributes.plot,
+            tagline: attributes.tagline,
+            runtime: attributes.runtime,
+            mpaa: attributes.mpaa,
+            id: value
+                .provider_ids
+                .get(default_provider)
+                .cloned()
+                .or(value.nfo_id()),
+            unique_id: value
+                .provider_ids
+                .iter()
+                .map(|(provider, id)| UniqueId {
+                    type_: Some(provider.clone()),
+                    default: provider == default_provider,
+                    value: id.clone(),
+                })
+                .collect(),
+            genre: attributes.genre,
+            tag: value.tags.clone(),
+            set: value.collection.as_ref().map(|collection| Set {
+                text: None,
+                name: Some(collection.name.clone()),
+                overview: collection.overview.clone(),
+            }),
+            country: split(attributes.country),
+            status: attributes.status,
+            credits: credits_named(CreditRole::Writer),
+            director: credits_named(CreditRole::Director),
+            actor: value
+                .credits
+                .iter()
+                .filter(|credit| credit.role == CreditRole::Actor)
+                .map(|credit| Actor {
+                    name: credit.name.clone(),
+                    role: credit.character.clone(),
+                    order: credit.order,
+                    thumb: credit.thumb.clone(),
+                    profile: credit
+                        .provider_ids
+                        .get(ProviderIds::TMDB)
+                        .map(|id| format!("https://www.themoviedb.org/person/{id}")),
+                })
+                .collect(),
+            premiered: attributes.premiered,
+            aired: attributes.aired,
+            end_date: attributes.end_date,
+            year: attributes.year,
+            studio: split(attributes.studio),
+            trailer: attributes.trailer,
+            named_season: attributes
+                .named_seasons
+                .into_iter()
+                .filter_map(|(number, name)| {
+                    number
+                        .parse()
+                        .ok()
+                        .map(|number| NamedSeason { number, name })
+                })
+                .collect(),
+            file_info: attributes.file_info,
+            ..Default::default()
+        }
+    }
+}
+
+impl Nfo {
+    pub fn to_xml(&self, root: &str) -> Result<String, anyhow::Error> {
+        let mut xml =
+            String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\" ?>\n");
+        let mut serializer = quick_xml::se::Serializer::with_root(&mut xml, Some(root))?;
+        serializer.indent(' ', 2);
+        self.serialize(serializer)?;
+        Ok(remove_empty_self_closing_tags(&xml))
+    }
+}
+
+/// Kodi's `plugin://plugin.video.youtube/...` trailers are rewritten to the YouTube watch page.
+pub fn trailer_url(trailer: &str) -> Option<String> {
+    let trailer = trailer.trim();
+
+    if let Some(query) = trailer.strip_prefix("plugin://plugin.video.youtube/") {
+        return query
+            .split(['?', '&'])
+            .find_map(|parameter| {
+                parameter
+                    .strip_prefix("videoid=")
+                    .or_else(|| parameter.strip_prefix("video_id="))
+            })
+            .filter(|video_id| !video_id.is_empty())
+            .map(|video_id| format!("https://www.youtube.com/watch?v={video_id}"));
+    }
+
+    if trailer.starts_with("http://") || trailer.starts_with("https://") {
+        return Some(trailer.to_string());
+    }
+
+    None
+}
+
+/// Returns `None` for media Kodi has no NFO for.
+pub async fn write(media: &Media) -> Result<Option<PathBuf>, anyhow::Error> {
+    let (root, path) = if media.episode.is_some() {
+        let Some(video) = media.files.iter().find(|f| f.type_ == FileType::Video) else {
+            return Err(anyhow::Error::msg(format!(
+                "Media {} has no video file",
+                media.id
+            )));
+        };
+
+        (
+            "episodedetails",
+            Path::new(&video.path).with_extension("nfo"),
+        )
+    } else if media.season.is_some() {
+        return Ok(None);
+    } else {
+        let Some(folder) = media.path.as_ref() else {
+            return Err(anyhow::Error::msg(format!(
+                "Media {} has no path",
+                media.id
+            )));
+        };
+
+        let root = if media.type_ == "movie" {
+            "movie"
+        } else {
+            "tvshow"
+        };
+
+        let mut path = Path::new(folder).join(format!("{root}.nfo"));
+        let mut dir = fs::read_dir(folder).await?;
+        while let Some(entry) = dir.next_entry().await? {
+            if entry.path().is_file() && entry.path().extension().is_some_and(|e| e == "nfo") {
+                path = entry.path();
+                break;
+            }
+        }
+
+        (root, path)
+    };
+
+    let mut xml = Nfo::from(media).to_xml(root)?;
+    if fs::try_exists(&path).await? {
+        let existing = fs::read_to_string(&path).await?;
+        xml = merge(&xml, &existing, root).map_err(|e| {
+            anyhow::Error::msg(format!(
+                "Not overwriting {}, it could not be read: {e}",
+                path.display()
+            ))
+        })?;
+    }
+
+    fs::write(&path, xml).await?;
+
+    Ok(Some(path))
+}
+
+/// Any other element of an existing NFO is kept when writing over it.
+const WRITTEN_ELEMENTS: &[&str] = &[
+    "title",
+    "originaltitle",
+    "sorttitle",
+    "showtitle",
+    "season",
+    "episode",
+    "displayseason",
+    "displayepisode",
+    "displayafterseason",
+    "ratings",
+    "rating",
+    "criticrating",
+    "userrating",
+    "top250",
+    "outline",
+    "plot",
+    "tagline",
+    "runtime",
+    "mpaa",
+    "id",
+    "uniqueid",
+    "genre",
+    "tag",
+    "set",
+    "country",
+    "status",
+    "credits",
+    "director",
+    "actor",
+    "premiered",
+    "aired",
+    "end_date",
+    "year",
+    "studio",
+    "trailer",
+    "namedseason",
+    "fileinfo",
+];
+
+/// Also keeps anything following the document, like the scraper URL Kodi allows there.
+fn merge(xml: &str, existing: &str, root: &str) -> Result<String, anyhow::Error> {
+    let mut reader = Reader::from_str(existing);
+    let mut kept = Vec::new();
+    let mut trailing = "";
+    let mut in_root = false;
+
+    loop {
+        let start = reader.buffer_position() as usize;
+        match reader.read_event()? {
+            Event::Start(_) if !in_root => in_root = true,
+            Event::Start(tag) => {
+                let name = String::from_utf8_lossy(tag.name().as_ref()).to_string();
+                reader.read_to_end(tag.name())?;
+                if !WRITTEN_ELEMENTS.contains(&name.as_str()) {
+                    kept.push(&existing[start..reader.buffer_position() as usize]);
+                }
+            }
+            Event::Empty(tag) if in_root => {
+                let name = String::from_utf8_lossy(tag.name().as_ref()).to_string();
+                if !WRITTEN_ELEMENTS.contains(&name.as_str()) {
+                    kept.push(&existing[start..reader.buffer_position() as usize]);
+                }
+            }
+            Event::End(_) => {
+                trailing = existing[reader.buffer_position() as usize..].trim();
+                break;
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !in_root {
+        return Err(anyhow::Error::msg("no root element"));
+    }
+
+    let end = format!("</{root}>");
+    let Some(position) = xml.rfind(&end) else {
+        return Ok(xml.to_string());
+    };
+
+    let mut merged = xml[..position].to_string();
+    for element in kept {
+        merged.push_str("  ");
+        merged.push_str(element.trim());
+        merged.push('\n');
+    }
+    merged.push_str(&xml[position..]);
+    if !trailing.is_empty() {
+        if !merged.ends_with('\n') {
+            merged.push('\n');
+        }
+        merged.push_str(trailing);
+        merged.push('\n');
+    }
+
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Credit;
+
+    fn parse(xml: &str) -> Nfo {
+        quick_xml::de::from_str(&remove_empty_self_closing_tags(xml)).unwrap()
+    }
+
+    #[test]
+    fn unique_ids_are_stored_per_provider() {
+        let nfo = parse(
+            r#"<movie>
+                <title>Alien</title>
+                <id>tt0078748</id>
+                <uniqueid type="tmdb" default="true">348</uniqueid>
+                <uniqueid type="TVDB">123</uniqueid>
+                <uniqueid type="imdb"/>
+                <uniqueid type="tvmaze"></uniqueid>
+                <uniqueid>456</uniqueid>
+            </movie>"#,
+        );
+
+        let provider_ids = ProviderIds::from(&nfo);
+
+        assert_eq!(provider_ids.get(ProviderIds::IMDB).unwrap(), "tt0078748");
+        assert_eq!(provider_ids.get(ProviderIds::TMDB).unwrap(), "348");
+        assert_eq!(provider_ids.get(ProviderIds::TVDB).unwrap(), "123");
+        assert_eq!(provider_ids.iter().count(), 3);
+    }
+
+    #[test]
+    fn numeric_id_is_not_taken_for_imdb() {
+        let nfo = parse("<tvshow><title>Lost</title><id>73739</id></tvshow>");
+
+        assert!(ProviderIds::from(&nfo).is_empty());
+    }
+
+    #[test]
+    fn cast_and_crew_are_parsed() {
+        let nfo = parse(
+            r#"<movie>
+                <title>Alien</title>
+                <director>Ridley Scott</director>
+                <credits>Dan O'Bannon</credits>
+                <actor>
+                    <name>Sigourney Weaver</name>
+                    <role>Ripley</role>
+                    <order>0</order>
+                    <profile>https://www.themoviedb.org/person/10205-sigourney-weaver</profile>
+                </actor>
+                <actor>
+                    <name>Tom Skerritt</name>
+                    <profile>https://www.imdb.com/name/nm0001732/</profile>
+                </actor>
+            </movie>"#,
+        );
+
+        let credits = Credit::from_nfo(&nfo);
+
+        assert_eq!(credits.len(), 4);
+        assert_eq!(credits[0].name, "Sigourney Weaver");
+        assert_eq!(credits[0].character.as_deref(), Some("Ripley"));
+        assert_eq!(credits[0].order, Some(0));
+        assert_eq!(
+            credits[0].provider_ids.get(ProviderIds::TMDB).unwrap(),
+            "10205"
+        );
+        assert_eq!(
+            credits[1].provider_ids.get(ProviderIds::IMDB).unwrap(),
+            "nm0001732"
+        );
+        assert_eq!(credits[2].role, CreditRole::Director);
+        assert_eq!(credits[3].role, CreditRole::Writer);
+    }
+
+    #[test]
+    fn sets_are_read_in_both_forms() {
+        let nfo = parse("<movie><title>Alien</title><set>Alien Collection</set></movie>");
+        assert_eq!(nfo.set.unwrap().name().unwrap(), "Alien Collection");
+
+        let nfo = parse(
+            "<movie><title>Alien</title><set><name>Alien Collection</name><overview>Xenomorphs</overview></set></movie>",
+        );
+        let set = nfo.set.unwrap();
+        assert_eq!(set.name().unwrap(), "Alien Collection");
+        assert_eq!(set.overview.as_deref(), Some("Xenomorphs"));
+    }
+
+    #[test]
+    fn episode_details_are_parsed() {
+        let nfo = parse(
+            "<episodedetails><title>Pilot</title><season>1</season><episode>2</episode><displayseason>0</displayseason><aired>2004-09-22</aired></episodedetails>",
+        );
+
+        assert_eq!(nfo.season, Some(1));
+        assert_eq!(nfo.episode, Some(2));
+        assert_eq!(nfo.display_season, Some(0));
+        assert_eq!(nfo.aired, chrono::NaiveDate::from_ymd_opt(2004, 9, 22));
+    }
+
+    #[test]
+    fn merge_keeps_elements_that_are_not_written() {
+        let xml = Nfo {
+            title: "Alien".to_string(),
+            ..Default::default()
+        }
+        .to_xml("movie")
+        .unwrap();
+        let existing = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes" ?>
+<movie>
+  <title>Old title</title>
+  <thumb aspect="poster">https://example.com/poster.jpg</thumb>
+  <fanart><thumb>https://example.com/fanart.jpg</thumb></fanart>
+  <watched/>
+  <plot>Old plot</plot>
+</movie>
+https://www.themoviedb.org/movie/348"#;
+
+        let merged = merge(&xml, existing, "movie").unwrap();
+
+        assert!(merged.contains("<title>Alien</title>"));
+        assert!(!merged.contains("Old title"));
+        assert!(!merged.contains("Old plot"));
+        assert!(merged.contains(r#"<thumb aspect="poster">https://example.com/poster.jpg</thumb>"#));
+        assert!(merged.contains("<fanart><thumb>https://example.com/fanart.jpg</thumb></fanart>"));
+        assert!(merged.contains("<watched/>"));
+        assert!(merged.ends_with("</movie>\nhttps://www.themoviedb.org/movie/348\n"));
+
+        let merged = parse(&merged);
+        assert_eq!(merged.title, "Alien");
+        assert_eq!(merged.thumb.len(), 1);
+    }
+
+    #[test]
+    fn merge_refuses_files_that_are_not_xml() {
+        let xml = Nfo::default().to_xml("movie").unwrap();
+
+        assert!(merge(&xml, "https://www.themoviedb.org/movie/348", "movie").is_err());
+    }
+}
@@ -0,0 +1,140 @@
+use crate::jobs::fetch_person::{FetchPerson, FetchPersonPayload};
+use crate::models::{Credit, InsertableMediaPerson, InsertablePerson, Media, ProviderIds};
+use crate::repositories;
+use crate::state::AppState;
+use chrono::Utc;
+use diesel_async::AsyncPgConnection;
+use diesel_json::Json;
+use std::collections::HashMap;
+use tracing::info;
+
+pub async fn sync_credits(state: &AppState, media: &Media) -> Result<(), anyhow::Error> {
+    if media.type_ == "collection" {
+        return Ok(());
+    }
+
+    let mut connection = state.pool.get().await?;
+
+    let mut person_ids: HashMap<&str, i64> = HashMap::new();
+    let mut roles = Vec::new();
+    for credit in media.credits.iter() {
+        let person_id = match person_ids.get(credit.name.as_str()) {
+            Some(person_id) => *person_id,
+            None => {
+                let person_id = find_or_create(state, &mut connection, media.id, credit).await?;
+                person_ids.insert(&credit.name, person_id);
+                person_id
+            }
+        };
+
+        roles.push(InsertableMediaPerson {
+            media_id: media.id,
+            person_id,
+            role: credit.role.as_str().to_string(),
+            character: credit.character.clone(),
+            sort_order: credit.order,
+        });
+    }
+
+    repositories::media_person::replace(&mut connection, media.id, &roles).await?;
+    Ok(())
+}
+
+/// People credited without any id are only shared by the roles of one media item, as a name
+/// alone does not tell people of the same name apart.
+fn match_key(media_id: i64, credit: &Credit) -> String {
+    [ProviderIds::TMDB, ProviderIds::TVDB, ProviderIds::IMDB]
+        .into_iter()
+        .find_map(|provider| {
+            credit
+                .provider_ids
+                .get(provider)
+                .map(|id| format!("{provider}:{id}"))
+        })
+        .or_else(|| {
+            credit
+                .provider_ids
+                .iter()
+                .next()
+                .map(|(provider, id)| format!("{provider}:{id}"))
+        })
+        .unwrap_or_else(|| format!("media:{media_id}:{}", credit.name))
+}
+
+async fn find_or_create(
+    state: &AppState,
+    connection: &mut AsyncPgConnection,
+    media_id: i64,
+    credit: &Credit,
+) -> Result<i64, anyhow::Error> {
+    let match_key = match_key(media_id, credit);
+
+    let mut person = None;
+    for (provider, id) in credit.provider_ids.iter() {
+        person = repositories::person::find_by_provider_id(connection, provider, id).await?;
+        if person.is_some() {
+            break;
+        }
+    }
+    if person.is_none() {
+        person = repositories::person::find_by_match_key(connection, &match_key).await?;
+    }
+
+    if person.is_none() {
+        let created = repositories::person::create(
+            connection,
+            &InsertablePerson {
+                name: credit.name.clone(),
+                provider_ids: Json::new(credit.provider_ids.clone()),
+                thumb: credit.thumb.clone(),
+                match_key: Some(match_key.clone()),
+            },
+        )
+        .await?;
+
+        if let Some(person) = created {
+            info!("Created person {} ({})", person.id, person.name);
+
+            state.queue.send(Box::new(FetchPerson::new(
+                state.clone(),
+                FetchPersonPayload::new(person.id),
+            )))?;
+            return Ok(person.id);
+        }
+
+        person = repositories::person::find_by_match_key(connection, &match_key).await?;
+    }
+
+    let Some(mut person) = person else {
+        return Err(anyhow::Error::msg(format!(
+            "Person {} ({}) not found",
+            credit.name, match_key
+        )));
+    };
+
+    let mut new_ids = false;
+    for (provider, id) in credit.provider_ids.iter() {
+        if person.provider_ids.get(provider).is_none() {
+            person.provider_ids.insert(provider, id.clone());
+            new_ids = true;
+        }
+    }
+    let new_thumb = person.thumb.is_none() && credit.thumb.is_some();
+
+    if new_ids || new_thumb {
+        if new_thumb {
+            person.thumb.clone_from(&credit.thumb);
+        }
+        person.updated_at = Utc::now().naive_utc();
+        let person = repositories::person::update(connection, &person).await?;
+
+        if new_ids || person.headshot.is_none() {
+            state.queue.send(Box::new(FetchPerson::new(
+                state.clone(),
+                FetchPersonPayload::new(person.id),
+            )))?;
+        }
+    }
+
+    Ok(person.id)
+}
@@ -0,0 +1,90 @@
+use crate::models::{CollectionItem, InsertableCollectionItem};
+use crate::schema::{collection_items, media};
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use tracing::debug;
+
+pub async fn find_by_collection_id(
+    connection: &mut AsyncPgConnection,
+    collection_id: i64,
+) -> QueryResult<Vec<CollectionItem>> {
+    collection_items::dsl::collection_items
+        .filter(collection_items::collection_id.eq(collection_id))
+        .select(CollectionItem::as_select())
+        .load(connection)
+        .await
+}
+
+pub async fn find_by_media_id(
+    connection: &mut AsyncPgConnection,
+    media_id: i64,
+) -> QueryResult<Vec<CollectionItem>> {
+    collection_items::dsl::collection_items
+        .filter(collection_items::media_id.eq(media_id))
+        .select(CollectionItem::as_select())
+        .load(connection)
+        .await
+}
+
+/// The collection of a library other items of the set `set_name` were put in, whatever it is
+/// called now.
+pub async fn find_collection_id_by_set_name(
+    connection: &mut AsyncPgConnection,
+    library_id: i64,
+    set_name: &str,
+) -> QueryResult<Option<i64>> {
+    collection_items::dsl::collection_items
+        .filter(
+            collection_items::set_name.eq(set_name).and(
+                collection_items::collection_id.eq_any(
+                    media::dsl::media
+                        .filter(media::library_id.eq(library_id))
+                        .select(media::id),
+                ),
+            ),
+        )
+        .select(collection_items::collection_id)
+        .first(connection)
+        .await
+        .optional()
+}
+
+pub async fn create(
+    connection: &mut AsyncPgConnection,
+    entity: &InsertableCollectionItem,
+) -> QueryResult<usize> {
+    debug!("Creating collection item entity {:?}", entity);
+
+    diesel::insert_into(collection_items::table)
+        .values(entity)
+        .on_conflict((collection_items::collection_id, collection_items::media_id))
+        .do_nothing()
+        .execute(connection)
+        .await
+}
+
+pub async fn delete(
+    connection: &mut AsyncPgConnection,
+    collection_id: i64,
+    media_id: i64,
+) -> QueryResult<usize> {
+    diesel::delete(collection_items::table)
+        .filter(
+            collection_items::collection_id
+                .eq(collection_id)
+                .and(collection_items::media_id.eq(media_id)),
+        )
+        .execute(connection)
+        .await
+}
+
+/// Keeps other transactions from finding or creating collections of the library until the
+/// current one ends, so items of a new set synced at once end up in a single collection.
+pub async fn lock_library(connection: &mut AsyncPgConnection, library_id: i64) -> QueryResult<()> {
+    diesel::sql_query("SELECT pg_advisory_xact_lock($1)")
+        .bind::<BigInt, _>(library_id)
+        .execute(connection)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use crate::models::DataMigration;
+use crate::schema::data_migrations;
+use diesel::prelude::*;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+
+pub async fn find_by_name(
+    connection: &mut AsyncPgConnection,
+    name: &str,
+) -> QueryResult<Option<DataMigration>> {
+    data_migrations::dsl::data_migrations
+        .filter(data_migrations::name.eq(name))
+        .select(DataMigration::as_select())
+        .first(connection)
+        .await
+        .optional()
+}
+
+pub async fn complete(connection: &mut AsyncPgConnection, name: &str) -> QueryResult<usize> {
+    diesel::update(data_migrations::table)
+        .filter(data_migrations::name.eq(name))
+        .set((
+            data_migrations::completed_at.eq(diesel::dsl::now),
+            data_migrations::updated_at.eq(diesel::dsl::now),
+        ))
+        .execute(connection)
+        .await
+}
@@ -0,0 +1,64 @@
+use crate::models::{Extra, InsertableExtra};
+use crate::schema::extras;
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use tracing::debug;
+
+pub async fn find_by_id(connection: &mut AsyncPgConnection, id: i64) -> QueryResult<Option<Extra>> {
+    extras::dsl::extras
+        .find(id)
+        .select(Extra::as_select())
+        .first(connection)
+        .await
+        .optional()
+}
+
+pub async fn find_by_media_id(
+    connection: &mut AsyncPgConnection,
+    media_id: i64,
+) -> QueryResult<Vec<Extra>> {
+    extras::dsl::extras
+        .filter(extras::media_id.eq(media_id))
+        .order((extras::kind, extras::title))
+        .select(Extra::as_select())
+        .load(connection)
+        .await
+}
+
+/// Keeps the ids of extras that were found before.
+pub async fn replace_for_media(
+    connection: &mut AsyncPgConnection,
+    media_id: i64,
+    entities: &[InsertableExtra],
+) -> QueryResult<Vec<Extra>> {
+    debug!("Replacing extras of media {} with {:?}", media_id, entities);
+
+    let paths: Vec<&String> = entities.iter().map(|entity| &entity.path).collect();
+    diesel::delete(extras::table)
+        .filter(
+            extras::media_id
+                .eq(media_id)
+                .and(extras::path.ne_all(paths)),
+        )
+        .execute(connection)
+        .await?;
+
+    if entities.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    diesel::insert_into(extras::table)
+        .values(entities)
+        .on_conflict(extras::path)
+        .do_update()
+        .set((
+            extras::updated_at.eq(diesel::dsl::now),
+            extras::media_id.eq(excluded(extras::media_id)),
+            extras::kind.eq(excluded(extras::kind)),
+            extras::title.eq(excluded(extras::title)),
+        ))
+        .returning(Extra::as_returning())
+        .get_results(connection)
+        .await
+}
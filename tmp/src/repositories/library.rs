@@ -25,3 +25,12 @@ pub async fn create(
         .get_result(connection)
         .await
 }
+
+pub async fn update(connection: &mut AsyncPgConnection, entity: &Library) -> QueryResult<Library> {
+    diesel::update(libraries::table)
+        .filter(libraries::dsl::id.eq(entity.id))
+        .set(entity)
+        .returning(Library::as_returning())
+        .get_result(connection)
+        .await
+}
@@ -1,8 +1,9 @@
 use crate::models::{InsertableMedia, Media};
 use crate::schema::media;
 use diesel::dsl::sql;
+use diesel::pg::Pg;
 use diesel::prelude::*;
-use diesel::sql_types::BigInt;
+use diesel::sql_types::{BigInt, Bool, Nullable};
 use diesel_async::{AsyncPgConnection, RunQueryDsl};
 use serde::Deserialize;
 use tracing::debug;
@@ -17,6 +18,8 @@ pub enum MediaCriteriaOrder {
 pub struct MediaCriteria {
     pub types: Option<Vec<String>>,
     pub title: Option<String>,
+    /// Languages whose translated titles `title` also matches.
+    pub languages: Vec<String>,
     pub library_id: Option<i64>,
     pub parent_id: Option<i64>,
     pub order_by: Option<MediaCriteriaOrder>,
@@ -31,6 +34,46 @@ pub async fn find_by_id(connection: &mut AsyncPgConnection, id: i64) -> QueryRes
         .optional()
 }
 
+pub async fn find_ids_in(
+    connection: &mut AsyncPgConnection,
+    ids: Vec<i64>,
+) -> QueryResult<Vec<i64>> {
+    media::dsl::media
+        .filter(media::id.eq_any(ids))
+        .select(media::id)
+        .load(connection)
+        .await
+}
+
+pub async fn find_by_ids(
+    connection: &mut AsyncPgConnection,
+    ids: Vec<i64>,
+) -> QueryResult<Vec<Media>> {
+    media::dsl::media
+        .filter(media::id.eq_any(ids))
+        .select(Media::as_select())
+        .load(connection)
+        .await
+}
+
+pub async fn find_collection_by_title(
+    connection: &mut AsyncPgConnection,
+    library_id: i64,
+    title: &str,
+) -> QueryResult<Option<Media>> {
+    media::dsl::media
+        .filter(
+            media::type_
+                .eq("collection")
+                .and(media::library_id.eq(library_id))
+                .and(media::title.eq(title)),
+        )
+        .select(Media::as_select())
+        .first(connection)
+        .await
+        .optional()
+}
+
 pub async fn find_by_season_and_parent_id(
     connection: &mut AsyncPgConnection,
     season: i32,
@@ -80,14 +123,29 @@ pub async fn find_all(
 ) -> QueryResult<Vec<Media>> {
     let mut query = media::dsl::media.into_boxed();
 
-    if let Some(types) = criteria.types {
-        if !types.is_empty() {
+    // Collections are only listed when asked for
+    match criteria.types {
+        Some(types) if !types.is_empty() => {
             query = query.filter(media::dsl::type_.eq_any(types));
         }
+        _ => {
+            query = query.filter(media::dsl::type_.ne("collection"));
+        }
     }
 
     if let Some(title) = criteria.title {
-        query = query.filter(media::dsl::title.like(format!("%{title}%")));
+        let pattern = format!("%{title}%");
+        let mut matches: Box<dyn BoxableExpression<media::table, Pg, SqlType = Nullable<Bool>>> =
+            Box::new(media::dsl::title.like(pattern.clone()).nullable());
+        for language in criteria.languages {
+            matches = Box::new(
+                matches.or(media::dsl::translations
+                    .retrieve_as_object(language)
+                    .retrieve_as_text("title")
+                    .like(pattern.clone())),
+            );
+        }
+        query = query.filter(matches);
     }
 
     if let Some(library_id) = criteria.library_id {
@@ -117,6 +175,14 @@ pub async fn find_all(
     query.select(Media::as_select()).load(connection).await
 }
 
+/// Every media item of every type, seasons, episodes and collections included.
+pub async fn find_all_unfiltered(connection: &mut AsyncPgConnection) -> QueryResult<Vec<Media>> {
+    media::dsl::media
+        .select(Media::as_select())
+        .load(connection)
+        .await
+}
+
 pub async fn find_continue_watching(
     connection: &mut AsyncPgConnection,
     user_id: i64,
@@ -155,7 +221,15 @@ pub async fn find_continue_watching(
                m.files,
                m.attributes,
                m.parent_id,
-               m.library_id
+               m.library_id,
+               m.provider_ids,
+               m.credits,
+               m.tags,
+               m.collection,
+               m.metadata_status,
+               m.locked_fields,
+               m.metadata_refreshed_at,
+               m.translations
         FROM (SELECT *
               FROM next_episodes
               UNION
@@ -181,6 +255,15 @@ pub async fn create(
         .await
 }
 
+pub async fn delete(connection: &mut AsyncPgConnection, id: i64) -> QueryResult<usize> {
+    debug!("Deleting media entity {}", id);
+
+    diesel::delete(media::table)
+        .filter(media::dsl::id.eq(id))
+        .execute(connection)
+        .await
+}
+
 pub async fn update(connection: &mut AsyncPgConnection, entity: &Media) -> QueryResult<Media> {
     diesel::update(media::table)
         .filter(media::dsl::id.eq(entity.id))
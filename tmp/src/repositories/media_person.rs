@@ -0,0 +1,43 @@
+use crate::models::{InsertableMediaPerson, MediaPerson};
+use crate::schema::media_people;
+use diesel::prelude::*;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+
+pub async fn find_by_media_id(
+    connection: &mut AsyncPgConnection,
+    media_id: i64,
+) -> QueryResult<Vec<MediaPerson>> {
+    media_people::dsl::media_people
+        .filter(media_people::media_id.eq(media_id))
+        .order(media_people::id)
+        .select(MediaPerson::as_select())
+        .load(connection)
+        .await
+}
+
+pub async fn find_by_person_id(
+    connection: &mut AsyncPgConnection,
+    person_id: i64,
+) -> QueryResult<Vec<MediaPerson>> {
+    media_people::dsl::media_people
+        .filter(media_people::person_id.eq(person_id))
+        .select(MediaPerson::as_select())
+        .load(connection)
+        .await
+}
+
+pub async fn replace(
+    connection: &mut AsyncPgConnection,
+    media_id: i64,
+    entities: &[InsertableMediaPerson],
+) -> QueryResult<usize> {
+    diesel::delete(media_people::table)
+        .filter(media_people::media_id.eq(media_id))
+        .execute(connection)
+        .await?;
+
+    diesel::insert_into(media_people::table)
+        .values(entities)
+        .execute(connection)
+        .await
+}
@@ -1,4 +1,11 @@
+pub mod collection_item;
+pub mod data_migration;
+pub mod extra;
 pub mod history;
 pub mod library;
 pub mod media;
+pub mod media_person;
+pub mod person;
+pub mod provider_episode;
+pub mod schedule;
 pub mod user;
@@ -0,0 +1,100 @@
+use crate::models::{InsertablePerson, Person};
+use crate::schema::{media_people, people};
+use diesel::prelude::*;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use serde_json::json;
+use tracing::debug;
+
+pub async fn find_by_id(
+    connection: &mut AsyncPgConnection,
+    id: i64,
+) -> QueryResult<Option<Person>> {
+    people::dsl::people
+        .find(id)
+        .select(Person::as_select())
+        .first(connection)
+        .await
+        .optional()
+}
+
+pub async fn find_ids_in(
+    connection: &mut AsyncPgConnection,
+    ids: Vec<i64>,
+) -> QueryResult<Vec<i64>> {
+    people::dsl::people
+        .filter(people::id.eq_any(ids))
+        .select(people::id)
+        .load(connection)
+        .await
+}
+
+pub async fn find_by_ids(
+    connection: &mut AsyncPgConnection,
+    ids: Vec<i64>,
+) -> QueryResult<Vec<Person>> {
+    people::dsl::people
+        .filter(people::id.eq_any(ids))
+        .select(Person::as_select())
+        .load(connection)
+        .await
+}
+
+pub async fn find_by_provider_id(
+    connection: &mut AsyncPgConnection,
+    provider: &str,
+    id: &str,
+) -> QueryResult<Option<Person>> {
+    people::dsl::people
+        .filter(people::provider_ids.contains(json!({ provider: id })))
+        .select(Person::as_select())
+        .first(connection)
+        .await
+        .optional()
+}
+
+pub async fn find_by_match_key(
+    connection: &mut AsyncPgConnection,
+    match_key: &str,
+) -> QueryResult<Option<Person>> {
+    people::dsl::people
+        .filter(people::match_key.eq(match_key))
+        .select(Person::as_select())
+        .first(connection)
+        .await
+        .optional()
+}
+
+/// Creates the person, unless someone else created a person with the same `match_key` first.
+pub async fn create(
+    connection: &mut AsyncPgConnection,
+    entity: &InsertablePerson,
+) -> QueryResult<Option<Person>> {
+    debug!("Creating person entity {:?}", entity);
+
+    diesel::insert_into(people::table)
+        .values(entity)
+        .on_conflict(people::match_key)
+        .do_nothing()
+        .returning(Person::as_returning())
+        .get_result(connection)
+        .await
+        .optional()
+}
+
+pub async fn update(connection: &mut AsyncPgConnection, entity: &Person) -> QueryResult<Person> {
+    diesel::update(people::table)
+        .filter(people::dsl::id.eq(entity.id))
+        .set(entity)
+        .returning(Person::as_returning())
+        .get_result(connection)
+        .await
+}
+
+pub async fn delete_uncredited(connection: &mut AsyncPgConnection) -> QueryResult<usize> {
+    diesel::delete(people::table)
+        .filter(diesel::dsl::not(diesel::dsl::exists(
+            media_people::dsl::media_people.filter(media_people::person_id.eq(people::id)),
+        )))
+        .execute(connection)
+        .await
+}
@@ -0,0 +1,74 @@
+use crate::models::{InsertableProviderEpisode, ProviderEpisode};
+use crate::schema::provider_episodes;
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Bool, Nullable};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+
+/// Specials (season 0) are only included when asked for.
+pub async fn find_missing(
+    connection: &mut AsyncPgConnection,
+    media_id: Option<i64>,
+    library_id: Option<i64>,
+    include_specials: bool,
+) -> QueryResult<Vec<ProviderEpisode>> {
+    diesel::sql_query(
+        "
+        SELECT pe.*
+        FROM provider_episodes pe
+        INNER JOIN media s ON pe.media_id = s.id
+        WHERE ($1 IS NULL OR pe.media_id = $1)
+        AND ($2 IS NULL OR s.library_id = $2)
+        AND ($3 OR pe.season <> 0)
+        AND pe.aired <= CURRENT_DATE
+        AND NOT EXISTS (
+            SELECT 1
+            FROM media e
+            INNER JOIN media p ON e.parent_id = p.id
+            WHERE p.parent_id = pe.media_id
+            AND p.season = pe.season
+            AND e.episode = pe.episode
+        )
+        ORDER BY s.title, pe.media_id, pe.season, pe.episode;
+    ",
+    )
+    .bind::<Nullable<BigInt>, _>(media_id)
+    .bind::<Nullable<BigInt>, _>(library_id)
+    .bind::<Bool, _>(include_specials)
+    .get_results::<ProviderEpisode>(connection)
+    .await
+}
+
+pub async fn find_airing_between(
+    connection: &mut AsyncPgConnection,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> QueryResult<Vec<ProviderEpisode>> {
+    provider_episodes::dsl::provider_episodes
+        .filter(provider_episodes::aired.between(from, to))
+        .order((
+            provider_episodes::aired,
+            provider_episodes::media_id,
+            provider_episodes::season,
+            provider_episodes::episode,
+        ))
+        .select(ProviderEpisode::as_select())
+        .load(connection)
+        .await
+}
+
+pub async fn replace(
+    connection: &mut AsyncPgConnection,
+    media_id: i64,
+    entities: &[InsertableProviderEpisode],
+) -> QueryResult<usize> {
+    diesel::delete(provider_episodes::table)
+        .filter(provider_episodes::media_id.eq(media_id))
+        .execute(connection)
+        .await?;
+
+    diesel::insert_into(provider_episodes::table)
+        .values(entities)
+        .execute(connection)
+        .await
+}
@@ -0,0 +1,58 @@
+use crate::models::{InsertableSchedule, Schedule};
+use crate::schema::schedules;
+use diesel::prelude::*;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use tracing::debug;
+
+pub async fn find_by_id(
+    connection: &mut AsyncPgConnection,
+    id: i64,
+) -> QueryResult<Option<Schedule>> {
+    schedules::dsl::schedules
+        .find(id)
+        .select(Schedule::as_select())
+        .first(connection)
+        .await
+        .optional()
+}
+
+pub async fn find_all(connection: &mut AsyncPgConnection) -> QueryResult<Vec<Schedule>> {
+    schedules::dsl::schedules
+        .order((schedules::library_id, schedules::task))
+        .select(Schedule::as_select())
+        .load(connection)
+        .await
+}
+
+pub async fn find_all_enabled(connection: &mut AsyncPgConnection) -> QueryResult<Vec<Schedule>> {
+    schedules::dsl::schedules
+        .filter(schedules::enabled.eq(true))
+        .select(Schedule::as_select())
+        .load(connection)
+        .await
+}
+
+pub async fn create(
+    connection: &mut AsyncPgConnection,
+    entity: &InsertableSchedule,
+) -> QueryResult<Schedule> {
+    debug!("Creating schedule entity {:?}", entity);
+
+    diesel::insert_into(schedules::table)
+        .values(entity)
+        .returning(Schedule::as_returning())
+        .get_result(connection)
+        .await
+}
+
+pub async fn update(
+    connection: &mut AsyncPgConnection,
+    entity: &Schedule,
+) -> QueryResult<Schedule> {
+    diesel::update(schedules::table)
+        .filter(schedules::dsl::id.eq(entity.id))
+        .set(entity)
+        .returning(Schedule::as_returning())
+        .get_result(connection)
+        .await
+}
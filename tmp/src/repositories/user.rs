@@ -25,6 +25,18 @@ pub async fn find_by_email(
         .optional()
 }
 
+pub async fn find_by_calendar_token(
+    connection: &mut AsyncPgConnection,
+    calendar_token: &str,
+) -> QueryResult<Option<User>> {
+    users::dsl::users
+        .filter(users::calendar_token.eq(calendar_token))
+        .select(User::as_select())
+        .first(connection)
+        .await
+        .optional()
+}
+
 pub async fn count(connection: &mut AsyncPgConnection) -> QueryResult<i64> {
     users::dsl::users.count().get_result(connection).await
 }
@@ -41,3 +53,12 @@ pub async fn create(
         .get_result(connection)
         .await
 }
+
+pub async fn update(connection: &mut AsyncPgConnection, entity: &User) -> QueryResult<User> {
+    diesel::update(users::table)
+        .filter(users::dsl::id.eq(entity.id))
+        .set(entity)
+        .returning(User::as_returning())
+        .get_result(connection)
+        .await
+}
@@ -0,0 +1,58 @@
+use crate::calendar;
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::DbConn;
+use crate::repositories;
+use axum::extract::Query;
+use axum::http::{header, HeaderMap};
+use axum::response::IntoResponse;
+use chrono::{Days, Utc};
+use serde::Deserialize;
+use tracing::error;
+
+const PAST_DAYS: u64 = 7;
+const UPCOMING_DAYS: u64 = 90;
+
+#[derive(Deserialize)]
+pub struct QueryParams {
+    token: String,
+}
+
+/// Authenticated by the `token` calendar apps keep in the URL instead of a session.
+pub async fn get(
+    DbConn(mut connection): DbConn,
+    Query(query_params): Query<QueryParams>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some("/calendar/ical".to_string());
+
+    let user = repositories::user::find_by_calendar_token(&mut connection, &query_params.token)
+        .await
+        .map_err(|e| {
+            error!("Error calling user::find_by_calendar_token: {}", e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .ok_or(Problem::from(ProblemType::Forbidden(instance.clone())))?;
+
+    let now = Utc::now().naive_utc();
+    let today = now.date();
+    let from = today
+        .checked_sub_days(Days::new(PAST_DAYS))
+        .unwrap_or(today);
+    let to = today
+        .checked_add_days(Days::new(UPCOMING_DAYS))
+        .unwrap_or(today);
+
+    let episodes = calendar::upcoming(&mut connection, from, to, &user.metadata_languages)
+        .await
+        .map_err(|e| {
+            error!("Error calling calendar::upcoming: {}", e);
+            Problem::from(ProblemType::InternalServerError(instance))
+        })?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        "text/calendar; charset=utf-8".parse().unwrap(),
+    );
+
+    Ok((headers, calendar::ical(&episodes, now)))
+}
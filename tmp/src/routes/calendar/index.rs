@@ -0,0 +1,85 @@
+use crate::calendar;
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::{DbConn, OptionalAuthUser};
+use axum::extract::Query;
+use axum::response::IntoResponse;
+use axum::Json;
+use chrono::{Days, NaiveDate, Utc};
+use serde::Deserialize;
+use tracing::error;
+
+const DEFAULT_DAYS: u64 = 30;
+const MAX_DAYS: i64 = 366;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryParams {
+    from: Option<String>,
+    to: Option<String>,
+}
+
+fn parse_date(
+    name: &str,
+    value: Option<&String>,
+    instance: &Option<String>,
+) -> Result<Option<NaiveDate>, Problem> {
+    value
+        .map(|value| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_e| Problem {
+                r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                    .to_string(),
+                title: format!("{name} is not a valid date"),
+                status: 400,
+                detail: Some(format!("{name} {value} is not a date such as 2025-06-08")),
+                instance: instance.clone(),
+            })
+        })
+        .transpose()
+}
+
+pub async fn get(
+    DbConn(mut connection): DbConn,
+    auth_user: OptionalAuthUser,
+    Query(query_params): Query<QueryParams>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some("/calendar".to_string());
+
+    let from = parse_date("from", query_params.from.as_ref(), &instance)?
+        .unwrap_or(Utc::now().date_naive());
+    let to = parse_date("to", query_params.to.as_ref(), &instance)?
+        .or(from.checked_add_days(Days::new(DEFAULT_DAYS)))
+        .unwrap_or(from);
+
+    if to < from {
+        return Err(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                .to_string(),
+            title: "to is before from".to_string(),
+            status: 400,
+            detail: Some(format!("to {to} is before from {from}")),
+            instance,
+        });
+    }
+
+    if (to - from).num_days() > MAX_DAYS {
+        return Err(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                .to_string(),
+            title: "to is too far after from".to_string(),
+            status: 400,
+            detail: Some(format!(
+                "to {to} is more than {MAX_DAYS} days after from {from}"
+            )),
+            instance,
+        });
+    }
+
+    Ok(Json(
+        calendar::upcoming(&mut connection, from, to, auth_user.metadata_languages())
+            .await
+            .map_err(|e| {
+                error!("Error calling calendar::upcoming: {}", e);
+                Problem::from(ProblemType::InternalServerError(instance))
+            })?,
+    ))
+}
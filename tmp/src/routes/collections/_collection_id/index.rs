@@ -0,0 +1,103 @@
+use crate::collections;
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::{AuthUser, DbConn, OptionalAuthUser};
+use crate::models::Media;
+use crate::repositories;
+use crate::views::MediaView;
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use diesel_async::AsyncPgConnection;
+use tracing::error;
+
+pub(super) async fn find_collection(
+    connection: &mut AsyncPgConnection,
+    collection_id: &str,
+    instance: &Option<String>,
+) -> Result<Media, Problem> {
+    let collection_id = collection_id.parse::<i64>().map_err(|_e| Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: "collection_id is not a valid id".to_string(),
+        status: 400,
+        detail: Some(format!("collection_id {collection_id} is not a valid id")),
+        instance: instance.clone(),
+    })?;
+
+    repositories::media::find_by_id(connection, collection_id)
+        .await
+        .map_err(|e| {
+            error!(
+                "Error while fetching collection with id {}: {}",
+                collection_id, e
+            );
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .filter(|media| media.type_ == "collection")
+        .ok_or(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+                .to_string(),
+            title: "Collection not found".to_string(),
+            status: 404,
+            detail: Some(format!("Collection with id {collection_id} not found")),
+            instance: instance.clone(),
+        })
+}
+
+pub async fn get(
+    DbConn(mut connection): DbConn,
+    auth_user: OptionalAuthUser,
+    Path(collection_id): Path<String>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/collections/{collection_id}"));
+
+    Ok(Json(MediaView::localized(
+        find_collection(&mut connection, &collection_id, &instance).await?,
+        auth_user.metadata_languages(),
+    )))
+}
+
+/// Its items stay in the library, but lose the set they were in it through.
+pub async fn delete(
+    DbConn(mut connection): DbConn,
+    auth_user: AuthUser,
+    Path(collection_id): Path<String>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/collections/{collection_id}"));
+
+    if !auth_user.is_admin {
+        return Err(ProblemType::Forbidden(instance).into());
+    }
+
+    let collection = find_collection(&mut connection, &collection_id, &instance).await?;
+
+    let items =
+        repositories::collection_item::find_by_collection_id(&mut connection, collection.id)
+            .await
+            .map_err(|e| {
+                error!(
+                    "Error calling collection_item::find_by_collection_id: {}",
+                    e
+                );
+                Problem::from(ProblemType::InternalServerError(instance.clone()))
+            })?;
+
+    for item in &items {
+        collections::remove_item(&mut connection, item)
+            .await
+            .map_err(|e| {
+                error!("Error removing collection item: {:?}", e);
+                Problem::from(ProblemType::InternalServerError(instance.clone()))
+            })?;
+    }
+
+    repositories::media::delete(&mut connection, collection.id)
+        .await
+        .map_err(|e| {
+            error!("Error deleting collection: {}", e);
+            Problem::from(ProblemType::InternalServerError(instance))
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,64 @@
+use super::super::super::index::find_collection;
+use crate::collections;
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::{AuthUser, DbConn};
+use crate::repositories;
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use tracing::error;
+
+pub async fn delete(
+    DbConn(mut connection): DbConn,
+    auth_user: AuthUser,
+    Path((collection_id, media_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/collections/{collection_id}/items/{media_id}"));
+
+    if !auth_user.is_admin {
+        return Err(ProblemType::Forbidden(instance).into());
+    }
+
+    let collection = find_collection(&mut connection, &collection_id, &instance).await?;
+
+    let media_id = media_id.parse::<i64>().map_err(|_e| Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: "media_id is not a valid id".to_string(),
+        status: 400,
+        detail: Some(format!("media_id {media_id} is not a valid id")),
+        instance: instance.clone(),
+    })?;
+
+    let item = repositories::collection_item::find_by_collection_id(&mut connection, collection.id)
+        .await
+        .map_err(|e| {
+            error!(
+                "Error calling collection_item::find_by_collection_id: {}",
+                e
+            );
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .into_iter()
+        .find(|item| item.media_id == media_id)
+        .ok_or(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+                .to_string(),
+            title: "Collection item not found".to_string(),
+            status: 404,
+            detail: Some(format!(
+                "Media with id {media_id} is not in collection {}",
+                collection.id
+            )),
+            instance: instance.clone(),
+        })?;
+
+    collections::remove_item(&mut connection, &item)
+        .await
+        .map_err(|e| {
+            error!("Error removing collection item: {:?}", e);
+            Problem::from(ProblemType::InternalServerError(instance))
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,152 @@
+use super::super::index::find_collection;
+use crate::collections;
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::{AuthUser, DbConn, OptionalAuthUser};
+use crate::models::{InsertableCollectionItem, Media};
+use crate::repositories;
+use crate::views::MediaView;
+use axum::extract::Path;
+use axum::response::IntoResponse;
+use axum::Json;
+use diesel_async::AsyncPgConnection;
+use serde::Deserialize;
+use tracing::error;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddCollectionItem {
+    media_id: String,
+}
+
+async fn find_items(
+    connection: &mut AsyncPgConnection,
+    collection: &Media,
+    languages: &[String],
+    instance: &Option<String>,
+) -> Result<Vec<MediaView>, Problem> {
+    let media_ids = repositories::collection_item::find_by_collection_id(connection, collection.id)
+        .await
+        .map_err(|e| {
+            error!(
+                "Error calling collection_item::find_by_collection_id: {}",
+                e
+            );
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .into_iter()
+        .map(|item| item.media_id)
+        .collect();
+
+    let mut items = repositories::media::find_by_ids(connection, media_ids)
+        .await
+        .map_err(|e| {
+            error!("Error calling media::find_by_ids: {}", e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?;
+    collections::sort_by_release(&mut items);
+
+    Ok(items
+        .into_iter()
+        .map(|media| MediaView::localized(media, languages))
+        .collect())
+}
+
+pub async fn get(
+    DbConn(mut connection): DbConn,
+    auth_user: OptionalAuthUser,
+    Path(collection_id): Path<String>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/collections/{collection_id}/items"));
+
+    let collection = find_collection(&mut connection, &collection_id, &instance).await?;
+
+    Ok(Json(
+        find_items(
+            &mut connection,
+            &collection,
+            auth_user.metadata_languages(),
+            &instance,
+        )
+        .await?,
+    ))
+}
+
+/// Items added by hand are kept when scans and metadata providers update the sets.
+pub async fn post(
+    DbConn(mut connection): DbConn,
+    auth_user: AuthUser,
+    Path(collection_id): Path<String>,
+    Json(body): Json<AddCollectionItem>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/collections/{collection_id}/items"));
+
+    if !auth_user.is_admin {
+        return Err(ProblemType::Forbidden(instance).into());
+    }
+
+    let collection = find_collection(&mut connection, &collection_id, &instance).await?;
+
+    let media_id = body.media_id.parse::<i64>().map_err(|_e| Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: "mediaId is not a valid id".to_string(),
+        status: 400,
+        detail: Some(format!("mediaId {} is not a valid id", body.media_id)),
+        instance: instance.clone(),
+    })?;
+
+    let media = repositories::media::find_by_id(&mut connection, media_id)
+        .await
+        .map_err(|e| {
+            error!("Error while fetching media with id {}: {}", media_id, e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .ok_or(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+                .to_string(),
+            title: "Media not found".to_string(),
+            status: 404,
+            detail: Some(format!("Media with id {media_id} not found")),
+            instance: instance.clone(),
+        })?;
+
+    if media.parent_id.is_some()
+        || media.type_ == "collection"
+        || media.library_id != collection.library_id
+    {
+        return Err(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                .to_string(),
+            title: "Media cannot be added to the collection".to_string(),
+            status: 400,
+            detail: Some(format!(
+                "Media with id {media_id} is not a movie or show of the collection's library"
+            )),
+            instance,
+        });
+    }
+
+    repositories::collection_item::create(
+        &mut connection,
+        &InsertableCollectionItem {
+            collection_id: collection.id,
+            media_id,
+            set_name: None,
+        },
+    )
+    .await
+    .map_err(|e| {
+        error!("Error creating collection item: {}", e);
+        Problem::from(ProblemType::InternalServerError(instance.clone()))
+    })?;
+
+    Ok(Json(
+        find_items(
+            &mut connection,
+            &collection,
+            &auth_user.metadata_languages,
+            &instance,
+        )
+        .await?,
+    ))
+}
@@ -0,0 +1,12 @@
+use crate::state::AppState;
+use axum::routing::get;
+use axum::Router;
+
+mod _media_id;
+mod index;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(index::get).post(index::post))
+        .nest("/{media_id}", _media_id::routes())
+}
@@ -0,0 +1,12 @@
+use crate::state::AppState;
+use axum::routing::get;
+use axum::Router;
+
+mod index;
+mod items;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(index::get).delete(index::delete))
+        .nest("/items", items::routes())
+}
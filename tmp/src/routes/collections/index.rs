@@ -0,0 +1,137 @@
+use crate::collections;
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::{AuthUser, DbConn, OptionalAuthUser};
+use crate::models::MediaSet;
+use crate::repositories;
+use crate::repositories::media::{MediaCriteria, MediaCriteriaOrder};
+use crate::state::AppState;
+use crate::views::MediaView;
+use axum::extract::{Query, State};
+use axum::response::IntoResponse;
+use axum::Json;
+use serde::Deserialize;
+use tracing::error;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryParams {
+    library_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCollection {
+    library_id: String,
+    title: String,
+    overview: Option<String>,
+}
+
+pub async fn get(
+    DbConn(mut connection): DbConn,
+    auth_user: OptionalAuthUser,
+    Query(query_params): Query<QueryParams>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some("/collections".to_string());
+
+    let library_id = query_params
+        .library_id
+        .as_ref()
+        .map_or(Ok(None), |id| id.parse::<i64>().map(Some))
+        .map_err(|_e| Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                .to_string(),
+            title: "library_id is not a valid id".to_string(),
+            status: 400,
+            detail: Some(format!(
+                "library_id {} is not a valid id",
+                query_params.library_id.clone().unwrap()
+            )),
+            instance: instance.clone(),
+        })?;
+
+    Ok(Json(
+        repositories::media::find_all(
+            &mut connection,
+            MediaCriteria {
+                types: Some(vec!["collection".to_string()]),
+                library_id,
+                order_by: Some(MediaCriteriaOrder::Title),
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(|e| {
+            error!("Error calling media::find_all: {}", e);
+            Problem::from(ProblemType::InternalServerError(instance))
+        })?
+        .into_iter()
+        .map(|media| MediaView::localized(media, auth_user.metadata_languages()))
+        .collect::<Vec<_>>(),
+    ))
+}
+
+pub async fn post(
+    State(state): State<AppState>,
+    DbConn(mut connection): DbConn,
+    auth_user: AuthUser,
+    Json(body): Json<CreateCollection>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some("/collections".to_string());
+
+    if !auth_user.is_admin {
+        return Err(ProblemType::Forbidden(instance).into());
+    }
+
+    let library_id = body.library_id.parse::<i64>().map_err(|_e| Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: "libraryId is not a valid id".to_string(),
+        status: 400,
+        detail: Some(format!("libraryId {} is not a valid id", body.library_id)),
+        instance: instance.clone(),
+    })?;
+
+    let title = body.title.trim().to_string();
+    if title.is_empty() {
+        return Err(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                .to_string(),
+            title: "title must not be empty".to_string(),
+            status: 400,
+            detail: Some("title must not be empty".to_string()),
+            instance,
+        });
+    }
+
+    repositories::library::find_by_id(&mut connection, library_id)
+        .await
+        .map_err(|e| {
+            error!("Error while fetching library with id {}: {}", library_id, e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .ok_or(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+                .to_string(),
+            title: "Library not found".to_string(),
+            status: 404,
+            detail: Some(format!("Library with id {library_id} not found")),
+            instance: instance.clone(),
+        })?;
+
+    let collection = collections::create(
+        &state,
+        &mut connection,
+        library_id,
+        &MediaSet {
+            name: title,
+            overview: body.overview,
+        },
+    )
+    .await
+    .map_err(|e| {
+        error!("Error creating collection: {:?}", e);
+        Problem::from(ProblemType::InternalServerError(instance))
+    })?;
+
+    Ok(Json(MediaView::from(collection)))
+}
@@ -0,0 +1,162 @@
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::{AuthUser, DbConn};
+use crate::models::EpisodeOrdering;
+use crate::repositories;
+use crate::state::AppState;
+use axum::extract::{Path, State};
+use axum::response::IntoResponse;
+use axum::Json;
+use chrono::Utc;
+use ignore::gitignore::GitignoreBuilder;
+use serde::Deserialize;
+use std::str::FromStr;
+use tracing::error;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateLibrary {
+    name: Option<String>,
+    ignore_patterns: Option<Vec<String>>,
+    metadata_provider: Option<String>,
+    episode_ordering: Option<String>,
+    preferred_languages: Option<Vec<String>>,
+    metadata_refresh_days: Option<i32>,
+}
+
+pub async fn patch(
+    State(state): State<AppState>,
+    DbConn(mut connection): DbConn,
+    auth_user: AuthUser,
+    Path(library_id): Path<String>,
+    Json(body): Json<UpdateLibrary>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/libraries/{library_id}"));
+
+    if !auth_user.is_admin {
+        return Err(ProblemType::Forbidden(instance).into());
+    }
+
+    let library_id = library_id.parse::<i64>().map_err(|_e| Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: "library_id is not a valid id".to_string(),
+        status: 400,
+        detail: Some(format!("library_id {library_id} is not a valid id")),
+        instance: instance.clone(),
+    })?;
+
+    let mut library = repositories::library::find_by_id(&mut connection, library_id)
+        .await
+        .map_err(|e| {
+            error!("Error while fetching library with id {}: {}", library_id, e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .ok_or(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+                .to_string(),
+            title: "Library not found".to_string(),
+            status: 404,
+            detail: Some(format!("Library with id {library_id} not found")),
+            instance: instance.clone(),
+        })?;
+
+    if let Some(name) = body.name {
+        library.name = name;
+    }
+
+    if let Some(ignore_patterns) = body.ignore_patterns {
+        let mut builder = GitignoreBuilder::new(&library.path);
+        for pattern in &ignore_patterns {
+            builder.add_line(None, pattern).map_err(|e| Problem {
+                r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                    .to_string(),
+                title: "ignorePatterns contains an invalid pattern".to_string(),
+                status: 400,
+                detail: Some(e.to_string()),
+                instance: instance.clone(),
+            })?;
+        }
+        library.ignore_patterns = ignore_patterns;
+    }
+
+    if let Some(metadata_provider) = body.metadata_provider {
+        if state
+            .metadata_provider_factory
+            .get_provider(&metadata_provider)
+            .is_none()
+        {
+            return Err(Problem {
+                r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                    .to_string(),
+                title: "metadataProvider is not a configured provider".to_string(),
+                status: 400,
+                detail: Some(format!(
+                    "Metadata provider {metadata_provider} is not configured"
+                )),
+                instance,
+            });
+        }
+        library.metadata_provider = metadata_provider;
+    }
+
+    if let Some(episode_ordering) = body.episode_ordering {
+        let episode_ordering =
+            EpisodeOrdering::from_str(&episode_ordering).map_err(|e| Problem {
+                r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                    .to_string(),
+                title: "episodeOrdering is not a valid ordering".to_string(),
+                status: 400,
+                detail: Some(e),
+                instance: instance.clone(),
+            })?;
+        library.episode_ordering = episode_ordering.as_str().to_string();
+    }
+
+    if let Some(preferred_languages) = body.preferred_languages {
+        if preferred_languages
+            .iter()
+            .any(|language| language.trim().is_empty())
+        {
+            return Err(Problem {
+                r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                    .to_string(),
+                title: "preferredLanguages contains an empty language".to_string(),
+                status: 400,
+                detail: Some("Languages must be ISO 639-1 codes such as en".to_string()),
+                instance,
+            });
+        }
+        library.preferred_languages = preferred_languages
+            .iter()
+            .map(|language| language.trim().to_lowercase())
+            .collect();
+    }
+
+    if let Some(metadata_refresh_days) = body.metadata_refresh_days {
+        if metadata_refresh_days < 1 {
+            return Err(Problem {
+                r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                    .to_string(),
+                title: "metadataRefreshDays must be at least 1".to_string(),
+                status: 400,
+                detail: Some(format!(
+                    "metadataRefreshDays {metadata_refresh_days} is not a number of days"
+                )),
+                instance,
+            });
+        }
+        library.metadata_refresh_days = metadata_refresh_days;
+    }
+
+    library.updated_at = Utc::now().naive_utc();
+    library.updated_by.clone_from(&auth_user.name);
+
+    let library = repositories::library::update(&mut connection, &library)
+        .await
+        .map_err(|e| {
+            error!("Error updating library: {}", e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?;
+
+    Ok(Json(library))
+}
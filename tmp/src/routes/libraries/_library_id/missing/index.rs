@@ -0,0 +1,94 @@
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::{DbConn, OptionalAuthUser};
+use crate::repositories;
+use crate::views::{MediaView, MissingEpisodeView, MissingEpisodesView};
+use axum::extract::{Path, Query};
+use axum::response::IntoResponse;
+use axum::Json;
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::error;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryParams {
+    include_specials: Option<bool>,
+}
+
+pub async fn get(
+    DbConn(mut connection): DbConn,
+    auth_user: OptionalAuthUser,
+    Path(library_id): Path<String>,
+    Query(query_params): Query<QueryParams>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/libraries/{library_id}/missing"));
+
+    let library_id = library_id.parse::<i64>().map_err(|_e| Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: "library_id is not a valid id".to_string(),
+        status: 400,
+        detail: Some(format!("library_id {library_id} is not a valid id")),
+        instance: instance.clone(),
+    })?;
+
+    repositories::library::find_by_id(&mut connection, library_id)
+        .await
+        .map_err(|e| {
+            error!("Error while fetching library with id {}: {}", library_id, e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .ok_or(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+                .to_string(),
+            title: "Library not found".to_string(),
+            status: 404,
+            detail: Some(format!("Library with id {library_id} not found")),
+            instance: instance.clone(),
+        })?;
+
+    let episodes = repositories::provider_episode::find_missing(
+        &mut connection,
+        None,
+        Some(library_id),
+        query_params.include_specials.unwrap_or(false),
+    )
+    .await
+    .map_err(|e| {
+        error!("Error calling provider_episode::find_missing: {}", e);
+        Problem::from(ProblemType::InternalServerError(instance.clone()))
+    })?;
+
+    let mut shows: HashMap<i64, _> = repositories::media::find_by_ids(
+        &mut connection,
+        episodes.iter().map(|episode| episode.media_id).collect(),
+    )
+    .await
+    .map_err(|e| {
+        error!("Error calling media::find_by_ids: {}", e);
+        Problem::from(ProblemType::InternalServerError(instance))
+    })?
+    .into_iter()
+    .map(|show| (show.id, show))
+    .collect();
+
+    // Episodes come grouped by show
+    let mut missing: Vec<MissingEpisodesView> = Vec::new();
+    for episode in episodes {
+        let show_id = episode.media_id.to_string();
+        if missing.last().is_none_or(|last| last.show.id != show_id) {
+            let Some(show) = shows.remove(&episode.media_id) else {
+                continue;
+            };
+            missing.push(MissingEpisodesView {
+                show: MediaView::localized(show, auth_user.metadata_languages()),
+                episodes: Vec::new(),
+            });
+        }
+        if let Some(last) = missing.last_mut() {
+            last.episodes.push(MissingEpisodeView::from(episode));
+        }
+    }
+
+    Ok(Json(missing))
+}
@@ -0,0 +1,14 @@
+use crate::state::AppState;
+use axum::routing::patch;
+use axum::Router;
+
+mod index;
+mod missing;
+mod nfo;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", patch(index::patch))
+        .nest("/missing", missing::routes())
+        .nest("/nfo", nfo::routes())
+}
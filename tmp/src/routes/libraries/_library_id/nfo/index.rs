@@ -0,0 +1,56 @@
+use crate::errors::{Problem, ProblemType};
+use crate::jobs::export_library_nfo::{ExportLibraryNfo, ExportLibraryNfoPayload};
+use crate::middlware::{AuthUser, DbConn};
+use crate::repositories;
+use crate::state::AppState;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use tracing::error;
+
+pub async fn post(
+    State(state): State<AppState>,
+    DbConn(mut connection): DbConn,
+    auth_user: AuthUser,
+    Path(library_id): Path<String>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/libraries/{library_id}/nfo"));
+
+    if !auth_user.is_admin {
+        return Err(ProblemType::Forbidden(instance).into());
+    }
+
+    let library_id = library_id.parse::<i64>().map_err(|_e| Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: "library_id is not a valid id".to_string(),
+        status: 400,
+        detail: Some(format!("library_id {library_id} is not a valid id")),
+        instance: instance.clone(),
+    })?;
+
+    let library = repositories::library::find_by_id(&mut connection, library_id)
+        .await
+        .map_err(|e| {
+            error!("Error while fetching library with id {}: {}", library_id, e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .ok_or(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+                .to_string(),
+            title: "Library not found".to_string(),
+            status: 404,
+            detail: Some(format!("Library with id {library_id} not found")),
+            instance: instance.clone(),
+        })?;
+
+    if let Err(e) = state.queue.send(Box::new(ExportLibraryNfo::new(
+        state.clone(),
+        ExportLibraryNfoPayload::new(library.id),
+    ))) {
+        error!("Failed to add job to queue: {:?}", e);
+        return Err(ProblemType::InternalServerError(instance).into());
+    }
+
+    Ok(StatusCode::ACCEPTED)
+}
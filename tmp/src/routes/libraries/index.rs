@@ -1,13 +1,15 @@
 use crate::errors::{Problem, ProblemType};
 use crate::jobs::scan_library::{ScanLibrary, ScanLibraryPayload};
 use crate::middlware::{AuthUser, DbConn};
-use crate::models::InsertableLibrary;
+use crate::models::{EpisodeOrdering, InsertableLibrary, ProviderIds};
 use crate::repositories;
+use crate::scheduler;
 use crate::state::AppState;
 use axum::extract::State;
 use axum::response::IntoResponse;
 use axum::Json;
 use serde::Deserialize;
+use std::str::FromStr;
 use tracing::error;
 
 #[derive(Deserialize)]
@@ -16,6 +18,12 @@ pub struct CreateLibrary {
     name: String,
     path: String,
     media_type: String,
+    #[serde(default)]
+    ignore_patterns: Vec<String>,
+    metadata_provider: Option<String>,
+    episode_ordering: Option<String>,
+    preferred_languages: Option<Vec<String>>,
+    metadata_refresh_days: Option<i32>,
 }
 
 pub async fn post(
@@ -26,6 +34,73 @@ pub async fn post(
 ) -> Result<impl IntoResponse, Problem> {
     let instance = Some("/libraries".to_string());
 
+    let metadata_provider = body
+        .metadata_provider
+        .clone()
+        .unwrap_or(ProviderIds::TMDB.to_string());
+    if state
+        .metadata_provider_factory
+        .get_provider(&metadata_provider)
+        .is_none()
+    {
+        return Err(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                .to_string(),
+            title: "metadataProvider is not a configured provider".to_string(),
+            status: 400,
+            detail: Some(format!(
+                "Metadata provider {metadata_provider} is not configured"
+            )),
+            instance,
+        });
+    }
+
+    let episode_ordering = match &body.episode_ordering {
+        Some(episode_ordering) => {
+            EpisodeOrdering::from_str(episode_ordering).map_err(|e| Problem {
+                r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                    .to_string(),
+                title: "episodeOrdering is not a valid ordering".to_string(),
+                status: 400,
+                detail: Some(e),
+                instance: instance.clone(),
+            })?
+        }
+        None => EpisodeOrdering::Aired,
+    };
+
+    let preferred_languages = body
+        .preferred_languages
+        .clone()
+        .unwrap_or(vec!["en".to_string()]);
+    if preferred_languages
+        .iter()
+        .any(|language| language.trim().is_empty())
+    {
+        return Err(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                .to_string(),
+            title: "preferredLanguages contains an empty language".to_string(),
+            status: 400,
+            detail: Some("Languages must be ISO 639-1 codes such as en".to_string()),
+            instance,
+        });
+    }
+
+    let metadata_refresh_days = body.metadata_refresh_days.unwrap_or(30);
+    if metadata_refresh_days < 1 {
+        return Err(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                .to_string(),
+            title: "metadataRefreshDays must be at least 1".to_string(),
+            status: 400,
+            detail: Some(format!(
+                "metadataRefreshDays {metadata_refresh_days} is not a number of days"
+            )),
+            instance,
+        });
+    }
+
     let library = repositories::library::create(
         &mut connection,
         &InsertableLibrary {
@@ -34,6 +109,14 @@ pub async fn post(
             name: body.name.clone(),
             path: body.path.clone(),
             media_type: body.media_type.clone().to_string(),
+            ignore_patterns: body.ignore_patterns.clone(),
+            metadata_provider,
+            episode_ordering: episode_ordering.as_str().to_string(),
+            preferred_languages: preferred_languages
+                .iter()
+                .map(|language| language.trim().to_lowercase())
+                .collect(),
+            metadata_refresh_days,
         },
     )
     .await
@@ -42,6 +125,15 @@ pub async fn post(
         Problem::from(ProblemType::InternalServerError(instance.clone()))
     })?;
 
+    for schedule in scheduler::default_library_schedules(&library, &auth_user.name) {
+        repositories::schedule::create(&mut connection, &schedule)
+            .await
+            .map_err(|e| {
+                error!("Error creating schedule: {:?}", e);
+                Problem::from(ProblemType::InternalServerError(instance.clone()))
+            })?;
+    }
+
     if let Err(e) = state.queue.send(Box::new(ScanLibrary::new(
         state.clone(),
         ScanLibraryPayload::new(library.id),
@@ -2,8 +2,11 @@ use crate::state::AppState;
 use axum::routing::post;
 use axum::Router;
 
+mod _library_id;
 mod index;
 
 pub fn routes() -> Router<AppState> {
-    Router::new().route("/", post(index::post))
+    Router::new()
+        .route("/", post(index::post))
+        .nest("/{library_id}", _library_id::routes())
 }
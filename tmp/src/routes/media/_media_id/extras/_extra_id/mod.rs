@@ -0,0 +1,8 @@
+use crate::state::AppState;
+use axum::Router;
+
+mod stream;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().nest("/stream", stream::routes())
+}
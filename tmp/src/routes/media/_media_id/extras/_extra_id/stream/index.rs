@@ -0,0 +1,55 @@
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::{AuthUser, DbConn};
+use crate::repositories;
+use crate::streaming::stream_file;
+use axum::extract::Path;
+use axum::http::HeaderMap;
+use axum::response::IntoResponse;
+use tracing::error;
+
+pub async fn get(
+    DbConn(mut connection): DbConn,
+    _: AuthUser,
+    Path((media_id, extra_id)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/media/{media_id}/extras/{extra_id}/stream"));
+
+    let media_id = media_id.parse::<i64>().map_err(|_e| Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: "media_id is not a valid id".to_string(),
+        status: 400,
+        detail: Some(format!("media_id {media_id} is not a valid id")),
+        instance: instance.clone(),
+    })?;
+
+    let extra_id = extra_id.parse::<i64>().map_err(|_e| Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: "extra_id is not a valid id".to_string(),
+        status: 400,
+        detail: Some(format!("extra_id {extra_id} is not a valid id")),
+        instance: instance.clone(),
+    })?;
+
+    let extra = repositories::extra::find_by_id(&mut connection, extra_id)
+        .await
+        .map_err(|e| {
+            error!("Error while fetching extra with id {}: {}", extra_id, e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .filter(|extra| extra.media_id == media_id)
+        .ok_or(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+                .to_string(),
+            title: "Extra not found".to_string(),
+            status: 404,
+            detail: Some(format!(
+                "Extra with id {extra_id} not found for Media with id {media_id}"
+            )),
+            instance: instance.clone(),
+        })?;
+
+    stream_file(std::path::Path::new(&extra.path), &headers, instance).await
+}
@@ -0,0 +1,38 @@
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::DbConn;
+use crate::repositories;
+use crate::views::ExtraView;
+use axum::extract::Path;
+use axum::response::IntoResponse;
+use axum::Json;
+use tracing::error;
+
+pub async fn get(
+    DbConn(mut connection): DbConn,
+    Path(media_id): Path<String>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/media/{media_id}/extras"));
+
+    let media_id = media_id.parse::<i64>().map_err(|_e| Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: "media_id is not a valid id".to_string(),
+        status: 400,
+        detail: Some(format!("media_id {media_id} is not a valid id")),
+        instance: instance.clone(),
+    })?;
+
+    let extras = repositories::extra::find_by_media_id(&mut connection, media_id)
+        .await
+        .map_err(|e| {
+            error!("Error while fetching extras of media {}: {}", media_id, e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?;
+
+    Ok(Json(
+        extras
+            .into_iter()
+            .map(ExtraView::from)
+            .collect::<Vec<ExtraView>>(),
+    ))
+}
@@ -0,0 +1,12 @@
+use crate::state::AppState;
+use axum::routing::get;
+use axum::Router;
+
+mod _extra_id;
+mod index;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(index::get))
+        .nest("/{extra_id}", _extra_id::routes())
+}
@@ -0,0 +1,175 @@
+use crate::errors::{Problem, ProblemType};
+use crate::factories::metadata_provider::{rank_search_results, MetadataProvider};
+use crate::jobs::identify_media::{IdentifyMedia, IdentifyMediaPayload};
+use crate::middlware::{AuthUser, DbConn};
+use crate::models::{Media, MediaAttributes};
+use crate::repositories;
+use crate::state::AppState;
+use crate::views::SearchResultView;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use diesel_async::AsyncPgConnection;
+use serde::Deserialize;
+use tracing::error;
+
+#[derive(Deserialize)]
+pub struct QueryParams {
+    title: Option<String>,
+    year: Option<i32>,
+}
+
+#[derive(Deserialize)]
+pub struct IdentifyBody {
+    id: String,
+}
+
+async fn find_media<'a>(
+    state: &'a AppState,
+    connection: &mut AsyncPgConnection,
+    media_id: &str,
+    instance: &Option<String>,
+) -> Result<(Media, &'a (dyn MetadataProvider + Send + Sync)), Problem> {
+    let media_id = media_id.parse::<i64>().map_err(|_e| Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: "media_id is not a valid id".to_string(),
+        status: 400,
+        detail: Some(format!("media_id {media_id} is not a valid id")),
+        instance: instance.clone(),
+    })?;
+
+    let media = repositories::media::find_by_id(connection, media_id)
+        .await
+        .map_err(|e| {
+            error!("Error while fetching media with id {}: {}", media_id, e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .ok_or(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+                .to_string(),
+            title: "Media not found".to_string(),
+            status: 404,
+            detail: Some(format!("Media with id {media_id} not found")),
+            instance: instance.clone(),
+        })?;
+
+    if media.parent_id.is_some() {
+        return Err(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                .to_string(),
+            title: "Media cannot be identified".to_string(),
+            status: 400,
+            detail: Some(format!(
+                "Media with id {media_id} is a season or episode, identify its show instead"
+            )),
+            instance: instance.clone(),
+        });
+    }
+
+    let library = repositories::library::find_by_id(connection, media.library_id)
+        .await
+        .map_err(|e| {
+            error!(
+                "Error while fetching library with id {}: {}",
+                media.library_id, e
+            );
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .ok_or(Problem::from(ProblemType::InternalServerError(
+            instance.clone(),
+        )))?;
+
+    let provider = state
+        .metadata_provider_factory
+        .get_provider(&library.metadata_provider)
+        .ok_or(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                .to_string(),
+            title: "Metadata provider is not configured".to_string(),
+            status: 400,
+            detail: Some(format!(
+                "Metadata provider {} of Library {} is not configured",
+                library.metadata_provider, library.id
+            )),
+            instance: instance.clone(),
+        })?;
+
+    Ok((media, provider))
+}
+
+pub async fn get(
+    State(state): State<AppState>,
+    DbConn(mut connection): DbConn,
+    auth_user: AuthUser,
+    Path(media_id): Path<String>,
+    Query(query_params): Query<QueryParams>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/media/{media_id}/identify"));
+
+    if !auth_user.is_admin {
+        return Err(ProblemType::Forbidden(instance).into());
+    }
+
+    let (media, provider) = find_media(&state, &mut connection, &media_id, &instance).await?;
+
+    let attributes = MediaAttributes::from(&media.attributes);
+    let title = query_params
+        .title
+        .or(attributes.title)
+        .unwrap_or(media.title.clone());
+    let year = query_params.year.or(attributes.year);
+
+    let results = provider
+        .search(&media.type_, &title, year)
+        .await
+        .map_err(|e| {
+            error!("Error searching {} for {}: {}", provider.name(), title, e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?;
+
+    Ok(Json(
+        rank_search_results(results, &title, year)
+            .into_iter()
+            .map(|result| SearchResultView::from((provider.name().to_string(), result)))
+            .collect::<Vec<_>>(),
+    ))
+}
+
+pub async fn post(
+    State(state): State<AppState>,
+    DbConn(mut connection): DbConn,
+    auth_user: AuthUser,
+    Path(media_id): Path<String>,
+    Json(body): Json<IdentifyBody>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/media/{media_id}/identify"));
+
+    if !auth_user.is_admin {
+        return Err(ProblemType::Forbidden(instance).into());
+    }
+
+    let (media, _) = find_media(&state, &mut connection, &media_id, &instance).await?;
+
+    if body.id.trim().is_empty() {
+        return Err(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                .to_string(),
+            title: "id must not be empty".to_string(),
+            status: 400,
+            detail: Some("id must be the id of a search result".to_string()),
+            instance,
+        });
+    }
+
+    if let Err(e) = state.queue.send(Box::new(IdentifyMedia::new(
+        state.clone(),
+        IdentifyMediaPayload::new(media.id, body.id.trim().to_string()),
+    ))) {
+        error!("Failed to add job to queue: {:?}", e);
+        return Err(ProblemType::InternalServerError(instance).into());
+    }
+
+    Ok(StatusCode::ACCEPTED)
+}
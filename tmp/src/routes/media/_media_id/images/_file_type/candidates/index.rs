@@ -0,0 +1,61 @@
+use super::super::index::{find_media, parse_artwork_path};
+use crate::errors::{Problem, ProblemType};
+use crate::factories::artwork_fetcher::find_candidates;
+use crate::middlware::{AuthUser, DbConn};
+use crate::repositories;
+use crate::state::AppState;
+use crate::views::ArtworkCandidateView;
+use axum::extract::{Path, State};
+use axum::response::IntoResponse;
+use axum::Json;
+use tracing::error;
+
+pub async fn get(
+    State(state): State<AppState>,
+    DbConn(mut connection): DbConn,
+    auth_user: AuthUser,
+    Path((media_id, file_type)): Path<(String, String)>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/media/{media_id}/images/{file_type}/candidates"));
+
+    if !auth_user.is_admin {
+        return Err(ProblemType::Forbidden(instance).into());
+    }
+
+    let (media_id, file_type) = parse_artwork_path(&media_id, &file_type, &instance)?;
+    let media = find_media(&mut connection, media_id, &instance).await?;
+
+    if media.parent_id.is_some() {
+        return Err(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                .to_string(),
+            title: "Media has no artwork candidates".to_string(),
+            status: 400,
+            detail: Some(format!(
+                "Media with id {media_id} is a season or episode, candidates are only listed for movies and shows"
+            )),
+            instance,
+        });
+    }
+
+    let library = repositories::library::find_by_id(&mut connection, media.library_id)
+        .await
+        .map_err(|e| {
+            error!(
+                "Error while fetching library with id {}: {}",
+                media.library_id, e
+            );
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .ok_or(Problem::from(ProblemType::InternalServerError(
+            instance.clone(),
+        )))?;
+
+    Ok(Json(
+        find_candidates(&state, &media, &library, &file_type)
+            .await
+            .into_iter()
+            .map(ArtworkCandidateView::from)
+            .collect::<Vec<_>>(),
+    ))
+}
@@ -1,19 +1,51 @@
 use crate::errors::{Problem, ProblemType};
-use crate::middlware::DbConn;
-use crate::models::FileType;
+use crate::factories::artwork_fetcher::find_candidates;
+use crate::factories::library_scanner::artwork::content_type;
+use crate::images::{accepts, has_alpha, ImageFormat, ImageOptions, DEFAULT_QUALITY};
+use crate::jobs::fetch_artwork::{FetchArtwork, FetchArtworkPayload};
+use crate::middlware::{AuthUser, DbConn};
+use crate::models::{self, FileSource, FileType, Media};
 use crate::repositories;
-use axum::body::Body;
-use axum::extract::Path;
+use crate::state::AppState;
+use crate::views::MediaView;
+use axum::body::{Body, Bytes};
+use axum::extract::{Path, Query, State};
 use axum::http::{header, HeaderMap, StatusCode};
 use axum::response::IntoResponse;
+use axum::Json;
+use diesel_async::AsyncPgConnection;
+use serde::Deserialize;
 use std::path::PathBuf;
+use std::str::FromStr;
 use tokio::fs::File;
 use tokio_util::io::ReaderStream;
 use tracing::error;
 
+#[derive(Deserialize)]
+pub struct QueryParams {
+    width: Option<u32>,
+    height: Option<u32>,
+    quality: Option<u8>,
+    format: Option<String>,
+}
+
+fn bad_request(title: &str, detail: String, instance: &Option<String>) -> Problem {
+    Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: title.to_string(),
+        status: 400,
+        detail: Some(detail),
+        instance: instance.clone(),
+    }
+}
+
 pub async fn get(
+    State(state): State<AppState>,
     DbConn(mut connection): DbConn,
     Path((media_id, file_type)): Path<(String, String)>,
+    Query(query_params): Query<QueryParams>,
+    headers: HeaderMap,
 ) -> Result<impl IntoResponse, Problem> {
     let instance = Some(format!("/media/{media_id}/fanart"));
 
@@ -35,6 +67,32 @@ pub async fn get(
         instance: instance.clone(),
     })?;
 
+    if query_params.width == Some(0) || query_params.height == Some(0) {
+        return Err(bad_request(
+            "width and height must be positive",
+            "width and height must be at least 1".to_string(),
+            &instance,
+        ));
+    }
+
+    if query_params
+        .quality
+        .is_some_and(|quality| !(1..=100).contains(&quality))
+    {
+        return Err(bad_request(
+            "quality is out of range",
+            "quality must be between 1 and 100".to_string(),
+            &instance,
+        ));
+    }
+
+    let format = query_params
+        .format
+        .as_deref()
+        .map(ImageFormat::from_str)
+        .transpose()
+        .map_err(|e| bad_request("format is not a valid format", e, &instance))?;
+
     let mut media = repositories::media::find_by_id(&mut connection, media_id)
         .await
         .map_err(|e| {
@@ -63,31 +121,81 @@ pub async fn get(
             instance: instance.clone(),
         })?;
 
-    let mut components = vec![Some(image.path.clone()), media.path];
-    while let Some(parent_id) = media.parent_id {
-        media = repositories::media::find_by_id(&mut connection, parent_id)
+    let path = if image.source == FileSource::Metadata {
+        state.metadata_store.path(image)
+    } else {
+        let mut components = vec![Some(image.path.clone()), media.path];
+        while let Some(parent_id) = media.parent_id {
+            media = repositories::media::find_by_id(&mut connection, parent_id)
+                .await
+                .map_err(|e| {
+                    error!("Error while fetching media with id {}: {}", parent_id, e);
+                    Problem::from(ProblemType::InternalServerError(instance.clone()))
+                })?
+                .ok_or(Problem {
+                    r#type:
+                        "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+                            .to_string(),
+                    title: "Media not found".to_string(),
+                    status: 404,
+                    detail: Some(format!("Media with id {parent_id} not found")),
+                    instance: instance.clone(),
+                })?;
+
+            components.push(media.path);
+        }
+
+        components
+            .into_iter()
+            .rev()
+            .flatten()
+            .fold(PathBuf::new(), |acc, component| acc.join(component))
+    };
+
+    let accept = headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok());
+
+    let derive = query_params.width.is_some()
+        || query_params.height.is_some()
+        || query_params.quality.is_some()
+        || format.is_some()
+        || !accepts(accept, content_type(&path), true);
+
+    let mut response_headers = HeaderMap::new();
+    if format.is_none() {
+        response_headers.insert(header::VARY, "Accept".parse().unwrap());
+    }
+
+    let path = if derive {
+        let format = match format {
+            Some(format) => format,
+            None => ImageFormat::negotiate(accept, has_alpha(&path).await),
+        };
+        let options = ImageOptions {
+            width: query_params.width,
+            height: query_params.height,
+            quality: query_params.quality.unwrap_or(DEFAULT_QUALITY),
+            format,
+        };
+
+        response_headers.insert(
+            header::CONTENT_TYPE,
+            options.format.content_type().parse().unwrap(),
+        );
+
+        state
+            .image_cache
+            .get_or_create(&path, &options)
             .await
             .map_err(|e| {
-                error!("Error while fetching media with id {}: {}", parent_id, e);
+                error!("Error while deriving image {:?}: {}", path, e);
                 Problem::from(ProblemType::InternalServerError(instance.clone()))
             })?
-            .ok_or(Problem {
-                r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
-                    .to_string(),
-                title: "Media not found".to_string(),
-                status: 404,
-                detail: Some(format!("Media with id {parent_id} not found")),
-                instance: instance.clone(),
-            })?;
-
-        components.push(media.path);
-    }
-
-    let path = components
-        .into_iter()
-        .rev()
-        .flatten()
-        .fold(PathBuf::new(), |acc, component| acc.join(component));
+    } else {
+        response_headers.insert(header::CONTENT_TYPE, content_type(&path).parse().unwrap());
+        path
+    };
 
     let file = File::open(path.clone()).await.map_err(|e| {
         error!(
@@ -100,8 +208,247 @@ pub async fn get(
 
     let stream = ReaderStream::new(file);
 
-    let mut response_headers = HeaderMap::new();
-    response_headers.insert(header::CONTENT_TYPE, "images/webp".parse().unwrap());
-
     Ok((StatusCode::OK, response_headers, Body::from_stream(stream)))
 }
+
+#[derive(Deserialize)]
+pub struct SelectArtwork {
+    url: String,
+}
+
+pub(super) fn parse_artwork_path(
+    media_id: &str,
+    file_type: &str,
+    instance: &Option<String>,
+) -> Result<(i64, FileType), Problem> {
+    let media_id = media_id.parse::<i64>().map_err(|_e| {
+        bad_request(
+            "media_id is not a valid id",
+            format!("media_id {media_id} is not a valid id"),
+            instance,
+        )
+    })?;
+
+    let file_type = file_type
+        .parse::<FileType>()
+        .ok()
+        .filter(FileType::is_artwork)
+        .ok_or(bad_request(
+            "file_type is not an artwork type",
+            format!("file_type {file_type} is not an artwork type"),
+            instance,
+        ))?;
+
+    Ok((media_id, file_type))
+}
+
+pub(super) async fn find_media(
+    connection: &mut AsyncPgConnection,
+    media_id: i64,
+    instance: &Option<String>,
+) -> Result<Media, Problem> {
+    repositories::media::find_by_id(connection, media_id)
+        .await
+        .map_err(|e| {
+            error!("Error while fetching media with id {}: {}", media_id, e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .ok_or(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+                .to_string(),
+            title: "Media not found".to_string(),
+            status: 404,
+            detail: Some(format!("Media with id {media_id} not found")),
+            instance: instance.clone(),
+        })
+}
+
+/// Locks the type so scans keep `file` over the library's own image.
+async fn replace_artwork(
+    state: &AppState,
+    connection: &mut AsyncPgConnection,
+    mut media: Media,
+    file: models::File,
+    instance: &Option<String>,
+) -> Result<Media, Problem> {
+    let file_type = file.type_.clone();
+    let (replaced, mut files): (Vec<models::File>, Vec<models::File>) =
+        media.files.0.drain(..).partition(|f| f.type_ == file_type);
+    files.insert(0, file.clone());
+    media.files.0 = files;
+
+    if !media.is_locked(file_type.as_str()) {
+        media.locked_fields.push(file_type.as_str().to_string());
+    }
+
+    let media = repositories::media::update(connection, &media)
+        .await
+        .map_err(|e| {
+            error!("Error updating media: {}", e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?;
+
+    delete_stored(state, &replaced, &media).await;
+    Ok(media)
+}
+
+async fn delete_stored(state: &AppState, files: &[models::File], media: &Media) {
+    for file in files {
+        if file.source == FileSource::Metadata
+            && !media.files.iter().any(|f| f.path == file.path)
+            && let Err(e) = state.metadata_store.delete(file).await
+        {
+            error!("Error deleting stored image {}: {}", file.path, e);
+        }
+    }
+}
+
+pub async fn put(
+    State(state): State<AppState>,
+    DbConn(mut connection): DbConn,
+    auth_user: AuthUser,
+    Path((media_id, file_type)): Path<(String, String)>,
+    body: Bytes,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/media/{media_id}/images/{file_type}"));
+
+    if !auth_user.is_admin {
+        return Err(ProblemType::Forbidden(instance).into());
+    }
+
+    let (media_id, file_type) = parse_artwork_path(&media_id, &file_type, &instance)?;
+
+    if image::guess_format(&body).is_err() {
+        return Err(bad_request(
+            "body is not an image",
+            "The request body must be a JPEG, PNG, WebP or other common image".to_string(),
+            &instance,
+        ));
+    }
+
+    let media = find_media(&mut connection, media_id, &instance).await?;
+
+    let file = state
+        .metadata_store
+        .save_image(media.id, file_type, body)
+        .await
+        .map_err(|e| {
+            error!("Error saving uploaded image for media {}: {}", media_id, e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?;
+
+    let media = replace_artwork(&state, &mut connection, media, file, &instance).await?;
+
+    Ok(Json(MediaView::from(media)))
+}
+
+pub async fn post(
+    State(state): State<AppState>,
+    DbConn(mut connection): DbConn,
+    auth_user: AuthUser,
+    Path((media_id, file_type)): Path<(String, String)>,
+    Json(body): Json<SelectArtwork>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/media/{media_id}/images/{file_type}"));
+
+    if !auth_user.is_admin {
+        return Err(ProblemType::Forbidden(instance).into());
+    }
+
+    let (media_id, file_type) = parse_artwork_path(&media_id, &file_type, &instance)?;
+    let media = find_media(&mut connection, media_id, &instance).await?;
+
+    let library = repositories::library::find_by_id(&mut connection, media.library_id)
+        .await
+        .map_err(|e| {
+            error!(
+                "Error while fetching library with id {}: {}",
+                media.library_id, e
+            );
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .ok_or(Problem::from(ProblemType::InternalServerError(
+            instance.clone(),
+        )))?;
+
+    // Only candidates are downloaded, the server does not fetch arbitrary URLs
+    if !find_candidates(&state, &media, &library, &file_type)
+        .await
+        .iter()
+        .any(|(_, image)| image.url == body.url)
+    {
+        return Err(bad_request(
+            "url is not a candidate",
+            format!("{} is not a {} candidate", body.url, file_type.as_str()),
+            &instance,
+        ));
+    }
+
+    let bytes = state.http_client.get_bytes(&body.url).await.map_err(|e| {
+        error!("Error downloading image {}: {}", body.url, e);
+        Problem::from(ProblemType::InternalServerError(instance.clone()))
+    })?;
+
+    let file = state
+        .metadata_store
+        .save_image(media.id, file_type, bytes)
+        .await
+        .map_err(|e| {
+            error!(
+                "Error saving image {} for media {}: {}",
+                body.url, media_id, e
+            );
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?;
+
+    let media = replace_artwork(&state, &mut connection, media, file, &instance).await?;
+
+    Ok(Json(MediaView::from(media)))
+}
+
+pub async fn delete(
+    State(state): State<AppState>,
+    DbConn(mut connection): DbConn,
+    auth_user: AuthUser,
+    Path((media_id, file_type)): Path<(String, String)>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/media/{media_id}/images/{file_type}"));
+
+    if !auth_user.is_admin {
+        return Err(ProblemType::Forbidden(instance).into());
+    }
+
+    let (media_id, file_type) = parse_artwork_path(&media_id, &file_type, &instance)?;
+    let mut media = find_media(&mut connection, media_id, &instance).await?;
+
+    let (removed, files): (Vec<models::File>, Vec<models::File>) = media
+        .files
+        .0
+        .drain(..)
+        .partition(|f| f.type_ == file_type && f.source == FileSource::Metadata);
+    media.files.0 = files;
+    media
+        .locked_fields
+        .retain(|field| field != file_type.as_str());
+
+    let media = repositories::media::update(&mut connection, &media)
+        .await
+        .map_err(|e| {
+            error!("Error updating media: {}", e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?;
+
+    delete_stored(&state, &removed, &media).await;
+
+    if media.parent_id.is_none()
+        && let Err(e) = state.queue.send(Box::new(FetchArtwork::new(
+            state.clone(),
+            FetchArtworkPayload::new(media.id),
+        )))
+    {
+        error!("Failed to add job to queue: {:?}", e);
+        return Err(ProblemType::InternalServerError(instance).into());
+    }
+
+    Ok(Json(MediaView::from(media)))
+}
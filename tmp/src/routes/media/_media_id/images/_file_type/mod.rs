@@ -1,9 +1,22 @@
 use crate::state::AppState;
+use axum::extract::DefaultBodyLimit;
 use axum::routing::get;
 use axum::Router;
 
+mod candidates;
 mod index;
 
+const MAX_UPLOAD_SIZE: usize = 32 * 1024 * 1024;
+
 pub fn routes() -> Router<AppState> {
-    Router::new().route("/", get(index::get))
+    Router::new()
+        .route(
+            "/",
+            get(index::get)
+                .put(index::put)
+                .post(index::post)
+                .delete(index::delete),
+        )
+        .layer(DefaultBodyLimit::max(MAX_UPLOAD_SIZE))
+        .nest("/candidates", candidates::routes())
 }
@@ -1,14 +1,99 @@
+use crate::collections;
 use crate::errors::{Problem, ProblemType};
-use crate::middlware::DbConn;
+use crate::middlware::{AuthUser, DbConn, OptionalAuthUser};
+use crate::models::{Media, MediaSet, LOCKABLE_FIELDS};
 use crate::repositories;
+use crate::state::AppState;
 use crate::views::MediaView;
-use axum::extract::Path;
+use axum::extract::{Path, State};
 use axum::response::IntoResponse;
 use axum::Json;
+use chrono::{NaiveDate, Utc};
+use diesel_json::Json as JsonColumn;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::json;
 use tracing::error;
 
+/// Distinguishes a field set to `null` (`Some(None)`) from one left out (`None`).
+fn nullable<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateMedia {
+    title: Option<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    original_title: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    sort_title: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    plot: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    outline: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    tagline: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    genre: Option<Option<Vec<String>>>,
+    #[serde(default, deserialize_with = "nullable")]
+    year: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "nullable")]
+    premiered: Option<Option<NaiveDate>>,
+    #[serde(default, deserialize_with = "nullable")]
+    aired: Option<Option<NaiveDate>>,
+    #[serde(default, deserialize_with = "nullable")]
+    end_date: Option<Option<NaiveDate>>,
+    #[serde(default, deserialize_with = "nullable")]
+    runtime: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "nullable")]
+    mpaa: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    studio: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    country: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    status: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    rating: Option<Option<f64>>,
+    #[serde(default, deserialize_with = "nullable")]
+    user_rating: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "nullable")]
+    trailer: Option<Option<String>>,
+    tags: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    collection: Option<Option<MediaSet>>,
+    locked_fields: Option<Vec<String>>,
+}
+
+fn set_attribute<T: Serialize>(
+    media: &mut Media,
+    edited: &mut Vec<String>,
+    key: &str,
+    value: Option<Option<T>>,
+) {
+    let Some(value) = value else {
+        return;
+    };
+
+    if !media.attributes.is_object() {
+        media.attributes = json!({});
+    }
+
+    let attributes = media.attributes.as_object_mut().unwrap();
+    match value {
+        Some(value) => attributes.insert(key.to_string(), json!(value)),
+        None => attributes.remove(key),
+    };
+    edited.push(key.to_string());
+}
+
 pub async fn get(
     DbConn(mut connection): DbConn,
+    auth_user: OptionalAuthUser,
     Path(media_id): Path<String>,
 ) -> Result<impl IntoResponse, Problem> {
     let instance = Some(format!("/media/{media_id}"));
@@ -22,7 +107,7 @@ pub async fn get(
         instance: instance.clone(),
     })?;
 
-    Ok(Json(MediaView::from(
+    Ok(Json(MediaView::localized(
         repositories::media::find_by_id(&mut connection, media_id)
             .await
             .map_err(|e| {
@@ -37,5 +122,164 @@ pub async fn get(
                 detail: Some(format!("Media with id {media_id} not found")),
                 instance: instance.clone(),
             })?,
+        auth_user.metadata_languages(),
     )))
 }
+
+/// Edited fields are locked, unless `lockedFields` is given, which replaces the locks instead.
+pub async fn patch(
+    State(state): State<AppState>,
+    DbConn(mut connection): DbConn,
+    auth_user: AuthUser,
+    Path(media_id): Path<String>,
+    Json(body): Json<UpdateMedia>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/media/{media_id}"));
+
+    if !auth_user.is_admin {
+        return Err(ProblemType::Forbidden(instance).into());
+    }
+
+    let media_id = media_id.parse::<i64>().map_err(|_e| Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: "media_id is not a valid id".to_string(),
+        status: 400,
+        detail: Some(format!("media_id {media_id} is not a valid id")),
+        instance: instance.clone(),
+    })?;
+
+    if body
+        .title
+        .as_ref()
+        .is_some_and(|title| title.trim().is_empty())
+    {
+        return Err(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                .to_string(),
+            title: "title must not be empty".to_string(),
+            status: 400,
+            detail: Some("title must not be empty".to_string()),
+            instance,
+        });
+    }
+
+    if let Some(field) = body
+        .locked_fields
+        .iter()
+        .flatten()
+        .find(|field| !LOCKABLE_FIELDS.contains(&field.as_str()))
+    {
+        return Err(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                .to_string(),
+            title: "lockedFields contains an unknown field".to_string(),
+            status: 400,
+            detail: Some(format!(
+                "{field} cannot be locked, lockable fields are {}",
+                LOCKABLE_FIELDS.join(", ")
+            )),
+            instance,
+        });
+    }
+
+    let mut media = repositories::media::find_by_id(&mut connection, media_id)
+        .await
+        .map_err(|e| {
+            error!("Error while fetching media with id {}: {}", media_id, e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .ok_or(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+                .to_string(),
+            title: "Media not found".to_string(),
+            status: 404,
+            detail: Some(format!("Media with id {media_id} not found")),
+            instance: instance.clone(),
+        })?;
+
+    let mut edited = Vec::new();
+    let title = body.title.map(|title| title.trim().to_string());
+    set_attribute(&mut media, &mut edited, "title", title.map(Some));
+    set_attribute(
+        &mut media,
+        &mut edited,
+        "originalTitle",
+        body.original_title,
+    );
+    set_attribute(&mut media, &mut edited, "sortTitle", body.sort_title);
+    set_attribute(&mut media, &mut edited, "plot", body.plot);
+    set_attribute(&mut media, &mut edited, "outline", body.outline);
+    set_attribute(&mut media, &mut edited, "tagline", body.tagline);
+    set_attribute(&mut media, &mut edited, "genre", body.genre);
+    set_attribute(&mut media, &mut edited, "year", body.year);
+    set_attribute(&mut media, &mut edited, "premiered", body.premiered);
+    set_attribute(&mut media, &mut edited, "aired", body.aired);
+    set_attribute(&mut media, &mut edited, "endDate", body.end_date);
+    set_attribute(&mut media, &mut edited, "runtime", body.runtime);
+    set_attribute(&mut media, &mut edited, "mpaa", body.mpaa);
+    set_attribute(&mut media, &mut edited, "studio", body.studio);
+    set_attribute(&mut media, &mut edited, "country", body.country);
+    set_attribute(&mut media, &mut edited, "status", body.status);
+    set_attribute(&mut media, &mut edited, "rating", body.rating);
+    set_attribute(&mut media, &mut edited, "userRating", body.user_rating);
+    set_attribute(&mut media, &mut edited, "trailer", body.trailer);
+
+    // The title column is what items are sorted by, the sort title if there is one
+    if edited
+        .iter()
+        .any(|field| field == "title" || field == "sortTitle")
+    {
+        let attributes = &media.attributes;
+        if let Some(title) = attributes
+            .get("sortTitle")
+            .and_then(|title| title.as_str())
+            .or(attributes.get("title").and_then(|title| title.as_str()))
+        {
+            media.title = title.to_string();
+        }
+    }
+
+    if let Some(tags) = body.tags {
+        media.tags = tags;
+        edited.push("tags".to_string());
+    }
+
+    let collection_edited = body.collection.is_some();
+    if let Some(collection) = body.collection {
+        media.collection = collection.map(JsonColumn::new);
+        edited.push("collection".to_string());
+    }
+
+    match body.locked_fields {
+        Some(locked_fields) => media.locked_fields = locked_fields,
+        None => {
+            for field in edited {
+                if !media.is_locked(&field) {
+                    media.locked_fields.push(field);
+                }
+            }
+        }
+    }
+    media.locked_fields.sort();
+    media.locked_fields.dedup();
+
+    media.update_metadata_status();
+    media.updated_at = Utc::now().naive_utc();
+
+    let media = repositories::media::update(&mut connection, &media)
+        .await
+        .map_err(|e| {
+            error!("Error updating media: {}", e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?;
+
+    if collection_edited {
+        collections::sync_media(&state, &media).await.map_err(|e| {
+            error!("Error syncing collections of media {}: {:?}", media.id, e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?;
+    }
+
+    Ok(Json(MediaView::from(media)))
+}
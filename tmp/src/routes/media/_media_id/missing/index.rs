@@ -0,0 +1,75 @@
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::DbConn;
+use crate::repositories;
+use crate::views::MissingEpisodeView;
+use axum::extract::{Path, Query};
+use axum::response::IntoResponse;
+use axum::Json;
+use serde::Deserialize;
+use tracing::error;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryParams {
+    include_specials: Option<bool>,
+}
+
+pub async fn get(
+    DbConn(mut connection): DbConn,
+    Path(media_id): Path<String>,
+    Query(query_params): Query<QueryParams>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/media/{media_id}/missing"));
+
+    let media_id = media_id.parse::<i64>().map_err(|_e| Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: "media_id is not a valid id".to_string(),
+        status: 400,
+        detail: Some(format!("media_id {media_id} is not a valid id")),
+        instance: instance.clone(),
+    })?;
+
+    let media = repositories::media::find_by_id(&mut connection, media_id)
+        .await
+        .map_err(|e| {
+            error!("Error while fetching media with id {}: {}", media_id, e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .ok_or(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+                .to_string(),
+            title: "Media not found".to_string(),
+            status: 404,
+            detail: Some(format!("Media with id {media_id} not found")),
+            instance: instance.clone(),
+        })?;
+
+    if media.type_ != "tvshow" || media.parent_id.is_some() {
+        return Err(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                .to_string(),
+            title: "Media is not a show".to_string(),
+            status: 400,
+            detail: Some(format!("Media with id {media_id} is not a show")),
+            instance,
+        });
+    }
+
+    Ok(Json(
+        repositories::provider_episode::find_missing(
+            &mut connection,
+            Some(media.id),
+            None,
+            query_params.include_specials.unwrap_or(false),
+        )
+        .await
+        .map_err(|e| {
+            error!("Error calling provider_episode::find_missing: {}", e);
+            Problem::from(ProblemType::InternalServerError(instance))
+        })?
+        .into_iter()
+        .map(MissingEpisodeView::from)
+        .collect::<Vec<_>>(),
+    ))
+}
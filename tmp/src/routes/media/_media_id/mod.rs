@@ -2,13 +2,27 @@ use crate::state::AppState;
 use axum::routing::get;
 use axum::Router;
 
+mod extras;
+mod identify;
 mod images;
 mod index;
+mod missing;
+mod nfo;
+mod people;
 mod stream;
+mod themes;
+mod trailer;
 
 pub fn routes() -> Router<AppState> {
     Router::new()
-        .route("/", get(index::get))
+        .route("/", get(index::get).patch(index::patch))
+        .nest("/extras", extras::routes())
+        .nest("/identify", identify::routes())
         .nest("/images", images::routes())
+        .nest("/missing", missing::routes())
+        .nest("/nfo", nfo::routes())
+        .nest("/people", people::routes())
         .nest("/stream", stream::routes())
+        .nest("/themes", themes::routes())
+        .nest("/trailer", trailer::routes())
 }
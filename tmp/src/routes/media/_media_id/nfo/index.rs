@@ -0,0 +1,62 @@
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::{AuthUser, DbConn};
+use crate::nfo;
+use crate::repositories;
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use tracing::error;
+
+pub async fn post(
+    DbConn(mut connection): DbConn,
+    auth_user: AuthUser,
+    Path(media_id): Path<String>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/media/{media_id}/nfo"));
+
+    if !auth_user.is_admin {
+        return Err(ProblemType::Forbidden(instance).into());
+    }
+
+    let media_id = media_id.parse::<i64>().map_err(|_e| Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: "media_id is not a valid id".to_string(),
+        status: 400,
+        detail: Some(format!("media_id {media_id} is not a valid id")),
+        instance: instance.clone(),
+    })?;
+
+    let media = repositories::media::find_by_id(&mut connection, media_id)
+        .await
+        .map_err(|e| {
+            error!("Error while fetching media with id {}: {}", media_id, e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .ok_or(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+                .to_string(),
+            title: "Media not found".to_string(),
+            status: 404,
+            detail: Some(format!("Media with id {media_id} not found")),
+            instance: instance.clone(),
+        })?;
+
+    match nfo::write(&media).await {
+        Ok(Some(_path)) => Ok(StatusCode::NO_CONTENT),
+        Ok(None) => Err(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/422"
+                .to_string(),
+            title: "Media has no nfo".to_string(),
+            status: 422,
+            detail: Some(format!(
+                "Media with id {media_id} can not be exported as nfo"
+            )),
+            instance,
+        }),
+        Err(e) => {
+            error!("Error writing nfo for media {}: {}", media_id, e);
+            Err(ProblemType::InternalServerError(instance).into())
+        }
+    }
+}
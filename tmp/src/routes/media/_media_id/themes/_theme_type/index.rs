@@ -0,0 +1,87 @@
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::{AuthUser, DbConn};
+use crate::models::FileType;
+use crate::repositories;
+use crate::streaming::stream_file;
+use axum::extract::Path;
+use axum::http::HeaderMap;
+use axum::response::IntoResponse;
+use std::path::PathBuf;
+use tracing::error;
+
+/// Seasons and episodes play the theme of their show.
+pub async fn get(
+    DbConn(mut connection): DbConn,
+    _: AuthUser,
+    Path((media_id, theme_type)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/media/{media_id}/themes/{theme_type}"));
+
+    let media_id = media_id.parse::<i64>().map_err(|_e| Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: "media_id is not a valid id".to_string(),
+        status: 400,
+        detail: Some(format!("media_id {media_id} is not a valid id")),
+        instance: instance.clone(),
+    })?;
+
+    let file_type = match theme_type.as_str() {
+        "music" => FileType::ThemeMusic,
+        "video" => FileType::ThemeVideo,
+        _ => {
+            return Err(Problem {
+                r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                    .to_string(),
+                title: "theme_type is not a valid theme_type".to_string(),
+                status: 400,
+                detail: Some(format!(
+                    "theme_type {theme_type} is not a valid theme_type, expected music or video"
+                )),
+                instance: instance.clone(),
+            });
+        }
+    };
+
+    let mut next_id = Some(media_id);
+    while let Some(id) = next_id {
+        let media = repositories::media::find_by_id(&mut connection, id)
+            .await
+            .map_err(|e| {
+                error!("Error while fetching media with id {}: {}", id, e);
+                Problem::from(ProblemType::InternalServerError(instance.clone()))
+            })?
+            .ok_or(Problem {
+                r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+                    .to_string(),
+                title: "Media not found".to_string(),
+                status: 404,
+                detail: Some(format!("Media with id {id} not found")),
+                instance: instance.clone(),
+            })?;
+
+        if let Some(theme) = media.files.iter().find(|f| f.type_ == file_type) {
+            let path = media
+                .path
+                .iter()
+                .fold(PathBuf::new(), |acc, component| acc.join(component))
+                .join(&theme.path);
+
+            return stream_file(&path, &headers, instance).await;
+        }
+
+        next_id = media.parent_id;
+    }
+
+    Err(Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+            .to_string(),
+        title: format!("{:?} not found", file_type),
+        status: 404,
+        detail: Some(format!(
+            "{theme_type} theme for Media with id {media_id} not found"
+        )),
+        instance,
+    })
+}
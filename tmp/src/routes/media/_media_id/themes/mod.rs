@@ -0,0 +1,8 @@
+use crate::state::AppState;
+use axum::Router;
+
+mod _theme_type;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().nest("/{theme_type}", _theme_type::routes())
+}
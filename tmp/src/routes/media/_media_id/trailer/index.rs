@@ -0,0 +1,76 @@
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::DbConn;
+use crate::models::{ExtraKind, MediaAttributes};
+use crate::nfo::trailer_url;
+use crate::repositories;
+use crate::views::TrailerView;
+use axum::extract::Path;
+use axum::response::IntoResponse;
+use axum::Json;
+use tracing::error;
+
+pub async fn get(
+    DbConn(mut connection): DbConn,
+    Path(media_id): Path<String>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/media/{media_id}/trailer"));
+
+    let media_id = media_id.parse::<i64>().map_err(|_e| Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: "media_id is not a valid id".to_string(),
+        status: 400,
+        detail: Some(format!("media_id {media_id} is not a valid id")),
+        instance: instance.clone(),
+    })?;
+
+    let media = repositories::media::find_by_id(&mut connection, media_id)
+        .await
+        .map_err(|e| {
+            error!("Error while fetching media with id {}: {}", media_id, e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .ok_or(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+                .to_string(),
+            title: "Media not found".to_string(),
+            status: 404,
+            detail: Some(format!("Media with id {media_id} not found")),
+            instance: instance.clone(),
+        })?;
+
+    let extras = repositories::extra::find_by_media_id(&mut connection, media_id)
+        .await
+        .map_err(|e| {
+            error!("Error while fetching extras of media {}: {}", media_id, e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?;
+
+    if let Some(trailer) = extras
+        .into_iter()
+        .find(|extra| extra.kind == ExtraKind::Trailer.as_str())
+    {
+        return Ok(Json(TrailerView {
+            extra_id: Some(trailer.id.to_string()),
+            url: None,
+        }));
+    }
+
+    let url = MediaAttributes::from(&media.attributes)
+        .trailer
+        .as_deref()
+        .and_then(trailer_url)
+        .ok_or(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+                .to_string(),
+            title: "Trailer not found".to_string(),
+            status: 404,
+            detail: Some(format!("Media with id {media_id} has no trailer")),
+            instance: instance.clone(),
+        })?;
+
+    Ok(Json(TrailerView {
+        extra_id: None,
+        url: Some(url),
+    }))
+}
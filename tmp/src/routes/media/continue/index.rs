@@ -20,7 +20,7 @@ pub async fn get(
                 Problem::from(ProblemType::InternalServerError(instance))
             })?
             .into_iter()
-            .map(MediaView::from)
+            .map(|media| MediaView::localized(media, &auth_user.metadata_languages))
             .collect::<Vec<_>>(),
     ))
 }
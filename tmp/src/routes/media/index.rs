@@ -1,5 +1,5 @@
 use crate::errors::{Problem, ProblemType};
-use crate::middlware::DbConn;
+use crate::middlware::{DbConn, OptionalAuthUser};
 use crate::repositories;
 use crate::repositories::media::{MediaCriteria, MediaCriteriaOrder};
 use crate::views::MediaView;
@@ -17,62 +17,99 @@ pub struct QueryParams {
     library_id: Option<String>,
     parent_id: Option<String>,
     order_by: Option<MediaCriteriaOrder>,
+    include_missing: Option<bool>,
 }
 
 pub async fn get(
     DbConn(mut connection): DbConn,
+    auth_user: OptionalAuthUser,
     query_params: Query<QueryParams>,
 ) -> Result<impl IntoResponse, Problem> {
     let instance = Some("/media".to_string());
 
-    Ok(Json(
-        repositories::media::find_all(
-            &mut connection,
-            MediaCriteria {
-                types: query_params.types.clone(),
-                title: query_params.title.clone(),
-                library_id: query_params
-                    .library_id
-                    .as_ref()
-                    .map_or(Ok(None), |id| id.parse::<i64>().map(Some))
-                    .map_err(|_e| Problem {
-                        r#type:
-                            "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
-                                .to_string(),
-                        title: "library_id is not a valid id".to_string(),
-                        status: 400,
-                        detail: Some(format!(
-                            "library_id {} is not a valid id",
-                            query_params.library_id.clone().unwrap()
-                        )),
-                        instance: instance.clone(),
-                    })?,
-                parent_id: query_params
-                    .parent_id
-                    .as_ref()
-                    .map_or(Ok(None), |id| id.parse::<i64>().map(Some))
-                    .map_err(|_e| Problem {
-                        r#type:
-                            "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
-                                .to_string(),
-                        title: "parent_id is not a valid id".to_string(),
-                        status: 400,
-                        detail: Some(format!(
-                            "parent_id {} is not a valid id",
-                            query_params.library_id.clone().unwrap()
-                        )),
-                        instance: instance.clone(),
-                    })?,
-                order_by: query_params.order_by.clone(),
-            },
-        )
-        .await
-        .map_err(|e| {
-            error!("Error calling media::find_all: {}", e);
-            Problem::from(ProblemType::InternalServerError(instance))
-        })?
-        .into_iter()
-        .map(MediaView::from)
-        .collect::<Vec<_>>(),
-    ))
+    let mut media = repositories::media::find_all(
+        &mut connection,
+        MediaCriteria {
+            types: query_params.types.clone(),
+            title: query_params.title.clone(),
+            languages: auth_user.metadata_languages().to_vec(),
+            library_id: query_params
+                .library_id
+                .as_ref()
+                .map_or(Ok(None), |id| id.parse::<i64>().map(Some))
+                .map_err(|_e| Problem {
+                    r#type:
+                        "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                            .to_string(),
+                    title: "library_id is not a valid id".to_string(),
+                    status: 400,
+                    detail: Some(format!(
+                        "library_id {} is not a valid id",
+                        query_params.library_id.clone().unwrap()
+                    )),
+                    instance: instance.clone(),
+                })?,
+            parent_id: query_params
+                .parent_id
+                .as_ref()
+                .map_or(Ok(None), |id| id.parse::<i64>().map(Some))
+                .map_err(|_e| Problem {
+                    r#type:
+                        "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                            .to_string(),
+                    title: "parent_id is not a valid id".to_string(),
+                    status: 400,
+                    detail: Some(format!(
+                        "parent_id {} is not a valid id",
+                        query_params.library_id.clone().unwrap()
+                    )),
+                    instance: instance.clone(),
+                })?,
+            order_by: query_params.order_by.clone(),
+        },
+    )
+    .await
+    .map_err(|e| {
+        error!("Error calling media::find_all: {}", e);
+        Problem::from(ProblemType::InternalServerError(instance.clone()))
+    })?
+    .into_iter()
+    .map(|media| MediaView::localized(media, auth_user.metadata_languages()))
+    .collect::<Vec<_>>();
+
+    // Episodes of a season can be listed along with placeholders for those missing
+    if query_params.include_missing.unwrap_or(false)
+        && let Some(parent_id) = query_params
+            .parent_id
+            .as_ref()
+            .and_then(|id| id.parse::<i64>().ok())
+        && let Some(season) = repositories::media::find_by_id(&mut connection, parent_id)
+            .await
+            .map_err(|e| {
+                error!("Error while fetching media with id {}: {}", parent_id, e);
+                Problem::from(ProblemType::InternalServerError(instance.clone()))
+            })?
+        && let (Some(show_id), Some(season_number), None) =
+            (season.parent_id, season.season, season.episode)
+    {
+        media.extend(
+            repositories::provider_episode::find_missing(
+                &mut connection,
+                Some(show_id),
+                None,
+                true,
+            )
+            .await
+            .map_err(|e| {
+                error!("Error calling provider_episode::find_missing: {}", e);
+                Problem::from(ProblemType::InternalServerError(instance))
+            })?
+            .into_iter()
+            .filter(|episode| episode.season == season_number)
+            .map(|episode| MediaView::missing(episode, &season)),
+        );
+        media.sort_by_key(|episode| episode.episode);
+    }
+
+    Ok(Json(media))
 }
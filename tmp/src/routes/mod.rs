@@ -1,15 +1,23 @@
 use crate::state::AppState;
 use axum::Router;
 
+mod calendar;
+mod collections;
 mod libraries;
 mod media;
+mod people;
+mod schedules;
 mod sessions;
 mod users;
 
 pub fn routes() -> Router<AppState> {
     Router::new()
         .nest("/media", media::routes())
+        .nest("/calendar", calendar::routes())
+        .nest("/collections", collections::routes())
         .nest("/libraries", libraries::routes())
+        .nest("/people", people::routes())
+        .nest("/schedules", schedules::routes())
         .nest("/sessions", sessions::routes())
         .nest("/users", users::routes())
 }
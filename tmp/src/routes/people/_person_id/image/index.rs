@@ -0,0 +1,139 @@
+use super::super::index::find_person;
+use crate::errors::{Problem, ProblemType};
+use crate::factories::library_scanner::artwork::content_type;
+use crate::images::{accepts, has_alpha, ImageFormat, ImageOptions, DEFAULT_QUALITY};
+use crate::middlware::DbConn;
+use crate::state::AppState;
+use axum::body::Body;
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use std::str::FromStr;
+use tokio::fs::File;
+use tokio_util::io::ReaderStream;
+use tracing::error;
+
+#[derive(Deserialize)]
+pub struct QueryParams {
+    width: Option<u32>,
+    height: Option<u32>,
+    quality: Option<u8>,
+    format: Option<String>,
+}
+
+fn bad_request(title: &str, detail: String, instance: &Option<String>) -> Problem {
+    Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: title.to_string(),
+        status: 400,
+        detail: Some(detail),
+        instance: instance.clone(),
+    }
+}
+
+pub async fn get(
+    State(state): State<AppState>,
+    DbConn(mut connection): DbConn,
+    Path(person_id): Path<String>,
+    Query(query_params): Query<QueryParams>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/people/{person_id}/image"));
+
+    if query_params.width == Some(0) || query_params.height == Some(0) {
+        return Err(bad_request(
+            "width and height must be positive",
+            "width and height must be at least 1".to_string(),
+            &instance,
+        ));
+    }
+
+    if query_params
+        .quality
+        .is_some_and(|quality| !(1..=100).contains(&quality))
+    {
+        return Err(bad_request(
+            "quality is out of range",
+            "quality must be between 1 and 100".to_string(),
+            &instance,
+        ));
+    }
+
+    let format = query_params
+        .format
+        .as_deref()
+        .map(ImageFormat::from_str)
+        .transpose()
+        .map_err(|e| bad_request("format is not a valid format", e, &instance))?;
+
+    let person = find_person(&mut connection, &person_id, &instance).await?;
+    drop(connection);
+
+    let headshot = person.headshot.ok_or(Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+            .to_string(),
+        title: "Headshot not found".to_string(),
+        status: 404,
+        detail: Some(format!("Person with id {} has no headshot", person.id)),
+        instance: instance.clone(),
+    })?;
+    let path = state.metadata_store.path(&headshot);
+
+    let accept = headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok());
+
+    let derive = query_params.width.is_some()
+        || query_params.height.is_some()
+        || query_params.quality.is_some()
+        || format.is_some()
+        || !accepts(accept, content_type(&path), true);
+
+    let mut response_headers = HeaderMap::new();
+    if format.is_none() {
+        response_headers.insert(header::VARY, "Accept".parse().unwrap());
+    }
+
+    let path = if derive {
+        let format = match format {
+            Some(format) => format,
+            None => ImageFormat::negotiate(accept, has_alpha(&path).await),
+        };
+        let options = ImageOptions {
+            width: query_params.width,
+            height: query_params.height,
+            quality: query_params.quality.unwrap_or(DEFAULT_QUALITY),
+            format,
+        };
+
+        response_headers.insert(
+            header::CONTENT_TYPE,
+            options.format.content_type().parse().unwrap(),
+        );
+
+        state
+            .image_cache
+            .get_or_create(&path, &options)
+            .await
+            .map_err(|e| {
+                error!("Error while deriving image {:?}: {}", path, e);
+                Problem::from(ProblemType::InternalServerError(instance.clone()))
+            })?
+    } else {
+        response_headers.insert(header::CONTENT_TYPE, content_type(&path).parse().unwrap());
+        path
+    };
+
+    let file = File::open(&path).await.map_err(|e| {
+        error!("Error while reading headshot {:?}: {}", path, e);
+        Problem::from(ProblemType::InternalServerError(instance.clone()))
+    })?;
+
+    Ok((
+        StatusCode::OK,
+        response_headers,
+        Body::from_stream(ReaderStream::new(file)),
+    ))
+}
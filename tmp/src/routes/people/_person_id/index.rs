@@ -0,0 +1,51 @@
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::DbConn;
+use crate::models::Person;
+use crate::repositories;
+use crate::views::PersonView;
+use axum::extract::Path;
+use axum::response::IntoResponse;
+use axum::Json;
+use diesel_async::AsyncPgConnection;
+use tracing::error;
+
+pub(super) async fn find_person(
+    connection: &mut AsyncPgConnection,
+    person_id: &str,
+    instance: &Option<String>,
+) -> Result<Person, Problem> {
+    let person_id = person_id.parse::<i64>().map_err(|_e| Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: "person_id is not a valid id".to_string(),
+        status: 400,
+        detail: Some(format!("person_id {person_id} is not a valid id")),
+        instance: instance.clone(),
+    })?;
+
+    repositories::person::find_by_id(connection, person_id)
+        .await
+        .map_err(|e| {
+            error!("Error while fetching person with id {}: {}", person_id, e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .ok_or(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+                .to_string(),
+            title: "Person not found".to_string(),
+            status: 404,
+            detail: Some(format!("Person with id {person_id} not found")),
+            instance: instance.clone(),
+        })
+}
+
+pub async fn get(
+    DbConn(mut connection): DbConn,
+    Path(person_id): Path<String>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/people/{person_id}"));
+
+    Ok(Json(PersonView::from(
+        find_person(&mut connection, &person_id, &instance).await?,
+    )))
+}
@@ -0,0 +1,55 @@
+use super::super::index::find_person;
+use crate::collections;
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::{DbConn, OptionalAuthUser};
+use crate::repositories;
+use crate::views::{FilmographyView, MediaView, RoleView};
+use axum::extract::Path;
+use axum::response::IntoResponse;
+use axum::Json;
+use tracing::error;
+
+pub async fn get(
+    DbConn(mut connection): DbConn,
+    auth_user: OptionalAuthUser,
+    Path(person_id): Path<String>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/people/{person_id}/media"));
+
+    let person = find_person(&mut connection, &person_id, &instance).await?;
+
+    let mut roles = repositories::media_person::find_by_person_id(&mut connection, person.id)
+        .await
+        .map_err(|e| {
+            error!("Error calling media_person::find_by_person_id: {}", e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?;
+
+    let mut media = repositories::media::find_by_ids(
+        &mut connection,
+        roles.iter().map(|role| role.media_id).collect(),
+    )
+    .await
+    .map_err(|e| {
+        error!("Error calling media::find_by_ids: {}", e);
+        Problem::from(ProblemType::InternalServerError(instance))
+    })?;
+    collections::sort_by_release(&mut media);
+
+    let filmography = media
+        .into_iter()
+        .map(|media| {
+            let (media_roles, rest) = roles
+                .drain(..)
+                .partition::<Vec<_>, _>(|role| role.media_id == media.id);
+            roles = rest;
+
+            FilmographyView {
+                roles: media_roles.into_iter().map(RoleView::from).collect(),
+                media: MediaView::localized(media, auth_user.metadata_languages()),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Json(filmography))
+}
@@ -0,0 +1,14 @@
+use crate::state::AppState;
+use axum::routing::get;
+use axum::Router;
+
+mod image;
+mod index;
+mod media;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(index::get))
+        .nest("/image", image::routes())
+        .nest("/media", media::routes())
+}
@@ -0,0 +1,8 @@
+use crate::state::AppState;
+use axum::Router;
+
+mod _person_id;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().nest("/{person_id}", _person_id::routes())
+}
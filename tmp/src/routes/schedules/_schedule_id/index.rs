@@ -0,0 +1,97 @@
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::{AuthUser, DbConn};
+use crate::repositories;
+use crate::scheduler;
+use crate::views::ScheduleView;
+use axum::extract::Path;
+use axum::response::IntoResponse;
+use axum::Json;
+use chrono::Utc;
+use serde::Deserialize;
+use tracing::error;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSchedule {
+    cron: Option<String>,
+    enabled: Option<bool>,
+}
+
+pub async fn patch(
+    DbConn(mut connection): DbConn,
+    auth_user: AuthUser,
+    Path(schedule_id): Path<String>,
+    Json(body): Json<UpdateSchedule>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/schedules/{schedule_id}"));
+
+    if !auth_user.is_admin {
+        return Err(ProblemType::Forbidden(instance).into());
+    }
+
+    let schedule_id = schedule_id.parse::<i64>().map_err(|_e| Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: "schedule_id is not a valid id".to_string(),
+        status: 400,
+        detail: Some(format!("schedule_id {schedule_id} is not a valid id")),
+        instance: instance.clone(),
+    })?;
+
+    let mut schedule = repositories::schedule::find_by_id(&mut connection, schedule_id)
+        .await
+        .map_err(|e| {
+            error!(
+                "Error while fetching schedule with id {}: {}",
+                schedule_id, e
+            );
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .ok_or(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+                .to_string(),
+            title: "Schedule not found".to_string(),
+            status: 404,
+            detail: Some(format!("Schedule with id {schedule_id} not found")),
+            instance: instance.clone(),
+        })?;
+
+    let now = Utc::now().naive_utc();
+
+    if let Some(cron) = body.cron {
+        schedule.next_run_at = scheduler::next_run_after(&cron, now).map_err(|e| Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                .to_string(),
+            title: "cron is not a valid cron expression".to_string(),
+            status: 400,
+            detail: Some(e.to_string()),
+            instance: instance.clone(),
+        })?;
+        schedule.cron = cron;
+    }
+
+    if let Some(enabled) = body.enabled {
+        if enabled && !schedule.enabled {
+            schedule.next_run_at = scheduler::next_run_after(&schedule.cron, now).map_err(|e| {
+                error!(
+                    "Error computing next run of schedule {}: {}",
+                    schedule_id, e
+                );
+                Problem::from(ProblemType::InternalServerError(instance.clone()))
+            })?;
+        }
+        schedule.enabled = enabled;
+    }
+
+    schedule.updated_at = now;
+    schedule.updated_by.clone_from(&auth_user.name);
+
+    let schedule = repositories::schedule::update(&mut connection, &schedule)
+        .await
+        .map_err(|e| {
+            error!("Error updating schedule: {}", e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?;
+
+    Ok(Json(ScheduleView::from(schedule)))
+}
@@ -0,0 +1,12 @@
+use crate::state::AppState;
+use axum::routing::patch;
+use axum::Router;
+
+mod index;
+mod run;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", patch(index::patch))
+        .nest("/run", run::routes())
+}
@@ -0,0 +1,57 @@
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::{AuthUser, DbConn};
+use crate::repositories;
+use crate::scheduler;
+use crate::state::AppState;
+use crate::views::ScheduleView;
+use axum::extract::{Path, State};
+use axum::response::IntoResponse;
+use axum::Json;
+use tracing::error;
+
+pub async fn post(
+    State(state): State<AppState>,
+    DbConn(mut connection): DbConn,
+    auth_user: AuthUser,
+    Path(schedule_id): Path<String>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some(format!("/schedules/{schedule_id}/run"));
+
+    if !auth_user.is_admin {
+        return Err(ProblemType::Forbidden(instance).into());
+    }
+
+    let schedule_id = schedule_id.parse::<i64>().map_err(|_e| Problem {
+        r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+            .to_string(),
+        title: "schedule_id is not a valid id".to_string(),
+        status: 400,
+        detail: Some(format!("schedule_id {schedule_id} is not a valid id")),
+        instance: instance.clone(),
+    })?;
+
+    let schedule = repositories::schedule::find_by_id(&mut connection, schedule_id)
+        .await
+        .map_err(|e| {
+            error!(
+                "Error while fetching schedule with id {}: {}",
+                schedule_id, e
+            );
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?
+        .ok_or(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/404"
+                .to_string(),
+            title: "Schedule not found".to_string(),
+            status: 404,
+            detail: Some(format!("Schedule with id {schedule_id} not found")),
+            instance: instance.clone(),
+        })?;
+
+    let schedule = scheduler::trigger(&state, schedule).await.map_err(|e| {
+        error!("Error running schedule {}: {}", schedule_id, e);
+        Problem::from(ProblemType::InternalServerError(instance.clone()))
+    })?;
+
+    Ok(Json(ScheduleView::from(schedule)))
+}
@@ -0,0 +1,30 @@
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::{AuthUser, DbConn};
+use crate::repositories;
+use crate::views::ScheduleView;
+use axum::response::IntoResponse;
+use axum::Json;
+use tracing::error;
+
+pub async fn get(
+    DbConn(mut connection): DbConn,
+    auth_user: AuthUser,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some("/schedules".to_string());
+
+    if !auth_user.is_admin {
+        return Err(ProblemType::Forbidden(instance).into());
+    }
+
+    Ok(Json(
+        repositories::schedule::find_all(&mut connection)
+            .await
+            .map_err(|e| {
+                error!("Error calling schedule::find_all: {}", e);
+                Problem::from(ProblemType::InternalServerError(instance))
+            })?
+            .into_iter()
+            .map(ScheduleView::from)
+            .collect::<Vec<_>>(),
+    ))
+}
@@ -2,6 +2,7 @@ use crate::errors::{Problem, ProblemType};
 use crate::middlware::{DbConn, OptionalAuthUser};
 use crate::models::InsertableUser;
 use crate::repositories;
+use crate::views::UserView;
 use axum::response::IntoResponse;
 use axum::Json;
 use password_auth::generate_hash;
@@ -15,6 +16,7 @@ pub struct CreateUser {
     pub password: String,
     pub name: String,
     pub is_admin: bool,
+    pub metadata_languages: Option<Vec<String>>,
 }
 
 pub async fn post(
@@ -42,6 +44,21 @@ pub async fn post(
         });
     }
 
+    let metadata_languages = body.metadata_languages.unwrap_or_default();
+    if metadata_languages
+        .iter()
+        .any(|language| language.trim().is_empty())
+    {
+        return Err(Problem {
+            r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                .to_string(),
+            title: "metadataLanguages contains an empty language".to_string(),
+            status: 400,
+            detail: Some("Languages must be ISO 639-1 codes such as en".to_string()),
+            instance,
+        });
+    }
+
     let user = InsertableUser {
         created_by: auth_user
             .clone()
@@ -53,6 +70,10 @@ pub async fn post(
         password: generate_hash(&body.password),
         name: body.name,
         is_admin: body.is_admin,
+        metadata_languages: metadata_languages
+            .iter()
+            .map(|language| language.trim().to_lowercase())
+            .collect(),
     };
 
     let user = repositories::user::create(&mut connection, &user)
@@ -62,5 +83,5 @@ pub async fn post(
             Problem::from(ProblemType::InternalServerError(instance.clone()))
         })?;
 
-    Ok(Json(user))
+    Ok(Json(UserView::from(user)))
 }
@@ -0,0 +1,65 @@
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::{AuthUser, DbConn};
+use crate::repositories;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use chrono::Utc;
+use rand::Rng;
+use serde::Serialize;
+use tracing::error;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarFeed {
+    token: String,
+    url: String,
+}
+
+/// Replaces the previous token so URLs shared before stop working.
+pub async fn post(
+    DbConn(mut connection): DbConn,
+    mut auth_user: AuthUser,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some("/users/me/calendar".to_string());
+
+    let mut bytes = [0u8; 32];
+    rand::rng().fill(&mut bytes[..]);
+    let token: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+
+    auth_user.calendar_token = Some(token.clone());
+    auth_user.updated_at = Utc::now().naive_utc();
+    auth_user.updated_by.clone_from(&auth_user.name);
+
+    repositories::user::update(&mut connection, &auth_user)
+        .await
+        .map_err(|e| {
+            error!("Error updating user: {}", e);
+            Problem::from(ProblemType::InternalServerError(instance))
+        })?;
+
+    Ok(Json(CalendarFeed {
+        url: format!("/calendar/ical?token={token}"),
+        token,
+    }))
+}
+
+pub async fn delete(
+    DbConn(mut connection): DbConn,
+    mut auth_user: AuthUser,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some("/users/me/calendar".to_string());
+
+    auth_user.calendar_token = None;
+    auth_user.updated_at = Utc::now().naive_utc();
+    auth_user.updated_by.clone_from(&auth_user.name);
+
+    repositories::user::update(&mut connection, &auth_user)
+        .await
+        .map_err(|e| {
+            error!("Error updating user: {}", e);
+            Problem::from(ProblemType::InternalServerError(instance))
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,59 @@
+use crate::errors::{Problem, ProblemType};
+use crate::middlware::{AuthUser, DbConn};
+use crate::repositories;
+use crate::views::UserView;
+use axum::response::IntoResponse;
+use axum::Json;
+use chrono::Utc;
+use serde::Deserialize;
+use tracing::error;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateMe {
+    metadata_languages: Option<Vec<String>>,
+}
+
+pub async fn get(auth_user: AuthUser) -> Result<impl IntoResponse, Problem> {
+    Ok(Json(UserView::from(auth_user)))
+}
+
+pub async fn patch(
+    DbConn(mut connection): DbConn,
+    mut auth_user: AuthUser,
+    Json(body): Json<UpdateMe>,
+) -> Result<impl IntoResponse, Problem> {
+    let instance = Some("/users/me".to_string());
+
+    if let Some(metadata_languages) = body.metadata_languages {
+        if metadata_languages
+            .iter()
+            .any(|language| language.trim().is_empty())
+        {
+            return Err(Problem {
+                r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                    .to_string(),
+                title: "metadataLanguages contains an empty language".to_string(),
+                status: 400,
+                detail: Some("Languages must be ISO 639-1 codes such as en".to_string()),
+                instance,
+            });
+        }
+        auth_user.metadata_languages = metadata_languages
+            .iter()
+            .map(|language| language.trim().to_lowercase())
+            .collect();
+    }
+
+    auth_user.updated_at = Utc::now().naive_utc();
+    auth_user.updated_by.clone_from(&auth_user.name);
+
+    let user = repositories::user::update(&mut connection, &auth_user)
+        .await
+        .map_err(|e| {
+            error!("Error updating user: {}", e);
+            Problem::from(ProblemType::InternalServerError(instance.clone()))
+        })?;
+
+    Ok(Json(UserView::from(user)))
+}
@@ -3,7 +3,10 @@ use axum::routing::post;
 use axum::Router;
 
 mod index;
+mod me;
 
 pub fn routes() -> Router<AppState> {
-    Router::new().route("/", post(index::post))
+    Router::new()
+        .route("/", post(index::post))
+        .nest("/me", me::routes())
 }
@@ -0,0 +1,200 @@
+use crate::jobs::clean_cache::CleanCache;
+use crate::jobs::generate_blur_hashes::GenerateBlurHashes;
+use crate::jobs::refresh_artwork::{RefreshArtwork, RefreshArtworkPayload};
+use crate::jobs::refresh_episodes::{RefreshEpisodes, RefreshEpisodesPayload};
+use crate::jobs::refresh_metadata::{RefreshMetadata, RefreshMetadataPayload};
+use crate::jobs::scan_library::{ScanLibrary, ScanLibraryPayload};
+use crate::jobs::Job;
+use crate::models::{InsertableSchedule, Library, Schedule};
+use crate::repositories;
+use crate::state::AppState;
+use chrono::{NaiveDateTime, Utc};
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScheduledTask {
+    ScanLibrary,
+    RefreshArtwork,
+    RefreshMetadata,
+    RefreshEpisodes,
+    CleanCache,
+    GenerateBlurHashes,
+}
+
+impl FromStr for ScheduledTask {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "scan_library" => Ok(Self::ScanLibrary),
+            "refresh_artwork" => Ok(Self::RefreshArtwork),
+            "refresh_metadata" => Ok(Self::RefreshMetadata),
+            "refresh_episodes" => Ok(Self::RefreshEpisodes),
+            "clean_cache" => Ok(Self::CleanCache),
+            "generate_blur_hashes" => Ok(Self::GenerateBlurHashes),
+            _ => Err(format!("Invalid scheduled task: {}", s)),
+        }
+    }
+}
+
+impl ScheduledTask {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ScanLibrary => "scan_library",
+            Self::RefreshArtwork => "refresh_artwork",
+            Self::RefreshMetadata => "refresh_metadata",
+            Self::RefreshEpisodes => "refresh_episodes",
+            Self::CleanCache => "clean_cache",
+            Self::GenerateBlurHashes => "generate_blur_hashes",
+        }
+    }
+
+    fn job(
+        &self,
+        state: AppState,
+        library_id: Option<i64>,
+    ) -> Result<Box<dyn Job + Send + Sync>, anyhow::Error> {
+        let library_id = || {
+            library_id.ok_or(anyhow::Error::msg(format!(
+                "Scheduled task {} requires a library",
+                self.as_str()
+            )))
+        };
+
+        Ok(match self {
+            Self::ScanLibrary => Box::new(ScanLibrary::new(
+                state,
+                ScanLibraryPayload::new(library_id()?),
+            )),
+            Self::RefreshArtwork => Box::new(RefreshArtwork::new(
+                state,
+                RefreshArtworkPayload::new(library_id()?),
+            )),
+            Self::RefreshMetadata => Box::new(RefreshMetadata::new(
+                state,
+                RefreshMetadataPayload::new(library_id()?),
+            )),
+            Self::RefreshEpisodes => Box::new(RefreshEpisodes::new(
+                state,
+                RefreshEpisodesPayload::new(library_id()?),
+            )),
+            Self::CleanCache => Box::new(CleanCache::new(state)),
+            Self::GenerateBlurHashes => Box::new(GenerateBlurHashes::new(state)),
+        })
+    }
+}
+
+/// Parses a cron expression (`sec min hour day-of-month month day-of-week [year]`) and returns
+/// the first time it fires after `after`.
+pub fn next_run_after(
+    cron: &str,
+    after: NaiveDateTime,
+) -> Result<Option<NaiveDateTime>, anyhow::Error> {
+    let schedule = cron::Schedule::from_str(cron)
+        .map_err(|e| anyhow::Error::msg(format!("Invalid cron expression {cron}: {e}")))?;
+
+    Ok(schedule
+        .after(&after.and_utc())
+        .next()
+        .map(|next| next.naive_utc()))
+}
+
+pub fn default_library_schedules(library: &Library, created_by: &str) -> Vec<InsertableSchedule> {
+    [
+        (ScheduledTask::ScanLibrary, "0 0 3 * * *"),
+        (ScheduledTask::RefreshArtwork, "0 0 4 * * Sun"),
+        (ScheduledTask::RefreshEpisodes, "0 30 4 * * *"),
+        (ScheduledTask::RefreshMetadata, "0 0 5 * * *"),
+    ]
+    .into_iter()
+    .map(|(task, cron)| InsertableSchedule {
+        created_by: created_by.to_string(),
+        updated_by: created_by.to_string(),
+        task: task.as_str().to_string(),
+        library_id: Some(library.id),
+        cron: cron.to_string(),
+        next_run_at: next_run_after(cron, Utc::now().naive_utc()).unwrap_or_default(),
+    })
+    .collect()
+}
+
+/// Queues the job behind a schedule and records the run, regardless of when it was due.
+pub async fn trigger(state: &AppState, mut schedule: Schedule) -> Result<Schedule, anyhow::Error> {
+    let task = schedule
+        .task
+        .parse::<ScheduledTask>()
+        .map_err(anyhow::Error::msg)?;
+
+    info!("Running scheduled task {} ({})", task.as_str(), schedule.id);
+    state
+        .queue
+        .send(task.job(state.clone(), schedule.library_id)?)?;
+
+    let now = Utc::now().naive_utc();
+    schedule.last_run_at = Some(now);
+    schedule.next_run_at = next_run_after(&schedule.cron, now)?;
+
+    let mut connection = state.pool.get().await?;
+    Ok(repositories::schedule::update(&mut connection, &schedule).await?)
+}
+
+async fn tick(state: &AppState) -> Result<(), anyhow::Error> {
+    let schedules = {
+        let mut connection = state.pool.get().await?;
+        repositories::schedule::find_all_enabled(&mut connection).await?
+    };
+
+    let now = Utc::now().naive_utc();
+    for schedule in schedules {
+        let id = schedule.id;
+        if let Err(e) = tick_schedule(state, schedule, now).await {
+            error!("Failed to run schedule {}: {}", id, e);
+        }
+    }
+
+    Ok(())
+}
+
+async fn tick_schedule(
+    state: &AppState,
+    mut schedule: Schedule,
+    now: NaiveDateTime,
+) -> Result<(), anyhow::Error> {
+    match schedule.next_run_at {
+        Some(next_run_at) if next_run_at <= now => {
+            trigger(state, schedule).await?;
+        }
+        Some(_) => {}
+        None => {
+            schedule.next_run_at = next_run_after(&schedule.cron, now)?;
+            if schedule.next_run_at.is_none() {
+                warn!(
+                    "Disabling schedule {}, {} has no future runs",
+                    schedule.id, schedule.cron
+                );
+                schedule.enabled = false;
+            }
+
+            let mut connection = state.pool.get().await?;
+            repositories::schedule::update(&mut connection, &schedule).await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn run(state: AppState) {
+    let mut interval = tokio::time::interval(TICK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = tick(&state).await {
+            error!("Failed to run scheduler: {}", e);
+        }
+    }
+}
@@ -1,5 +1,39 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    collection_items (id) {
+        id -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        collection_id -> Int8,
+        media_id -> Int8,
+        set_name -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    data_migrations (id) {
+        id -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        name -> Text,
+        completed_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    extras (id) {
+        id -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        media_id -> Int8,
+        #[max_length = 255]
+        kind -> Varchar,
+        title -> Text,
+        path -> Text,
+    }
+}
+
 diesel::table! {
     history (id) {
         id -> Int8,
@@ -21,6 +55,13 @@ diesel::table! {
         name -> Text,
         path -> Text,
         media_type -> Text,
+        ignore_patterns -> Array<Text>,
+        #[max_length = 255]
+        metadata_provider -> Varchar,
+        #[max_length = 255]
+        episode_ordering -> Varchar,
+        preferred_languages -> Array<Text>,
+        metadata_refresh_days -> Int4,
     }
 }
 
@@ -40,6 +81,78 @@ diesel::table! {
         attributes -> Jsonb,
         parent_id -> Nullable<Int8>,
         library_id -> Int8,
+        provider_ids -> Jsonb,
+        credits -> Jsonb,
+        tags -> Array<Text>,
+        collection -> Nullable<Jsonb>,
+        #[max_length = 255]
+        metadata_status -> Varchar,
+        locked_fields -> Array<Text>,
+        metadata_refreshed_at -> Nullable<Timestamp>,
+        translations -> Jsonb,
+    }
+}
+
+diesel::table! {
+    media_people (id) {
+        id -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        media_id -> Int8,
+        person_id -> Int8,
+        role -> Text,
+        character -> Nullable<Text>,
+        sort_order -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    people (id) {
+        id -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        name -> Text,
+        provider_ids -> Jsonb,
+        biography -> Nullable<Text>,
+        birthday -> Nullable<Date>,
+        deathday -> Nullable<Date>,
+        place_of_birth -> Nullable<Text>,
+        thumb -> Nullable<Text>,
+        headshot -> Nullable<Jsonb>,
+        metadata_refreshed_at -> Nullable<Timestamp>,
+        match_key -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    provider_episodes (id) {
+        id -> Int8,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        media_id -> Int8,
+        season -> Int4,
+        episode -> Int4,
+        title -> Nullable<Text>,
+        plot -> Nullable<Text>,
+        aired -> Nullable<Date>,
+        provider_ids -> Jsonb,
+    }
+}
+
+diesel::table! {
+    schedules (id) {
+        id -> Int8,
+        created_at -> Timestamp,
+        created_by -> Text,
+        updated_at -> Timestamp,
+        updated_by -> Text,
+        #[max_length = 255]
+        task -> Varchar,
+        library_id -> Nullable<Int8>,
+        cron -> Text,
+        enabled -> Bool,
+        last_run_at -> Nullable<Timestamp>,
+        next_run_at -> Nullable<Timestamp>,
     }
 }
 
@@ -57,16 +170,30 @@ diesel::table! {
         #[max_length = 255]
         name -> Varchar,
         is_admin -> Bool,
+        metadata_languages -> Array<Text>,
+        calendar_token -> Nullable<Text>,
     }
 }
 
+diesel::joinable!(extras -> media (media_id));
 diesel::joinable!(history -> media (media_id));
 diesel::joinable!(history -> users (user_id));
 diesel::joinable!(media -> libraries (library_id));
+diesel::joinable!(media_people -> media (media_id));
+diesel::joinable!(media_people -> people (person_id));
+diesel::joinable!(provider_episodes -> media (media_id));
+diesel::joinable!(schedules -> libraries (library_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    collection_items,
+    data_migrations,
+    extras,
     history,
     libraries,
     media,
+    media_people,
+    people,
+    provider_episodes,
+    schedules,
     users,
 );
@@ -1,9 +1,15 @@
+use crate::clients::http::HttpClient;
 use crate::factories::artwork_fetcher::ArtworkFetcherFactory;
 use crate::factories::library_scanner::ScannerFactory;
+use crate::factories::metadata_provider::MetadataProviderFactory;
+use crate::ffmpeg::Ffmpeg;
+use crate::images::ImageCache;
 use crate::jobs::Job;
+use crate::metadata_store::MetadataStore;
 use deadpool::managed::Pool;
 use diesel_async::pooled_connection::AsyncDieselConnectionManager;
 use diesel_async::AsyncPgConnection;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::mpsc::UnboundedSender;
 
@@ -16,4 +22,10 @@ pub struct AppState {
     pub queue: UnboundedSender<Box<dyn Job + Send + Sync>>,
     pub artwork_fetcher_factory: Arc<ArtworkFetcherFactory>,
     pub scanner_factory: Arc<ScannerFactory>,
+    pub metadata_provider_factory: Arc<MetadataProviderFactory>,
+    pub http_client: Arc<HttpClient>,
+    pub image_cache: Arc<ImageCache>,
+    pub metadata_store: Arc<MetadataStore>,
+    pub ffmpeg: Arc<Ffmpeg>,
+    pub cache_dir: PathBuf,
 }
@@ -0,0 +1,119 @@
+use crate::errors::{Problem, ProblemType};
+use axum::body::Body;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use std::io::SeekFrom;
+use std::path::Path;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+use tracing::error;
+
+pub async fn stream_file(
+    path: &Path,
+    headers: &HeaderMap,
+    instance: Option<String>,
+) -> Result<Response, Problem> {
+    let mut video_file = File::open(path).await.map_err(|e| {
+        error!("Error while opening video file: {}", e);
+        Problem::from(ProblemType::InternalServerError(instance.clone()))
+    })?;
+
+    let metadata = video_file.metadata().await.map_err(|e| {
+        error!("Error reading video files metadata: {}", e);
+        Problem::from(ProblemType::InternalServerError(instance.clone()))
+    })?;
+
+    let file_size = metadata.len();
+
+    let (start, end) = if let Some(range_header) = headers.get(header::RANGE) {
+        let range_str = range_header.to_str().unwrap_or("");
+        if !range_str.starts_with("bytes=") {
+            return Err(Problem {
+                r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/400"
+                    .to_string(),
+                title: "Invalid Range header".to_string(),
+                status: 400,
+                instance,
+                ..Default::default()
+            });
+        }
+
+        let mut parts = range_str.trim_start_matches("bytes=").split('-');
+        let start = parts
+            .next()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(0);
+        let end = parts
+            .next()
+            .and_then(|e| e.parse::<u64>().ok())
+            .unwrap_or(file_size - 1)
+            .min(file_size - 1);
+        if start > end {
+            return Err(Problem {
+                r#type: "https://developer.mozilla.org/en-US/docs/Web/HTTP/Reference/Status/416"
+                    .to_string(),
+                title: "Range not satisfiable".to_string(),
+                status: 416,
+                instance,
+                ..Default::default()
+            });
+        }
+
+        (start, end)
+    } else {
+        (0, file_size - 1)
+    };
+
+    if start > 0
+        && let Err(e) = video_file.seek(SeekFrom::Start(start)).await
+    {
+        error!("Error seeking video file: {}", e);
+        return Err(ProblemType::InternalServerError(instance).into());
+    }
+
+    let content_length = end - start + 1;
+
+    let content_type = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("mp4") => "video/mp4",
+        Some("webm") => "video/webm",
+        Some("avi") => "video/x-msvideo",
+        Some("mov") => "video/quicktime",
+        Some("mkv") => "video/x-matroska",
+        Some("mp3") => "audio/mpeg",
+        Some("flac") => "audio/flac",
+        Some("ogg") => "audio/ogg",
+        Some("opus") => "audio/opus",
+        Some("m4a") => "audio/mp4",
+        Some("aac") => "audio/aac",
+        Some("wav") => "audio/wav",
+        _ => "application/octet-stream",
+    };
+
+    let limited_reader = video_file.take(content_length);
+    let stream = ReaderStream::with_capacity(limited_reader, 65536);
+
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert(header::CONTENT_TYPE, content_type.parse().unwrap());
+    response_headers.insert(
+        header::CONTENT_LENGTH,
+        content_length.to_string().parse().unwrap(),
+    );
+    response_headers.insert(header::ACCEPT_RANGES, "bytes".parse().unwrap());
+
+    if headers.contains_key(header::RANGE) {
+        response_headers.insert(
+            header::CONTENT_RANGE,
+            format!("bytes {start}-{end}/{file_size}").parse().unwrap(),
+        );
+
+        return Ok((
+            StatusCode::PARTIAL_CONTENT,
+            response_headers,
+            Body::from_stream(stream),
+        )
+            .into_response());
+    }
+
+    Ok((StatusCode::OK, response_headers, Body::from_stream(stream)).into_response())
+}
@@ -1,5 +1,11 @@
-use crate::models::Media;
+use crate::factories::metadata_provider::{RemoteImage, SearchResult};
+use crate::models::{
+    Credit, Extra, Media, MediaPerson, MediaSet, MetadataStatus, Person, ProviderEpisode,
+    ProviderIds, Schedule, User,
+};
 use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
 
 #[derive(Debug, Default, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -14,6 +20,14 @@ pub struct MediaView {
     pub episode: Option<i32>,
     pub attributes: serde_json::Value,
     pub parent_id: Option<String>,
+    pub provider_ids: ProviderIds,
+    pub credits: Vec<Credit>,
+    pub tags: Vec<String>,
+    pub collection: Option<MediaSet>,
+    pub metadata_status: String,
+    pub locked_fields: Vec<String>,
+    pub blur_hashes: BTreeMap<String, String>,
+    pub missing: bool,
 }
 
 impl From<Media> for MediaView {
@@ -29,6 +43,364 @@ impl From<Media> for MediaView {
             episode: value.episode,
             attributes: value.attributes,
             parent_id: value.parent_id.map(|id| id.to_string()),
+            provider_ids: value.provider_ids.0,
+            credits: value.credits.0,
+            tags: value.tags,
+            collection: value.collection.map(|collection| collection.0),
+            metadata_status: value.metadata_status,
+            locked_fields: value.locked_fields,
+            blur_hashes: value
+                .files
+                .iter()
+                .fold(BTreeMap::new(), |mut blur_hashes, file| {
+                    if let Some(blur_hash) = &file.blur_hash {
+                        blur_hashes
+                            .entry(file.type_.as_str().to_string())
+                            .or_insert(blur_hash.clone());
+                    }
+                    blur_hashes
+                }),
+            missing: false,
+        }
+    }
+}
+
+impl MediaView {
+    pub fn localized(value: Media, languages: &[String]) -> Self {
+        let translation = value.translated(languages);
+        let has_sort_title = value
+            .attributes
+            .get("sortTitle")
+            .is_some_and(|title| title.is_string());
+
+        let mut view = Self::from(value);
+        if let Some(attributes) = view.attributes.as_object_mut() {
+            for (key, text) in translation.attributes() {
+                if let Some(text) = text {
+                    attributes.insert(key.to_string(), json!(text));
+                }
+            }
+        }
+        if let Some(title) = translation.title.filter(|_| !has_sort_title) {
+            view.title = title;
+        }
+
+        view
+    }
+
+    /// Carries the id of the provider's episode, there is no media item behind it.
+    pub fn missing(value: ProviderEpisode, season: &Media) -> Self {
+        Self {
+            id: value.id.to_string(),
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+            type_: season.type_.clone(),
+            library_id: season.library_id.to_string(),
+            title: value
+                .title
+                .clone()
+                .unwrap_or(format!("Episode {}", value.episode)),
+            season: Some(value.season),
+            episode: Some(value.episode),
+            attributes: json!({
+                "title": value.title,
+                "plot": value.plot,
+                "aired": value.aired,
+            }),
+            parent_id: Some(season.id.to_string()),
+            provider_ids: value.provider_ids.0,
+            metadata_status: MetadataStatus::Complete.as_str().to_string(),
+            missing: true,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleView {
+    pub id: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub created_by: String,
+    pub updated_at: chrono::NaiveDateTime,
+    pub updated_by: String,
+    pub task: String,
+    pub library_id: Option<String>,
+    pub cron: String,
+    pub enabled: bool,
+    pub last_run_at: Option<chrono::NaiveDateTime>,
+    pub next_run_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<Schedule> for ScheduleView {
+    fn from(value: Schedule) -> Self {
+        Self {
+            id: value.id.to_string(),
+            created_at: value.created_at,
+            created_by: value.created_by,
+            updated_at: value.updated_at,
+            updated_by: value.updated_by,
+            task: value.task,
+            library_id: value.library_id.map(|id| id.to_string()),
+            cron: value.cron,
+            enabled: value.enabled,
+            last_run_at: value.last_run_at,
+            next_run_at: value.next_run_at,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserView {
+    pub id: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub created_by: String,
+    pub updated_at: chrono::NaiveDateTime,
+    pub updated_by: String,
+    pub email: String,
+    pub name: String,
+    pub is_admin: bool,
+    pub metadata_languages: Vec<String>,
+    pub has_calendar: bool,
+}
+
+impl From<User> for UserView {
+    fn from(value: User) -> Self {
+        Self {
+            id: value.id.to_string(),
+            created_at: value.created_at,
+            created_by: value.created_by,
+            updated_at: value.updated_at,
+            updated_by: value.updated_by,
+            email: value.email,
+            name: value.name,
+            is_admin: value.is_admin,
+            metadata_languages: value.metadata_languages,
+            has_calendar: value.calendar_token.is_some(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtraView {
+    pub id: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+    pub media_id: String,
+    pub kind: String,
+    pub title: String,
+}
+
+impl From<Extra> for ExtraView {
+    fn from(value: Extra) -> Self {
+        Self {
+            id: value.id.to_string(),
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+            media_id: value.media_id.to_string(),
+            kind: value.kind,
+            title: value.title,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrailerView {
+    pub extra_id: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtworkCandidateView {
+    pub source: String,
+    pub url: String,
+    pub language: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub score: f64,
+}
+
+impl From<(String, RemoteImage)> for ArtworkCandidateView {
+    fn from((source, image): (String, RemoteImage)) -> Self {
+        Self {
+            source,
+            url: image.url,
+            language: image.language.filter(|language| !language.is_empty()),
+            width: image.width,
+            height: image.height,
+            score: image.score,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResultView {
+    pub provider: String,
+    pub id: String,
+    pub title: String,
+    pub original_title: Option<String>,
+    pub year: Option<i32>,
+    pub overview: Option<String>,
+    pub poster_url: Option<String>,
+    pub score: f64,
+}
+
+impl From<(String, SearchResult)> for SearchResultView {
+    fn from((provider, result): (String, SearchResult)) -> Self {
+        Self {
+            provider,
+            id: result.id,
+            title: result.title,
+            original_title: result.original_title,
+            year: result.year,
+            overview: result.overview,
+            poster_url: result.poster_url,
+            score: result.score,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonView {
+    pub id: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+    pub name: String,
+    pub provider_ids: ProviderIds,
+    pub biography: Option<String>,
+    pub birthday: Option<chrono::NaiveDate>,
+    pub deathday: Option<chrono::NaiveDate>,
+    pub place_of_birth: Option<String>,
+    pub has_headshot: bool,
+    pub blur_hash: Option<String>,
+}
+
+impl From<Person> for PersonView {
+    fn from(value: Person) -> Self {
+        Self {
+            id: value.id.to_string(),
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+            name: value.name,
+            provider_ids: value.provider_ids.0,
+            biography: value.biography,
+            birthday: value.birthday,
+            deathday: value.deathday,
+            place_of_birth: value.place_of_birth,
+            has_headshot: value.headshot.is_some(),
+            blur_hash: value.headshot.and_then(|headshot| headshot.0.blur_hash),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CastView {
+    pub role: String,
+    pub character: Option<String>,
+    pub order: Option<i32>,
+    pub person: PersonView,
+}
+
+impl From<(MediaPerson, Person)> for CastView {
+    fn from((role, person): (MediaPerson, Person)) -> Self {
+        Self {
+            role: role.role,
+            character: role.character,
+            order: role.sort_order,
+            person: PersonView::from(person),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleView {
+    pub role: String,
+    pub character: Option<String>,
+}
+
+impl From<MediaPerson> for RoleView {
+    fn from(value: MediaPerson) -> Self {
+        Self {
+            role: value.role,
+            character: value.character,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilmographyView {
+    pub roles: Vec<RoleView>,
+    pub media: MediaView,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingEpisodeView {
+    pub id: String,
+    pub season: i32,
+    pub episode: i32,
+    pub title: Option<String>,
+    pub plot: Option<String>,
+    pub aired: Option<chrono::NaiveDate>,
+    pub provider_ids: ProviderIds,
+}
+
+impl From<ProviderEpisode> for MissingEpisodeView {
+    fn from(value: ProviderEpisode) -> Self {
+        Self {
+            id: value.id.to_string(),
+            season: value.season,
+            episode: value.episode,
+            title: value.title,
+            plot: value.plot,
+            aired: value.aired,
+            provider_ids: value.provider_ids.0,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingEpisodesView {
+    pub show: MediaView,
+    pub episodes: Vec<MissingEpisodeView>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarEpisodeView {
+    pub id: String,
+    pub show_id: String,
+    pub show_title: String,
+    pub season: i32,
+    pub episode: i32,
+    pub title: Option<String>,
+    pub plot: Option<String>,
+    pub aired: Option<chrono::NaiveDate>,
+    pub provider_ids: ProviderIds,
+}
+
+impl From<(ProviderEpisode, String)> for CalendarEpisodeView {
+    fn from((episode, show_title): (ProviderEpisode, String)) -> Self {
+        Self {
+            id: episode.id.to_string(),
+            show_id: episode.media_id.to_string(),
+            show_title,
+            season: episode.season,
+            episode: episode.episode,
+            title: episode.title,
+            plot: episode.plot,
+            aired: episode.aired,
+            provider_ids: episode.provider_ids.0,
         }
     }
 }